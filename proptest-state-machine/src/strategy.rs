@@ -121,7 +121,10 @@ pub trait ReferenceStateMachine {
 /// 1. We start by trying to delete transitions from the back of the list, until
 ///    we can do so no further (the list has reached the `min_size`).
 ///    We start from the back, because it's less likely to affect the state
-///    machine's pre-conditions, if any.
+///    machine's pre-conditions, if any. Transitions are first deleted in
+///    chunks of half of the list, then a quarter and so on, before each of the
+///    remaining transitions is deleted on its own, so that long sequences can
+///    be shrunk quickly.
 /// 2. Then, we again iteratively attempt to shrink the individual transitions,
 ///    but this time starting from the front of the list - i.e. from the first
 ///    transition to be applied.
@@ -213,8 +216,9 @@ impl<
             max_ix,
             // On a failure, we start by shrinking transitions from the back
             // which is less likely to invalidate pre-conditions
-            shrink: Shrink::DeleteTransition(max_ix),
+            shrink: Shrink::delete_chunks(max_size / 2, max_size),
             last_shrink: None,
+            deleted_chunk: Vec::new(),
         })
     }
 }
@@ -224,6 +228,9 @@ impl<
 enum Shrink {
     /// Shrink the initial state
     InitialState,
+    /// Delete the transitions in the chunk of the given length which ends
+    /// just before the given index
+    DeleteChunk(usize, usize),
     /// Delete a transition at given index
     DeleteTransition(usize),
     /// Shrink a transition at given index
@@ -231,6 +238,19 @@ enum Shrink {
 }
 use Shrink::*;
 
+impl Shrink {
+    /// Start deleting chunks of `len` transitions from the back of a list of
+    /// `size` transitions, or single transitions once the chunks would be no
+    /// longer than that.
+    fn delete_chunks(len: usize, size: usize) -> Self {
+        if len > 1 {
+            DeleteChunk(size, len)
+        } else {
+            DeleteTransition(size - 1)
+        }
+    }
+}
+
 /// The state of a transition in the model
 #[derive(Clone, Copy, Debug)]
 enum TransitionState {
@@ -279,6 +299,8 @@ pub struct SequentialValueTree<
     shrink: Shrink,
     /// The last applied shrink operation, if any
     last_shrink: Option<Shrink>,
+    /// The transitions deleted by the last `Shrink::DeleteChunk`
+    deleted_chunk: Vec<usize>,
 }
 
 impl<
@@ -292,10 +314,59 @@ impl<
     /// Try to apply the next `self.shrink`. Returns `true` if a shrink has been
     /// applied.
     fn try_simplify(&mut self) -> bool {
-        if let DeleteTransition(ix) = self.shrink {
+        while let DeleteChunk(end, len) = self.shrink {
+            if end == 0 {
+                // Reached the beginning of the list, try smaller chunks
+                self.shrink = Shrink::delete_chunks(len / 2, self.max_ix + 1);
+                continue;
+            }
+            let start = end.saturating_sub(len);
+            // Try to delete the previous chunk next
+            self.shrink = DeleteChunk(start, len);
+
+            let included = (start..end)
+                .filter(|&ix| self.included_transitions.test(ix))
+                .count();
+            // Skip chunks that were already deleted or that would take the
+            // list under the `min_size`
+            if included == 0
+                || self.included_transitions.count() - included < self.min_size
+            {
+                continue;
+            }
+
+            self.deleted_chunk.clear();
+            for ix in start..end {
+                if self.included_transitions.test(ix) {
+                    self.included_transitions.clear(ix);
+                    self.deleted_chunk.push(ix);
+                }
+            }
+            // If this delete is not acceptable, undo it and try again
+            if !self.check_acceptable(None) {
+                for &ix in &self.deleted_chunk {
+                    self.included_transitions.set(ix);
+                }
+                continue;
+            }
+            for &ix in &self.deleted_chunk {
+                self.shrinkable_transitions.clear(ix);
+            }
+            self.last_shrink = Some(DeleteChunk(end, len));
+            return true;
+        }
+
+        while let DeleteTransition(ix) = self.shrink {
             if self.included_transitions.count() == self.min_size {
                 // Can't delete any more transitions, move on to shrinking them
                 self.shrink = Transition(0);
+            } else if !self.included_transitions.test(ix) {
+                // Already deleted along with its chunk
+                self.shrink = if ix == 0 {
+                    Transition(0)
+                } else {
+                    DeleteTransition(ix - 1)
+                };
             } else {
                 // Delete the index from the included transitions
                 self.included_transitions.clear(ix);
@@ -312,7 +383,7 @@ impl<
                 if !self.check_acceptable(None) {
                     self.included_transitions.set(ix);
                     self.last_shrink = None;
-                    return self.try_simplify();
+                    continue;
                 }
                 // If the delete was accepted, remove this index from shrinkable
                 // transitions
@@ -514,6 +585,15 @@ impl<
     fn complicate(&mut self) -> bool {
         match self.last_shrink {
             None => false,
+            Some(DeleteChunk(..)) => {
+                // Undo the last chunk we deleted, as for a single transition
+                for &ix in &self.deleted_chunk {
+                    self.included_transitions.set(ix);
+                    self.shrinkable_transitions.set(ix);
+                }
+                self.last_shrink = None;
+                true
+            }
             Some(DeleteTransition(ix)) => {
                 // Undo the last item we deleted. Can't complicate any further,
                 // so unset prev_shrink.
@@ -575,7 +655,7 @@ mod test {
     ///
     /// This constant can be determined from the test
     /// `number_of_sequential_value_tree_simplifications`.
-    const SIMPLIFICATIONS: usize = 699;
+    const SIMPLIFICATIONS: usize = 604;
     /// Number of transitions in the [`deterministic_sequential_value_tree`].
     const TRANSITIONS: usize = 32;
 
@@ -595,6 +675,39 @@ mod test {
         assert_eq!(i, SIMPLIFICATIONS);
     }
 
+    #[test]
+    fn sequential_value_tree_deletes_chunks() {
+        let sequential =
+            <HeapStateMachine as ReferenceStateMachine>::sequential_strategy(
+                0..1000,
+            );
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..16 {
+            let mut value_tree = sequential.new_tree(&mut runner).unwrap();
+            while value_tree.current().1.len() < 500 {
+                value_tree = sequential.new_tree(&mut runner).unwrap();
+            }
+            let fails =
+                |value_tree: &TestValueTree| value_tree.current().1.len() >= 3;
+            let mut steps = 0;
+
+            while value_tree.current().1.len() > 3 {
+                steps += 1;
+                if !value_tree.simplify() {
+                    break;
+                }
+                if !fails(&value_tree) {
+                    value_tree.complicate();
+                }
+            }
+
+            assert_eq!(3, value_tree.current().1.len());
+            // Deleting one transition at a time would take over 500 steps.
+            assert!(steps < 100, "took {} steps", steps);
+        }
+    }
+
     proptest! {
         /// Test the simplifications and complication of the
         /// `SequentialValueTree` produced by
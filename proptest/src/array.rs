@@ -128,6 +128,35 @@ impl<S: Strategy, const N: usize> Strategy for [S; N] {
             last_shrinker: None,
         })
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        if self.iter().all(|s| s.as_enumerable().is_some()) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+impl<S: Strategy, const N: usize> Enumerable for [S; N] {
+    fn domain_size(&self) -> Option<u64> {
+        product_size(self.iter().map(domain_size))
+    }
+
+    fn enumerate_tree(
+        &self,
+        mut index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        Ok(ArrayValueTree {
+            tree: unarray::build_array_result(|i| {
+                enumerate_part(&self[i], &mut index, runner)
+            })?,
+            shrinker: 0,
+            last_shrinker: None,
+        })
+    }
 }
 impl<S: Strategy, const N: usize> Strategy
     for UniformArrayStrategy<S, [S::Value; N]>
@@ -144,6 +173,33 @@ impl<S: Strategy, const N: usize> Strategy
             last_shrinker: None,
         })
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.strategy.as_enumerable().map(|_| self as _)
+    }
+}
+impl<S: Strategy, const N: usize> Enumerable
+    for UniformArrayStrategy<S, [S::Value; N]>
+{
+    fn domain_size(&self) -> Option<u64> {
+        product_size((0..N).map(|_| domain_size(&self.strategy)))
+    }
+
+    fn enumerate_tree(
+        &self,
+        mut index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        Ok(ArrayValueTree {
+            tree: unarray::build_array_result(|_| {
+                enumerate_part(&self.strategy, &mut index, runner)
+            })?,
+            shrinker: 0,
+            last_shrinker: None,
+        })
+    }
 }
 impl<T: ValueTree, const N: usize> ValueTree for ArrayValueTree<[T; N]> {
     type Value = [T::Value; N];
@@ -275,11 +275,8 @@ impl<T: BitSetLike> Strategy for SampledBitSetStrategy<T> {
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         let mut bits = T::new_bitset(self.bits.end_excl());
-        let count = sample_uniform_incl(
-            runner,
-            self.size.start(),
-            self.size.end_incl(),
-        );
+        let end = runner.scale_to_size(self.size.start(), self.size.end_incl());
+        let count = sample_uniform_incl(runner, self.size.start(), end);
         if bits.len() < count {
             panic!("not enough bits to sample");
         }
@@ -30,6 +30,30 @@ impl Strategy for Any {
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         Ok(BoolValueTree::new(runner.rng().gen()))
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        Some(self)
+    }
+
+    fn tree_from_value(
+        &self,
+        &value: &bool,
+        _: &mut TestRunner,
+    ) -> NewTree<Self> {
+        Ok(BoolValueTree::new(value))
+    }
+}
+
+impl Enumerable for Any {
+    fn domain_size(&self) -> Option<u64> {
+        Some(2)
+    }
+
+    fn enumerate_tree(&self, index: u64, _: &mut TestRunner) -> NewTree<Self> {
+        Ok(BoolValueTree::new(1 == index))
+    }
 }
 
 /// Generates boolean values by picking `true` with the given `probability`
@@ -52,6 +76,20 @@ impl Strategy for Weighted {
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         Ok(BoolValueTree::new(runner.rng().gen_bool(self.0)))
     }
+
+    fn tree_from_value(
+        &self,
+        &value: &bool,
+        _: &mut TestRunner,
+    ) -> NewTree<Self> {
+        // `gen_bool` never returns `true` for a probability of 0.0, nor
+        // `false` for 1.0.
+        if (value && self.0 > 0.0) || (!value && self.0 < 1.0) {
+            Ok(BoolValueTree::new(value))
+        } else {
+            Err("value is never generated with this probability".into())
+        }
+    }
 }
 
 /// The `ValueTree` to shrink booleans to false.
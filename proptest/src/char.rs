@@ -100,19 +100,21 @@ pub fn select_char(
     ::core::char::from_u32(base + offset).expect("bad character selected")
 }
 
+/// Returns the start of the first of `ranges` containing `ch`, and the offset
+/// of `ch` from it.
+fn in_range(ranges: &[CharRange], ch: char) -> Option<(u32, u32)> {
+    ranges
+        .iter()
+        .find(|r| ch >= *r.start() && ch <= *r.end())
+        .map(|r| (*r.start() as u32, ch as u32 - *r.start() as u32))
+}
+
 fn select_range_index(
     rnd: &mut impl Rng,
     special: &[char],
     preferred: &[CharRange],
     ranges: &[CharRange],
 ) -> (u32, u32) {
-    fn in_range(ranges: &[CharRange], ch: char) -> Option<(u32, u32)> {
-        ranges
-            .iter()
-            .find(|r| ch >= *r.start() && ch <= *r.end())
-            .map(|r| (*r.start() as u32, ch as u32 - *r.start() as u32))
-    }
-
     if !special.is_empty() && rnd.gen() {
         let s = special[rnd.gen_range(0..special.len())];
         if let Some(ret) = in_range(ranges, s) {
@@ -256,9 +258,26 @@ impl<'a> Strategy for CharStrategy<'a> {
             &self.preferred,
             &self.ranges,
         );
+        Ok(CharValueTree::new(base, base + offset))
+    }
+
+    fn tree_from_value(
+        &self,
+        &value: &char,
+        _: &mut TestRunner,
+    ) -> NewTree<Self> {
+        match in_range(&self.ranges, value) {
+            Some((base, offset)) => Ok(CharValueTree::new(base, base + offset)),
+            None => Err("value is outside the character ranges".into()),
+        }
+    }
+}
 
+impl CharValueTree {
+    /// Creates a value tree starting at `start`, in a range starting at
+    /// `base`.
+    fn new(base: u32, start: u32) -> Self {
         // Select a minimum point more convenient than 0
-        let start = base + offset;
         let bottom = if start >= '¡' as u32 && base < '¡' as u32 {
             '¡' as u32
         } else if start >= 'a' as u32 && base < 'a' as u32 {
@@ -273,13 +292,11 @@ impl<'a> Strategy for CharStrategy<'a> {
             base
         };
 
-        Ok(CharValueTree {
+        CharValueTree {
             value: num::u32::BinarySearch::new_above(bottom, start),
-        })
+        }
     }
-}
 
-impl CharValueTree {
     fn reposition(&mut self) {
         while ::core::char::from_u32(self.value.current()).is_none() {
             if !self.value.complicate() {
@@ -393,6 +410,18 @@ mod test {
         assert!(accepted >= 200);
     }
 
+    #[test]
+    fn tree_from_value_shrinks_within_range() {
+        let mut runner = TestRunner::deterministic();
+        let input = ranges(Cow::Owned(vec!['0'..='9', 'x'..='z']));
+        assert!(input.tree_from_value(&'a', &mut runner).is_err());
+
+        let mut value = input.tree_from_value(&'y', &mut runner).unwrap();
+        assert_eq!('y', value.current());
+        while value.simplify() {}
+        assert_eq!('x', value.current());
+    }
+
     #[test]
     fn test_sanity() {
         check_strategy_sanity(
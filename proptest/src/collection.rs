@@ -538,10 +538,25 @@ where
 
 #[derive(Clone, Copy, Debug)]
 enum Shrink {
+    /// Delete the elements in the chunk of the given length which starts at
+    /// the given index.
+    DeleteChunk(usize, usize),
     DeleteElement(usize),
     ShrinkElement(usize),
 }
 
+impl Shrink {
+    /// Start deleting chunks of `len` elements, or single elements once the
+    /// chunks would be no longer than that.
+    fn delete_chunks(len: usize) -> Self {
+        if len > 1 {
+            Shrink::DeleteChunk(0, len)
+        } else {
+            Shrink::DeleteElement(0)
+        }
+    }
+}
+
 /// `ValueTree` corresponding to `VecStrategy`.
 #[derive(Clone, Debug)]
 pub struct VecValueTree<T: ValueTree> {
@@ -550,6 +565,8 @@ pub struct VecValueTree<T: ValueTree> {
     min_size: usize,
     shrink: Shrink,
     prev_shrink: Option<Shrink>,
+    /// The elements deleted by the last `Shrink::DeleteChunk`.
+    deleted_chunk: Vec<usize>,
 }
 
 impl<T: Strategy> Strategy for VecStrategy<T> {
@@ -558,6 +575,7 @@ impl<T: Strategy> Strategy for VecStrategy<T> {
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         let (start, end) = self.size.start_end_incl();
+        let end = runner.scale_to_size(start, end);
         let max_size = sample_uniform_incl(runner, start, end);
         let mut elements = Vec::with_capacity(max_size);
         while elements.len() < max_size {
@@ -568,8 +586,33 @@ impl<T: Strategy> Strategy for VecStrategy<T> {
             elements,
             included_elements: VarBitSet::saturated(max_size),
             min_size: start,
-            shrink: Shrink::DeleteElement(0),
+            shrink: Shrink::delete_chunks(max_size / 2),
+            prev_shrink: None,
+            deleted_chunk: Vec::new(),
+        })
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Vec<T::Value>,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        let (start, end) = self.size.start_end_incl();
+        if value.len() < start || value.len() > end {
+            return Err("value's length is outside the size range".into());
+        }
+        let elements = value
+            .iter()
+            .map(|v| self.element.tree_from_value(v, runner))
+            .collect::<Result<Vec<_>, Reason>>()?;
+
+        Ok(VecValueTree {
+            elements,
+            included_elements: VarBitSet::saturated(value.len()),
+            min_size: start,
+            shrink: Shrink::delete_chunks(value.len() / 2),
             prev_shrink: None,
+            deleted_chunk: Vec::new(),
         })
     }
 }
@@ -591,6 +634,31 @@ impl<T: Strategy> Strategy for Vec<T> {
             min_size: len,
             shrink: Shrink::ShrinkElement(0),
             prev_shrink: None,
+            deleted_chunk: Vec::new(),
+        })
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Vec<T::Value>,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        if value.len() != self.len() {
+            return Err("value's length differs from the strategies'".into());
+        }
+        let elements = self
+            .iter()
+            .zip(value)
+            .map(|(t, v)| t.tree_from_value(v, runner))
+            .collect::<Result<Vec<_>, Reason>>()?;
+
+        Ok(VecValueTree {
+            elements,
+            included_elements: VarBitSet::saturated(value.len()),
+            min_size: value.len(),
+            shrink: Shrink::ShrinkElement(0),
+            prev_shrink: None,
+            deleted_chunk: Vec::new(),
         })
     }
 }
@@ -612,15 +680,54 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
         // list until we can do so no further, then to shrink each remaining
         // element in sequence.
         //
+        // Elements are first deleted in chunks, starting with each half of
+        // the list, then each quarter and so on, so that a long list which
+        // only needs a few of its elements to fail shrinks in a number of
+        // steps closer to the logarithm of its length than to the length
+        // itself. Then each remaining element is deleted on its own.
+        //
         // For `complicate()`, we simply undo the last shrink operation, if
         // there was any.
-        if let Shrink::DeleteElement(ix) = self.shrink {
+        while let Shrink::DeleteChunk(start, len) = self.shrink {
+            if start >= self.elements.len() {
+                self.shrink = Shrink::delete_chunks(len / 2);
+                continue;
+            }
+            self.shrink = Shrink::DeleteChunk(start + len, len);
+
+            let end = self.elements.len().min(start + len);
+            let included = (start..end)
+                .filter(|&ix| self.included_elements.test(ix))
+                .count();
+            // Skip chunks which were already deleted, or which would put us
+            // under the minimum length.
+            if 0 == included
+                || self.included_elements.count() - included < self.min_size
+            {
+                continue;
+            }
+
+            self.deleted_chunk.clear();
+            for ix in start..end {
+                if self.included_elements.test(ix) {
+                    self.included_elements.clear(ix);
+                    self.deleted_chunk.push(ix);
+                }
+            }
+            self.prev_shrink = Some(Shrink::DeleteChunk(start, len));
+            return true;
+        }
+
+        while let Shrink::DeleteElement(ix) = self.shrink {
             // Can't delete an element if beyond the end of the vec or if it
             // would put us under the minimum length.
             if ix >= self.elements.len()
                 || self.included_elements.count() == self.min_size
             {
                 self.shrink = Shrink::ShrinkElement(0);
+            } else if !self.included_elements.test(ix) {
+                // Already deleted along with its chunk.
+                self.shrink = Shrink::DeleteElement(ix + 1);
             } else {
                 self.included_elements.clear(ix);
                 self.prev_shrink = Some(self.shrink);
@@ -656,6 +763,14 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
     fn complicate(&mut self) -> bool {
         match self.prev_shrink {
             None => false,
+            Some(Shrink::DeleteChunk(..)) => {
+                // Undo the last chunk we deleted, as for a single element.
+                for &ix in &self.deleted_chunk {
+                    self.included_elements.set(ix);
+                }
+                self.prev_shrink = None;
+                true
+            }
             Some(Shrink::DeleteElement(ix)) => {
                 // Undo the last item we deleted. Can't complicate any further,
                 // so unset prev_shrink.
@@ -736,6 +851,60 @@ mod test {
         check_strategy_sanity(vec(0i32..1000, 5..10), None);
     }
 
+    #[test]
+    fn test_vec_deletes_chunks() {
+        let input = vec(0u32..10, 0..5000);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..16 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            while case.current().len() < 1000 {
+                case = input.new_tree(&mut runner).unwrap();
+            }
+            let fails = |v: &Vec<u32>| v.len() >= 3;
+            let mut steps = 0;
+
+            if case.simplify() {
+                loop {
+                    steps += 1;
+                    if fails(&case.current()) {
+                        if !case.simplify() {
+                            break;
+                        }
+                    } else if !case.complicate() {
+                        break;
+                    }
+                }
+            }
+
+            assert_eq!(vec![0, 0, 0], case.current());
+            // Deleting one element at a time would take over 1000 steps.
+            assert!(steps < 100, "took {} steps", steps);
+        }
+    }
+
+    #[test]
+    fn test_vec_tree_from_value() {
+        let input = vec(0u32..100, 1..5);
+        let mut runner = TestRunner::deterministic();
+        assert!(input.tree_from_value(&vec![], &mut runner).is_err());
+        assert!(input.tree_from_value(&vec![1; 5], &mut runner).is_err());
+        assert!(input.tree_from_value(&vec![1, 100], &mut runner).is_err());
+
+        let case = input
+            .tree_from_value(&vec![7, 42, 99], &mut runner)
+            .unwrap();
+        assert_eq!(vec![7, 42, 99], case.current());
+        let result = runner.run_one(case, |v| {
+            prop_assert!(v.iter().all(|&e| e < 40));
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, minimal)) => assert_eq!(vec![40], minimal),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
     #[test]
     fn test_parallel_vec() {
         let input =
@@ -71,6 +71,27 @@ macro_rules! opaque_strategy_wrapper {
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
                 self.0.new_tree(runner).map($vtname)
             }
+
+            fn as_enumerable(&self) -> Option<
+                &dyn $crate::strategy::Enumerable<
+                    Tree = Self::Tree, Value = Self::Value>>
+            {
+                self.0.as_enumerable().map(|_| self as _)
+            }
+        }
+
+        $(#[$allmeta])*
+        impl $($sgen)* $crate::strategy::Enumerable
+        for $stratname $($sgen)* $($swhere)* {
+            fn domain_size(&self) -> Option<u64> {
+                $crate::strategy::domain_size(&self.0)
+            }
+
+            fn enumerate_tree(&self, index: u64, runner: &mut TestRunner)
+                              -> NewTree<Self> {
+                $crate::strategy::enumerate_tree(&self.0, index, runner)
+                    .map($vtname)
+            }
         }
 
         $(#[$allmeta])*
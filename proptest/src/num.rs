@@ -10,7 +10,8 @@
 //! Strategies to generate numeric values (as opposed to integers used as bit
 //! fields).
 //!
-//! All strategies in this module shrink by binary searching towards 0.
+//! All strategies in this module shrink by binary searching towards 0, or
+//! towards the target given to `Strategy::prop_shrink_towards`.
 
 mod float_samplers;
 
@@ -45,7 +46,8 @@ macro_rules! int_any {
         #[must_use = "strategies do nothing unless used"]
         pub struct Any(());
         /// Generates integers with completely arbitrary values, uniformly
-        /// distributed over the whole range.
+        /// distributed over the whole range, except that some values are
+        /// picked from its edges as controlled by `Config::edge_case_percent`.
         pub const ANY: Any = Any(());
 
         impl Strategy for Any {
@@ -53,33 +55,212 @@ macro_rules! int_any {
             type Value = $typ;
 
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-                Ok(BinarySearch::new(runner.rng().gen()))
+                let value = sample_edge(runner, $typ::MIN, $typ::MAX)
+                    .unwrap_or_else(|| runner.rng().gen());
+                Ok(BinarySearch::new(value))
+            }
+
+            fn as_enumerable(
+                &self,
+            ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>>
+            {
+                Some(self)
+            }
+
+            fn tree_from_value(
+                &self,
+                &value: &$typ,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                Ok(BinarySearch::new(value))
+            }
+        }
+
+        impl Enumerable for Any {
+            fn domain_size(&self) -> Option<u64> {
+                enumerable_size($typ::MIN, $typ::MAX)
+            }
+
+            fn enumerate_tree(
+                &self,
+                index: u64,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                Ok(BinarySearch::new(enumerated_value($typ::MIN, index)))
             }
         }
     };
 }
 
+macro_rules! int_edge {
+    ($typ: ident) => {
+        /// Pick one of the edge values of the inclusive range `[start, end]`
+        /// with the probability given by `Config::edge_case_percent`, or
+        /// return `None` if the range is to be sampled uniformly instead.
+        fn sample_edge(
+            runner: &mut TestRunner,
+            start: $typ,
+            end: $typ,
+        ) -> Option<$typ> {
+            let percent = runner.config().edge_case_percent.min(100);
+            if 0 == percent
+                || start > end
+                || !runner.rng().gen_ratio(percent, 100)
+            {
+                return None;
+            }
+
+            let mut edges: [$typ; 9 + 2 * $typ::BITS as usize] =
+                [0; 9 + 2 * $typ::BITS as usize];
+            let mut len = 0;
+            let mut push = |value: Option<$typ>| {
+                if let Some(value) = value {
+                    if start <= value && value <= end {
+                        edges[len] = value;
+                        len += 1;
+                    }
+                }
+            };
+
+            push(Some(start));
+            push(start.checked_add(1));
+            push(end.checked_sub(1));
+            push(Some(end));
+            push(Some(0));
+            push(Some(1));
+            push((0 as $typ).checked_sub(1));
+            push(Some($typ::MIN));
+            push(Some($typ::MAX));
+            for bit in 0..$typ::BITS {
+                let power = (1 as $typ) << bit;
+                push(Some(power));
+                push(power.checked_neg());
+            }
+
+            let index = runner.rng().gen_range(0..len);
+            Some(edges[index])
+        }
+
+        /// Returns the number of values in the inclusive range
+        /// `[start, end]`, which must not be empty, or `None` if that does
+        /// not fit in a `u64`.
+        fn enumerable_size(start: $typ, end: $typ) -> Option<u64> {
+            // The difference is taken modulo 2^BITS so that it is also
+            // correct for signed ranges which span more than `$typ::MAX`.
+            let offset = (end.wrapping_sub(start) as u128)
+                & (u128::MAX >> (128 - $typ::BITS));
+            offset
+                .checked_add(1)
+                .and_then(|size| ::core::convert::TryFrom::try_from(size).ok())
+        }
+
+        /// Returns the value at `index` in a range starting at `start`.
+        fn enumerated_value(start: $typ, index: u64) -> $typ {
+            start.wrapping_add(index as $typ)
+        }
+    };
+}
+
 macro_rules! numeric_api {
     ($typ:ident, $epsilon:expr) => {
-        numeric_api!($typ, $typ, $epsilon);
+        numeric_api!(@strategies $typ, $typ, $epsilon, {
+            fn as_enumerable(
+                &self,
+            ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>>
+            {
+                Some(self)
+            }
+        });
+
+        impl Enumerable for ::core::ops::Range<$typ> {
+            fn domain_size(&self) -> Option<u64> {
+                if self.start < self.end {
+                    enumerable_size(self.start, self.end - $epsilon)
+                } else {
+                    Some(0)
+                }
+            }
+
+            fn enumerate_tree(
+                &self,
+                index: u64,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                let value = enumerated_value(self.start, index);
+                Ok(BinarySearch::new_clamped(
+                    self.start,
+                    value,
+                    self.end - $epsilon,
+                ))
+            }
+        }
+
+        impl Enumerable for ::core::ops::RangeInclusive<$typ> {
+            fn domain_size(&self) -> Option<u64> {
+                if self.start() <= self.end() {
+                    enumerable_size(*self.start(), *self.end())
+                } else {
+                    Some(0)
+                }
+            }
+
+            fn enumerate_tree(
+                &self,
+                index: u64,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                let value = enumerated_value(*self.start(), index);
+                Ok(BinarySearch::new_clamped(*self.start(), value, *self.end()))
+            }
+        }
     };
     ($typ:ident, $sample_typ:ty, $epsilon:expr) => {
+        numeric_api!(@strategies $typ, $sample_typ, $epsilon, {});
+    };
+    (@strategies $typ:ident, $sample_typ:ty, $epsilon:expr,
+     { $($enumerable:tt)* }) => {
         impl Strategy for ::core::ops::Range<$typ> {
             type Tree = BinarySearch;
             type Value = $typ;
 
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-                Ok(BinarySearch::new_clamped(
-                    self.start,
+                let edge = if self.start < self.end {
+                    sample_edge(runner, self.start, self.end - $epsilon)
+                } else {
+                    None
+                };
+                let value = edge.unwrap_or_else(|| {
                     $crate::num::sample_uniform::<$sample_typ>(
                         runner,
                         self.start.into(),
                         self.end.into(),
                     )
-                    .into(),
+                    .into()
+                });
+                Ok(BinarySearch::new_clamped(
+                    self.start,
+                    value,
                     self.end - $epsilon,
                 ))
             }
+
+            $($enumerable)*
+
+            fn tree_from_value(
+                &self,
+                &value: &$typ,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                if self.contains(&value) {
+                    Ok(BinarySearch::new_clamped(
+                        self.start,
+                        value,
+                        self.end - $epsilon,
+                    ))
+                } else {
+                    Err("value is outside the range".into())
+                }
+            }
         }
 
         impl Strategy for ::core::ops::RangeInclusive<$typ> {
@@ -87,16 +268,34 @@ macro_rules! numeric_api {
             type Value = $typ;
 
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-                Ok(BinarySearch::new_clamped(
-                    *self.start(),
-                    $crate::num::sample_uniform_incl::<$sample_typ>(
-                        runner,
-                        (*self.start()).into(),
-                        (*self.end()).into(),
-                    )
-                    .into(),
-                    *self.end(),
-                ))
+                let value = sample_edge(runner, *self.start(), *self.end())
+                    .unwrap_or_else(|| {
+                        $crate::num::sample_uniform_incl::<$sample_typ>(
+                            runner,
+                            (*self.start()).into(),
+                            (*self.end()).into(),
+                        )
+                        .into()
+                    });
+                Ok(BinarySearch::new_clamped(*self.start(), value, *self.end()))
+            }
+
+            $($enumerable)*
+
+            fn tree_from_value(
+                &self,
+                &value: &$typ,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                if self.contains(&value) {
+                    Ok(BinarySearch::new_clamped(
+                        *self.start(),
+                        value,
+                        *self.end(),
+                    ))
+                } else {
+                    Err("value is outside the range".into())
+                }
             }
         }
 
@@ -105,16 +304,28 @@ macro_rules! numeric_api {
             type Value = $typ;
 
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-                Ok(BinarySearch::new_clamped(
-                    self.start,
-                    $crate::num::sample_uniform_incl::<$sample_typ>(
-                        runner,
-                        self.start.into(),
-                        ::core::$typ::MAX.into(),
-                    )
-                    .into(),
-                    ::core::$typ::MAX,
-                ))
+                let value = sample_edge(runner, self.start, $typ::MAX)
+                    .unwrap_or_else(|| {
+                        $crate::num::sample_uniform_incl::<$sample_typ>(
+                            runner,
+                            self.start.into(),
+                            $typ::MAX.into(),
+                        )
+                        .into()
+                    });
+                Ok(BinarySearch::new_clamped(self.start, value, $typ::MAX))
+            }
+
+            fn tree_from_value(
+                &self,
+                &value: &$typ,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                if self.contains(&value) {
+                    Ok(BinarySearch::new_clamped(self.start, value, $typ::MAX))
+                } else {
+                    Err("value is outside the range".into())
+                }
             }
         }
 
@@ -123,16 +334,32 @@ macro_rules! numeric_api {
             type Value = $typ;
 
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-                Ok(BinarySearch::new_clamped(
-                    ::core::$typ::MIN,
+                let edge = if $typ::MIN < self.end {
+                    sample_edge(runner, $typ::MIN, self.end - $epsilon)
+                } else {
+                    None
+                };
+                let value = edge.unwrap_or_else(|| {
                     $crate::num::sample_uniform::<$sample_typ>(
                         runner,
-                        ::core::$typ::MIN.into(),
+                        $typ::MIN.into(),
                         self.end.into(),
                     )
-                    .into(),
-                    self.end,
-                ))
+                    .into()
+                });
+                Ok(BinarySearch::new_clamped($typ::MIN, value, self.end))
+            }
+
+            fn tree_from_value(
+                &self,
+                &value: &$typ,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                if self.contains(&value) {
+                    Ok(BinarySearch::new_clamped($typ::MIN, value, self.end))
+                } else {
+                    Err("value is outside the range".into())
+                }
             }
         }
 
@@ -141,23 +368,35 @@ macro_rules! numeric_api {
             type Value = $typ;
 
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-                Ok(BinarySearch::new_clamped(
-                    ::core::$typ::MIN,
-                    $crate::num::sample_uniform_incl::<$sample_typ>(
-                        runner,
-                        ::core::$typ::MIN.into(),
-                        self.end.into(),
-                    )
-                    .into(),
-                    self.end,
-                ))
+                let value = sample_edge(runner, $typ::MIN, self.end)
+                    .unwrap_or_else(|| {
+                        $crate::num::sample_uniform_incl::<$sample_typ>(
+                            runner,
+                            $typ::MIN.into(),
+                            self.end.into(),
+                        )
+                        .into()
+                    });
+                Ok(BinarySearch::new_clamped($typ::MIN, value, self.end))
+            }
+
+            fn tree_from_value(
+                &self,
+                &value: &$typ,
+                _: &mut TestRunner,
+            ) -> NewTree<Self> {
+                if self.contains(&value) {
+                    Ok(BinarySearch::new_clamped($typ::MIN, value, self.end))
+                } else {
+                    Err("value is outside the range".into())
+                }
             }
         }
     };
 }
 
 macro_rules! signed_integer_bin_search {
-    ($typ:ident) => {
+    ($typ:ident, $unsigned:ident) => {
         #[allow(missing_docs)]
         pub mod $typ {
             use rand::Rng;
@@ -166,14 +405,19 @@ macro_rules! signed_integer_bin_search {
             use crate::test_runner::TestRunner;
 
             int_any!($typ);
+            int_edge!($typ);
 
-            /// Shrinks an integer towards 0, using binary search to find
-            /// boundary points.
+            /// Shrinks an integer towards 0, or the target given to
+            /// `prop_shrink_towards`, using binary search to find boundary
+            /// points.
             #[derive(Clone, Copy, Debug)]
             pub struct BinarySearch {
                 lo: $typ,
                 curr: $typ,
                 hi: $typ,
+                /// The value `lo` started from, which `hi` approaches from
+                /// one side.
+                target: $typ,
             }
             impl BinarySearch {
                 /// Creates a new binary searcher starting at the given value.
@@ -182,6 +426,7 @@ macro_rules! signed_integer_bin_search {
                         lo: 0,
                         curr: start,
                         hi: start,
+                        target: 0,
                     }
                 }
 
@@ -191,22 +436,32 @@ macro_rules! signed_integer_bin_search {
                 fn new_clamped(lo: $typ, start: $typ, hi: $typ) -> Self {
                     use core::cmp::{max, min};
 
+                    let lo = if start < 0 {
+                        min(0, hi - 1)
+                    } else {
+                        max(0, lo)
+                    };
                     BinarySearch {
-                        lo: if start < 0 {
-                            min(0, hi - 1)
-                        } else {
-                            max(0, lo)
-                        },
+                        lo,
                         hi: start,
                         curr: start,
+                        target: lo,
                     }
                 }
 
                 fn reposition(&mut self) -> bool {
-                    // Won't ever overflow since lo starts at 0 and advances
-                    // towards hi.
-                    let interval = self.hi - self.lo;
-                    let new_mid = self.lo + interval / 2;
+                    // The distance is computed unsigned, since it can be
+                    // larger than `$typ::MAX` when `lo` and `hi` have
+                    // different signs.
+                    let new_mid = if self.hi >= self.lo {
+                        let interval =
+                            self.hi.wrapping_sub(self.lo) as $unsigned;
+                        self.lo.wrapping_add((interval / 2) as $typ)
+                    } else {
+                        let interval =
+                            self.lo.wrapping_sub(self.hi) as $unsigned;
+                        self.lo.wrapping_sub((interval / 2) as $typ)
+                    };
 
                     if new_mid == self.curr {
                         false
@@ -216,10 +471,12 @@ macro_rules! signed_integer_bin_search {
                     }
                 }
 
-                fn magnitude_greater(lhs: $typ, rhs: $typ) -> bool {
-                    if 0 == lhs {
+                /// Whether `lhs` is further than `rhs` from the target, when
+                /// both are on the same side of it.
+                fn magnitude_greater(&self, lhs: $typ, rhs: $typ) -> bool {
+                    if self.target == lhs {
                         false
-                    } else if lhs < 0 {
+                    } else if lhs < self.target {
                         lhs < rhs
                     } else {
                         lhs > rhs
@@ -234,7 +491,7 @@ macro_rules! signed_integer_bin_search {
                 }
 
                 fn simplify(&mut self) -> bool {
-                    if !BinarySearch::magnitude_greater(self.hi, self.lo) {
+                    if !self.magnitude_greater(self.hi, self.lo) {
                         return false;
                     }
 
@@ -243,16 +500,24 @@ macro_rules! signed_integer_bin_search {
                 }
 
                 fn complicate(&mut self) -> bool {
-                    if !BinarySearch::magnitude_greater(self.hi, self.lo) {
+                    if !self.magnitude_greater(self.hi, self.lo) {
                         return false;
                     }
 
-                    self.lo = self.curr + if self.hi < 0 { -1 } else { 1 };
+                    self.lo =
+                        self.curr + if self.hi < self.target { -1 } else { 1 };
 
                     self.reposition()
                 }
             }
 
+            impl ShrinkTarget for BinarySearch {
+                fn set_shrink_target(&mut self, target: $typ) {
+                    self.lo = target;
+                    self.target = target;
+                }
+            }
+
             numeric_api!($typ, 1);
         }
     };
@@ -268,14 +533,19 @@ macro_rules! unsigned_integer_bin_search {
             use crate::test_runner::TestRunner;
 
             int_any!($typ);
+            int_edge!($typ);
 
-            /// Shrinks an integer towards 0, using binary search to find
-            /// boundary points.
+            /// Shrinks an integer towards 0, or the target given to
+            /// `prop_shrink_towards`, using binary search to find boundary
+            /// points.
             #[derive(Clone, Copy, Debug)]
             pub struct BinarySearch {
                 lo: $typ,
                 curr: $typ,
                 hi: $typ,
+                /// The value `lo` started from, which `hi` approaches from
+                /// one side.
+                target: $typ,
             }
             impl BinarySearch {
                 /// Creates a new binary searcher starting at the given value.
@@ -284,6 +554,7 @@ macro_rules! unsigned_integer_bin_search {
                         lo: 0,
                         curr: start,
                         hi: start,
+                        target: 0,
                     }
                 }
 
@@ -294,6 +565,7 @@ macro_rules! unsigned_integer_bin_search {
                         lo: lo,
                         curr: start,
                         hi: start,
+                        target: lo,
                     }
                 }
 
@@ -304,8 +576,11 @@ macro_rules! unsigned_integer_bin_search {
                 }
 
                 fn reposition(&mut self) -> bool {
-                    let interval = self.hi - self.lo;
-                    let new_mid = self.lo + interval / 2;
+                    let new_mid = if self.hi >= self.lo {
+                        self.lo + (self.hi - self.lo) / 2
+                    } else {
+                        self.lo - (self.lo - self.hi) / 2
+                    };
 
                     if new_mid == self.curr {
                         false
@@ -314,6 +589,16 @@ macro_rules! unsigned_integer_bin_search {
                         true
                     }
                 }
+
+                /// Whether the search is over, because `lo` has reached `hi`
+                /// coming from the target.
+                fn done(&self) -> bool {
+                    if self.hi < self.target {
+                        self.hi >= self.lo
+                    } else {
+                        self.hi <= self.lo
+                    }
+                }
             }
             impl ValueTree for BinarySearch {
                 type Value = $typ;
@@ -323,7 +608,7 @@ macro_rules! unsigned_integer_bin_search {
                 }
 
                 fn simplify(&mut self) -> bool {
-                    if self.hi <= self.lo {
+                    if self.done() {
                         return false;
                     }
 
@@ -332,27 +617,38 @@ macro_rules! unsigned_integer_bin_search {
                 }
 
                 fn complicate(&mut self) -> bool {
-                    if self.hi <= self.lo {
+                    if self.done() {
                         return false;
                     }
 
-                    self.lo = self.curr + 1;
+                    self.lo = if self.hi < self.target {
+                        self.curr - 1
+                    } else {
+                        self.curr + 1
+                    };
                     self.reposition()
                 }
             }
 
+            impl ShrinkTarget for BinarySearch {
+                fn set_shrink_target(&mut self, target: $typ) {
+                    self.lo = target;
+                    self.target = target;
+                }
+            }
+
             numeric_api!($typ, 1);
         }
     };
 }
 
-signed_integer_bin_search!(i8);
-signed_integer_bin_search!(i16);
-signed_integer_bin_search!(i32);
-signed_integer_bin_search!(i64);
+signed_integer_bin_search!(i8, u8);
+signed_integer_bin_search!(i16, u16);
+signed_integer_bin_search!(i32, u32);
+signed_integer_bin_search!(i64, u64);
 #[cfg(not(target_arch = "wasm32"))]
-signed_integer_bin_search!(i128);
-signed_integer_bin_search!(isize);
+signed_integer_bin_search!(i128, u128);
+signed_integer_bin_search!(isize, usize);
 unsigned_integer_bin_search!(u8);
 unsigned_integer_bin_search!(u16);
 unsigned_integer_bin_search!(u32);
@@ -679,6 +975,17 @@ macro_rules! float_any {
                 Ok(BinarySearch::new_with_types(
                     $typ::from_bits(generated_value), flags))
             }
+
+            fn tree_from_value(&self, &value: &$typ, _: &mut TestRunner)
+                               -> NewTree<Self> {
+                let tree = BinarySearch::new_with_types(
+                    value, self.0.normalise());
+                if tree.current_allowed() {
+                    Ok(tree)
+                } else {
+                    Err("value is not of the classes being generated".into())
+                }
+            }
         }
     }
 }
@@ -701,15 +1008,26 @@ macro_rules! float_bin_search {
 
             float_any!($typ);
 
-            /// Shrinks a float towards 0, using binary search to find boundary
+            /// Shrinks a float towards 0, or the target given to
+            /// `prop_shrink_towards`, using binary search to find boundary
             /// points.
             ///
-            /// Non-finite values immediately shrink to 0.
+            /// Non-finite values immediately shrink to the target.
             #[derive(Clone, Copy, Debug)]
             pub struct BinarySearch {
                 lo: $typ,
                 curr: $typ,
                 hi: $typ,
+                /// The value `lo` started from, which `hi` approaches from
+                /// one side.
+                target: $typ,
+                /// Whether a target was given by `set_shrink_target`, in
+                /// which case the interval between `lo` and `hi` is bisected
+                /// instead of only trying `lo`.
+                targeted: bool,
+                /// Whether `lo` has been tried, after which candidates are
+                /// between it and `hi` instead.
+                bisect: bool,
                 allowed: FloatTypes,
             }
 
@@ -720,6 +1038,9 @@ macro_rules! float_bin_search {
                         lo: 0.0,
                         curr: start,
                         hi: start,
+                        target: 0.0,
+                        targeted: false,
+                        bisect: false,
                         allowed: FloatTypes::all(),
                     }
                 }
@@ -729,6 +1050,9 @@ macro_rules! float_bin_search {
                         lo: 0.0,
                         curr: start,
                         hi: start,
+                        target: 0.0,
+                        targeted: false,
+                        bisect: false,
                         allowed,
                     }
                 }
@@ -737,14 +1061,18 @@ macro_rules! float_bin_search {
                 /// on the other side of `lo` or `hi` from `start`. `lo` is
                 /// inclusive, `hi` is exclusive.
                 fn new_clamped(lo: $typ, start: $typ, hi: $typ) -> Self {
+                    let lo = if start.is_sign_negative() {
+                        hi.min(0.0)
+                    } else {
+                        lo.max(0.0)
+                    };
                     BinarySearch {
-                        lo: if start.is_sign_negative() {
-                            hi.min(0.0)
-                        } else {
-                            lo.max(0.0)
-                        },
+                        lo,
                         hi: start,
                         curr: start,
+                        target: lo,
+                        targeted: false,
+                        bisect: false,
                         allowed: FloatTypes::all(),
                     }
                 }
@@ -796,6 +1124,10 @@ macro_rules! float_bin_search {
                 }
 
                 fn reposition(&mut self) -> bool {
+                    if self.targeted {
+                        return self.reposition_towards_target();
+                    }
+
                     let interval = self.hi - self.lo;
                     let interval =
                         if interval.is_finite() { interval } else { 0.0 };
@@ -815,22 +1147,64 @@ macro_rules! float_bin_search {
                     }
                 }
 
-                fn done(lo: $typ, hi: $typ) -> bool {
-                    (lo.abs() > hi.abs() && !hi.is_nan()) || lo.is_nan()
+                /// Move to `lo` if it has not been tried yet, or else to the
+                /// midpoint of `lo` and `hi`, returning whether that is a new
+                /// value.
+                fn reposition_towards_target(&mut self) -> bool {
+                    // There is no useful midpoint towards a non-finite value.
+                    let new_mid = if self.bisect
+                        && self.lo.is_finite()
+                        && self.hi.is_finite()
+                    {
+                        midpoint(self.lo, self.hi)
+                    } else {
+                        self.lo
+                    };
+                    let tried_lo = self.bisect;
+                    self.bisect = true;
+
+                    if same(new_mid, self.curr)
+                        || (tried_lo && same(new_mid, self.lo))
+                    {
+                        false
+                    } else {
+                        self.curr = new_mid;
+                        true
+                    }
+                }
+
+                fn done(&self) -> bool {
+                    let (lo, hi) = (self.lo, self.hi);
+                    ((lo - self.target).abs() > (hi - self.target).abs()
+                        && !hi.is_nan())
+                        || lo.is_nan()
                 }
 
                 fn complicate_once(&mut self) -> bool {
-                    if BinarySearch::done(self.lo, self.hi) {
+                    if self.done() {
                         return false;
                     }
 
-                    self.lo = if self.curr == self.lo {
-                        self.hi
-                    } else {
-                        self.curr
-                    };
+                    if !self.targeted {
+                        self.lo = if self.curr == self.lo {
+                            self.hi
+                        } else {
+                            self.curr
+                        };
+                        return self.reposition();
+                    }
 
-                    self.reposition()
+                    self.lo = self.curr;
+                    if self.reposition() {
+                        true
+                    } else if !same(self.curr, self.hi) {
+                        // Nothing is left between `lo` and `hi`, so go back to
+                        // the simplest value known to fail.
+                        self.curr = self.hi;
+                        true
+                    } else {
+                        false
+                    }
                 }
             }
             impl ValueTree for BinarySearch {
@@ -841,7 +1215,7 @@ macro_rules! float_bin_search {
                 }
 
                 fn simplify(&mut self) -> bool {
-                    if BinarySearch::done(self.lo, self.hi) {
+                    if self.done() {
                         return false;
                     }
 
@@ -864,6 +1238,59 @@ macro_rules! float_bin_search {
                 }
             }
 
+            impl ShrinkTarget for BinarySearch {
+                fn set_shrink_target(&mut self, target: $typ) {
+                    self.lo = target;
+                    self.target = target;
+                    self.targeted = true;
+                }
+            }
+
+            /// Returns the float halfway between `lo` and `hi` when all floats
+            /// are put in order, so that a binary search takes at most as
+            /// many steps as there are bits.
+            fn midpoint(lo: $typ, hi: $typ) -> $typ {
+                // Maps floats to integers in the same order.
+                fn key(value: $typ) -> <$typ as FloatLayout>::Bits {
+                    let bits = value.to_bits();
+                    if 0 == bits & $typ::SIGN_MASK {
+                        bits | $typ::SIGN_MASK
+                    } else {
+                        !bits
+                    }
+                }
+                fn from_key(key: <$typ as FloatLayout>::Bits) -> $typ {
+                    $typ::from_bits(if 0 == key & $typ::SIGN_MASK {
+                        !key
+                    } else {
+                        key & !$typ::SIGN_MASK
+                    })
+                }
+
+                let (lo, hi) = (key(lo), key(hi));
+                from_key(if lo <= hi {
+                    lo + (hi - lo) / 2
+                } else {
+                    lo - (lo - hi) / 2
+                })
+            }
+
+            /// Whether `a` and `b` are the same value, counting all NaNs as
+            /// the same.
+            fn same(a: $typ, b: $typ) -> bool {
+                a == b || (a.is_nan() && b.is_nan())
+            }
+
+            /// Float ranges are always sampled uniformly, since the edge
+            /// values of floats are generated by the `Any` strategies.
+            fn sample_edge(
+                _: &mut TestRunner,
+                _: $typ,
+                _: $typ,
+            ) -> Option<$typ> {
+                None
+            }
+
             numeric_api!($typ, $sample_typ, 0.0);
         }
     };
@@ -913,6 +1340,87 @@ mod test {
         assert!(ok > 1, "inclusive end not included.");
     }
 
+    #[test]
+    fn tree_from_value_checks_range() {
+        let mut runner = TestRunner::deterministic();
+        assert!((0..10u8).tree_from_value(&10, &mut runner).is_err());
+        assert!((-5i32..=5).tree_from_value(&-6, &mut runner).is_err());
+        assert!((..1.0f64).tree_from_value(&1.0, &mut runner).is_err());
+        assert!(f64::POSITIVE.tree_from_value(&-1.0, &mut runner).is_err());
+        assert!(f64::NORMAL
+            .tree_from_value(&f64::INFINITY, &mut runner)
+            .is_err());
+
+        let mut tree = (-5i32..=5).tree_from_value(&-3, &mut runner).unwrap();
+        assert_eq!(-3, tree.current());
+        while tree.simplify() {}
+        assert_eq!(0, tree.current());
+
+        let mut tree = (10u64..).tree_from_value(&1000, &mut runner).unwrap();
+        assert_eq!(1000, tree.current());
+        while tree.simplify() {}
+        assert_eq!(10, tree.current());
+
+        let tree = f32::ANY.tree_from_value(&-2.5, &mut runner).unwrap();
+        assert_eq!(-2.5, tree.current());
+    }
+
+    #[test]
+    fn integer_edges_generated() {
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                edge_case_percent: 100,
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::default()),
+        );
+
+        let mut seen = std::collections::BTreeSet::new();
+        for _ in 0..1000 {
+            let value = (-100i32..100).new_tree(&mut runner).unwrap().current();
+            assert!(-100 <= value && value < 100, "{} out of range", value);
+            seen.insert(value);
+        }
+        for edge in &[-100, -99, -64, -1, 0, 1, 2, 64, 98, 99] {
+            assert!(seen.contains(edge), "{} never generated", edge);
+        }
+        assert!(seen.len() < 40, "not only edges: {:?}", seen);
+
+        let mut seen = std::collections::BTreeSet::new();
+        for _ in 0..1000 {
+            seen.insert(i64::ANY.new_tree(&mut runner).unwrap().current());
+        }
+        assert!(seen.contains(&i64::MIN));
+        assert!(seen.contains(&i64::MAX));
+        assert!(seen.contains(&-1));
+    }
+
+    #[test]
+    fn untargeted_float_shrinking_only_tries_zero() {
+        let mut tree = f64::BinarySearch::new(100.0);
+        assert!(tree.simplify());
+        assert_eq!(0.0, tree.current());
+        assert!(tree.complicate());
+        assert_eq!(100.0, tree.current());
+        assert!(!tree.simplify());
+    }
+
+    #[test]
+    fn edge_case_percent_zero_samples_uniformly() {
+        let rng = TestRng::deterministic_rng(RngAlgorithm::default());
+        let config = Config {
+            edge_case_percent: 0,
+            ..Config::default()
+        };
+        let mut runner = TestRunner::new_with_rng(config.clone(), rng.clone());
+        let mut uniform = TestRunner::new_with_rng(config, rng);
+
+        for _ in 0..100 {
+            let value = (0u32..1000).new_tree(&mut runner).unwrap().current();
+            assert_eq!(sample_uniform(&mut uniform, 0u32, 1000), value);
+        }
+    }
+
     #[test]
     fn i8_binary_search_always_converges() {
         fn assert_converges<P: Fn(i32) -> bool>(start: i8, pass: P) {
@@ -1057,10 +1565,11 @@ mod test {
         macro_rules! contract_sanity {
             ($t:tt) => {
                 mod $t {
-                    use crate::strategy::check_strategy_sanity;
+                    use crate::strategy::{check_strategy_sanity, Strategy};
 
                     const FORTY_TWO: $t = 42 as $t;
                     const FIFTY_SIX: $t = 56 as $t;
+                    const FORTY_NINE: $t = 49 as $t;
 
                     #[test]
                     fn range() {
@@ -1086,6 +1595,14 @@ mod test {
                     fn range_from() {
                         check_strategy_sanity(FORTY_TWO.., None);
                     }
+
+                    #[test]
+                    fn shrink_towards() {
+                        check_strategy_sanity(
+                            (..=FIFTY_SIX).prop_shrink_towards(FORTY_NINE),
+                            None,
+                        );
+                    }
                 }
             };
         }
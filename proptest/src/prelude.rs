@@ -25,8 +25,8 @@ pub use crate::strategy::{BoxedStrategy, Just, SBoxedStrategy, Strategy};
 pub use crate::test_runner::Config as ProptestConfig;
 pub use crate::test_runner::TestCaseError;
 pub use crate::{
-    prop_assert, prop_assert_eq, prop_assert_ne, prop_assume, prop_compose,
-    prop_oneof, proptest,
+    prop_assert, prop_assert_eq, prop_assert_ne, prop_assume, prop_classify,
+    prop_collect, prop_compose, prop_cover, prop_oneof, prop_target, proptest,
 };
 
 pub use rand::{Rng, RngCore};
@@ -0,0 +1,169 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// A `Strategy` whose values can be listed one by one, so that a test whose
+/// whole input space is small can be run on every input instead of on
+/// randomly chosen ones.
+///
+/// `TestRunner::run` tests every value of the strategy, in order, when
+/// `Strategy::as_enumerable` returns `Some` and the `domain_size` is at most
+/// `Config::exhaustive_threshold`.
+///
+/// This is implemented by `Just`, `bool::ANY`, the `ANY` strategies and
+/// `Range` and `RangeInclusive` of integer types, `sample::select`, `Union`,
+/// `TupleUnion` (as created by `prop_oneof!`), tuples, `prop_map`,
+/// `prop_map_into` and fixed-size arrays. Strategies made of other
+/// strategies are only enumerable when those are.
+pub trait Enumerable: Strategy {
+    /// Returns the number of values this strategy enumerates, or `None` if
+    /// that does not fit in a `u64`.
+    fn domain_size(&self) -> Option<u64>;
+
+    /// Generate the value tree for the value at `index`, which must be less
+    /// than `domain_size()`.
+    ///
+    /// The value tree shrinks in the same way as one from `new_tree`. The
+    /// runner is only used for the parts of it which are generated lazily
+    /// while shrinking, such as the other options of a `Union`.
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self>;
+}
+
+/// Returns the number of values `strategy` enumerates, or `None` if it is
+/// not enumerable or they do not fit in a `u64`.
+pub(crate) fn domain_size<S: Strategy + ?Sized>(strategy: &S) -> Option<u64> {
+    strategy.as_enumerable()?.domain_size()
+}
+
+/// Generate the value tree for the value of `strategy` at `index`.
+pub(crate) fn enumerate_tree<S: Strategy + ?Sized>(
+    strategy: &S,
+    index: u64,
+    runner: &mut TestRunner,
+) -> NewTree<S> {
+    match strategy.as_enumerable() {
+        Some(strategy) => strategy.enumerate_tree(index, runner),
+        None => Err("strategy is not enumerable".into()),
+    }
+}
+
+/// Generate the value tree for one part of a product of strategies, such as
+/// a tuple, taking the index of its value from the remainder of `index` and
+/// leaving the quotient for the parts after it.
+pub(crate) fn enumerate_part<S: Strategy + ?Sized>(
+    strategy: &S,
+    index: &mut u64,
+    runner: &mut TestRunner,
+) -> NewTree<S> {
+    let size = match domain_size(strategy) {
+        Some(size) if size > 0 => size,
+        _ => return Err("strategy has no values to enumerate".into()),
+    };
+    let tree = enumerate_tree(strategy, *index % size, runner)?;
+    *index /= size;
+    Ok(tree)
+}
+
+/// Returns the number of values in a product of strategies with the given
+/// domain sizes.
+pub(crate) fn product_size(
+    sizes: impl IntoIterator<Item = Option<u64>>,
+) -> Option<u64> {
+    sizes
+        .into_iter()
+        .try_fold(1u64, |product, size| product.checked_mul(size?))
+}
+
+/// Returns the position of the option `index` falls in, given the domain
+/// sizes of a list of options which are enumerated one after the other, and
+/// the index within that option.
+pub(crate) fn pick_enumerated(
+    sizes: impl IntoIterator<Item = Option<u64>>,
+    mut index: u64,
+) -> Result<(usize, u64), Reason> {
+    for (pick, size) in sizes.into_iter().enumerate() {
+        let size = size.ok_or("strategy is not enumerable")?;
+        if index < size {
+            return Ok((pick, index));
+        }
+        index -= size;
+    }
+    Err("index is past the end of the enumerated values".into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::Vec;
+    use crate::strategy::Just;
+
+    fn enumerate_all<S: Strategy>(strategy: &S) -> Vec<S::Value> {
+        let mut runner = TestRunner::deterministic();
+        let size = domain_size(strategy).expect("not enumerable");
+        (0..size)
+            .map(|index| {
+                enumerate_tree(strategy, index, &mut runner)
+                    .unwrap()
+                    .current()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn integer_domain_sizes() {
+        assert_eq!(Some(256), domain_size(&crate::num::i8::ANY));
+        assert_eq!(Some(256), domain_size(&(-128i8..=127)));
+        assert_eq!(Some(0), domain_size(&(5u8..5)));
+        assert_eq!(Some(1 << 32), domain_size(&crate::num::u32::ANY));
+        assert_eq!(None, domain_size(&crate::num::u64::ANY));
+        assert_eq!(Some(u64::MAX), domain_size(&(1u64..=u64::MAX)));
+        assert!((0.0f64..1.0).as_enumerable().is_none());
+    }
+
+    #[test]
+    fn enumerates_every_value_in_order() {
+        assert_eq!(vec![-2, -1, 0, 1], enumerate_all(&(-2i32..2)));
+        assert_eq!(vec![false, true], enumerate_all(&crate::bool::ANY));
+        assert_eq!(
+            vec![(false, 'a'), (true, 'a'), (false, 'b'), (true, 'b')],
+            enumerate_all(&(
+                crate::bool::ANY,
+                prop_oneof![Just('a'), Just('b')]
+            ))
+        );
+        assert_eq!(
+            vec![0, 1, 10, 11, 12],
+            enumerate_all(&(0u8..2).prop_union(10u8..13))
+        );
+        assert_eq!(
+            vec!["x", "y"],
+            enumerate_all(&crate::sample::select(vec!["x", "y"]))
+        );
+        assert_eq!(
+            vec![[0, 0], [1, 0], [0, 1], [1, 1]],
+            enumerate_all(&[0u8..2, 0u8..2])
+        );
+        assert_eq!(vec![2, 4], enumerate_all(&(1u8..=2).prop_map(|v| v * 2)));
+    }
+
+    #[test]
+    fn strategies_with_unenumerable_parts_are_not_enumerable() {
+        assert!((crate::bool::ANY, 0.0f64..1.0).as_enumerable().is_none());
+        assert!((0u8..2)
+            .prop_filter("odd", |v| v % 2 == 1)
+            .as_enumerable()
+            .is_none());
+    }
+}
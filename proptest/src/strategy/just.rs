@@ -9,7 +9,7 @@
 
 use crate::std_facade::fmt;
 
-use crate::strategy::{NewTree, Strategy, ValueTree};
+use crate::strategy::{Enumerable, NewTree, Strategy, ValueTree};
 use crate::test_runner::TestRunner;
 
 macro_rules! noshrink {
@@ -43,6 +43,32 @@ impl<T: Clone + fmt::Debug> Strategy for Just<T> {
     fn new_tree(&self, _: &mut TestRunner) -> NewTree<Self> {
         Ok(self.clone())
     }
+
+    /// Since `T` need not be `PartialEq`, values are compared by their
+    /// `Debug` representation, as the default result cache does.
+    fn tree_from_value(&self, value: &T, _: &mut TestRunner) -> NewTree<Self> {
+        if format!("{:?}", value) == format!("{:?}", self.0) {
+            Ok(self.clone())
+        } else {
+            Err("value is not the one produced by `Just`".into())
+        }
+    }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        Some(self)
+    }
+}
+
+impl<T: Clone + fmt::Debug> Enumerable for Just<T> {
+    fn domain_size(&self) -> Option<u64> {
+        Some(1)
+    }
+
+    fn enumerate_tree(&self, _: u64, _: &mut TestRunner) -> NewTree<Self> {
+        Ok(self.clone())
+    }
 }
 
 impl<T: Clone + fmt::Debug> ValueTree for Just<T> {
@@ -143,3 +169,16 @@ impl<T: fmt::Debug> ValueTree for fn() -> T {
         self()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tree_from_value_only_accepts_the_value() {
+        let mut runner = TestRunner::deterministic();
+        let input = Just(42u32);
+        assert_eq!(42, input.tree_from_value(&42, &mut runner).unwrap().0);
+        assert!(input.tree_from_value(&43, &mut runner).is_err());
+    }
+}
@@ -12,6 +12,7 @@ use core::fmt;
 use core::marker::PhantomData;
 
 use crate::strategy::traits::*;
+use crate::strategy::{domain_size, enumerate_tree, Enumerable};
 use crate::test_runner::*;
 
 //==============================================================================
@@ -55,6 +56,31 @@ impl<S: Strategy, O: fmt::Debug, F: Fn(S::Value) -> O> Strategy for Map<S, F> {
             fun: Arc::clone(&self.fun),
         })
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.source.as_enumerable().map(|_| self as _)
+    }
+}
+
+impl<S: Strategy, O: fmt::Debug, F: Fn(S::Value) -> O> Enumerable
+    for Map<S, F>
+{
+    fn domain_size(&self) -> Option<u64> {
+        domain_size(&self.source)
+    }
+
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        enumerate_tree(&self.source, index, runner).map(|v| Map {
+            source: v,
+            fun: Arc::clone(&self.fun),
+        })
+    }
 }
 
 impl<S: ValueTree, O: fmt::Debug, F: Fn(S::Value) -> O> ValueTree
@@ -126,6 +152,29 @@ where
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         self.source.new_tree(runner).map(MapInto::new)
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.source.as_enumerable().map(|_| self as _)
+    }
+}
+
+impl<S: Strategy, O: fmt::Debug> Enumerable for MapInto<S, O>
+where
+    S::Value: Into<O>,
+{
+    fn domain_size(&self) -> Option<u64> {
+        domain_size(&self.source)
+    }
+
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        enumerate_tree(&self.source, index, runner).map(MapInto::new)
+    }
 }
 
 impl<S: ValueTree, O: fmt::Debug> ValueTree for MapInto<S, O>
@@ -147,6 +196,105 @@ where
     }
 }
 
+//==============================================================================
+// MapInvertible
+//==============================================================================
+
+/// `Strategy` map adaptor which can also map values back to the source.
+///
+/// See `Strategy::prop_map_invertible()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct MapInvertible<S, F, G> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+    pub(super) inverse: Arc<G>,
+}
+
+impl<S: fmt::Debug, F, G> fmt::Debug for MapInvertible<S, F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapInvertible")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("inverse", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F, G> Clone for MapInvertible<S, F, G> {
+    fn clone(&self) -> Self {
+        MapInvertible {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            inverse: Arc::clone(&self.inverse),
+        }
+    }
+}
+
+impl<
+        S: Strategy,
+        O: fmt::Debug + PartialEq,
+        F: Fn(S::Value) -> O,
+        G: Fn(&O) -> S::Value,
+    > Strategy for MapInvertible<S, F, G>
+{
+    type Tree = Map<S::Tree, F>;
+    type Value = O;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source.new_tree(runner).map(|v| Map {
+            source: v,
+            fun: Arc::clone(&self.fun),
+        })
+    }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.source.as_enumerable().map(|_| self as _)
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &O,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        let source = (self.inverse)(value);
+        let source = self.source.tree_from_value(&source, runner)?;
+        // Values `fun` cannot produce may still map back to some source
+        // value, which then maps to something else.
+        if (self.fun)(source.current()) != *value {
+            return Err("value is not one the mapping can produce".into());
+        }
+        Ok(Map {
+            source,
+            fun: Arc::clone(&self.fun),
+        })
+    }
+}
+
+impl<
+        S: Strategy,
+        O: fmt::Debug + PartialEq,
+        F: Fn(S::Value) -> O,
+        G: Fn(&O) -> S::Value,
+    > Enumerable for MapInvertible<S, F, G>
+{
+    fn domain_size(&self) -> Option<u64> {
+        domain_size(&self.source)
+    }
+
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        enumerate_tree(&self.source, index, runner).map(|v| Map {
+            source: v,
+            fun: Arc::clone(&self.fun),
+        })
+    }
+}
+
 //==============================================================================
 // Perturb
 //==============================================================================
@@ -275,6 +423,28 @@ mod test {
             .unwrap();
     }
 
+    #[test]
+    fn map_invertible_shrinks_given_value() {
+        let input = (0..1000u32).prop_map_invertible(|v| v * 2, |v| v / 2);
+        let mut runner = TestRunner::default();
+
+        let tree = input.tree_from_value(&842, &mut runner).unwrap();
+        assert_eq!(842, tree.current());
+        let result = runner.run_one(tree, |v| {
+            prop_assert!(v < 100);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, minimal)) => assert_eq!(100, minimal),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        // 2000 maps back to 1000, which is outside the source range.
+        assert!(input.tree_from_value(&2000, &mut runner).is_err());
+        // 43 maps back to 21, which maps to 42 instead.
+        assert!(input.tree_from_value(&43, &mut runner).is_err());
+    }
+
     #[test]
     fn perturb_uses_same_rng_every_time() {
         let mut runner = TestRunner::default();
@@ -9,6 +9,7 @@
 
 //! Defines the core traits used by Proptest.
 
+mod enumerable;
 mod filter;
 mod filter_map;
 mod flatten;
@@ -17,10 +18,13 @@ mod just;
 mod lazy;
 mod map;
 mod recursive;
+mod shrink_towards;
+mod shrink_with;
 mod shuffle;
 mod traits;
 mod unions;
 
+pub use self::enumerable::*;
 pub use self::filter::*;
 pub use self::filter_map::*;
 pub use self::flatten::*;
@@ -30,6 +34,8 @@ pub use self::lazy::*;
 pub use self::lazy::*;
 pub use self::map::*;
 pub use self::recursive::*;
+pub use self::shrink_towards::*;
+pub use self::shrink_with::*;
 pub use self::shuffle::*;
 pub use self::traits::*;
 pub use self::unions::*;
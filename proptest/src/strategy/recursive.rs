@@ -108,10 +108,14 @@ impl<
         // also clamp all probabilities to 0.9 to ensure that we can't end up
         // with levels which are always pure branches, which further
         // underestimates size.
+        //
+        // The max depth is scaled down to the runner's current size, so that
+        // a run starts with shallow trees.
 
+        let depth = runner.scale_to_size(0, self.depth as usize);
         let mut branch_probabilities = Vec::new();
         let mut k2 = u64::from(self.expected_branch_size) * 2;
-        for _ in 0..self.depth {
+        for _ in 0..depth {
             branch_probabilities.push(f64::from(self.desired_size) / k2 as f64);
             k2 = k2.saturating_mul(u64::from(self.expected_branch_size) * 2);
         }
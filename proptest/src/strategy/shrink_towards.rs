@@ -0,0 +1,185 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which shrinks numbers towards a target other than 0.
+///
+/// See `Strategy::prop_shrink_towards()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct ShrinkTowards<S: Strategy> {
+    pub(super) source: S,
+    pub(super) target: S::Value,
+}
+
+/// A value tree which can be used with the `prop_shrink_towards` combinator.
+///
+/// This is implemented by the `BinarySearch` value trees of the numeric
+/// strategies in `proptest::num`.
+///
+/// This is not a general-purpose trait. Its method is prefixed with
+/// `set_shrink_` to avoid the compiler suggesting it or this trait as
+/// corrections in errors.
+pub trait ShrinkTarget: ValueTree {
+    /// Shrink towards `target` instead of 0 or the start of the range.
+    ///
+    /// This must be called before the first call to `simplify()`.
+    fn set_shrink_target(&mut self, target: Self::Value);
+}
+
+impl<S: Strategy> Strategy for ShrinkTowards<S>
+where
+    S::Tree: ShrinkTarget,
+    S::Value: Copy,
+{
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.check_target(runner)?;
+        let mut tree = self.source.new_tree(runner)?;
+        tree.set_shrink_target(self.target);
+        Ok(tree)
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        self.check_target(runner)?;
+        let mut tree = self.source.tree_from_value(value, runner)?;
+        tree.set_shrink_target(self.target);
+        Ok(tree)
+    }
+}
+
+impl<S: Strategy> ShrinkTowards<S> {
+    /// Fail unless the source strategy can produce the target, since
+    /// shrinking towards it would otherwise leave the source's range.
+    fn check_target(&self, runner: &mut TestRunner) -> Result<(), Reason> {
+        match self.source.tree_from_value(&self.target, runner) {
+            Ok(_) => Ok(()),
+            Err(_) => {
+                Err("shrink target is outside the range of the strategy \
+                           it is for"
+                    .into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::num;
+    use crate::strategy::check_strategy_sanity;
+
+    #[test]
+    fn shrinks_towards_target_from_both_sides() {
+        let mut runner = TestRunner::deterministic();
+        let input = (0u16..20_000).prop_shrink_towards(8080);
+        let (mut from_below, mut from_above) = (0, 0);
+
+        for _ in 0..256 {
+            let tree = input.new_tree(&mut runner).unwrap();
+            let start = tree.current();
+            let result = runner.run_one(tree, |v| {
+                prop_assert!((8000..9000).contains(&v));
+                Ok(())
+            });
+
+            match result {
+                Ok(_) => (),
+                Err(TestError::Fail(_, value)) if start < 8000 => {
+                    assert_eq!(7999, value);
+                    from_below += 1;
+                }
+                Err(TestError::Fail(_, value)) => {
+                    assert_eq!(9000, value);
+                    from_above += 1;
+                }
+                Err(e) => panic!("Unexpected result: {:?}", e),
+            }
+        }
+        assert!(from_below > 0 && from_above > 0);
+    }
+
+    #[test]
+    fn shrinks_across_zero_without_overflow() {
+        let mut runner = TestRunner::deterministic();
+        let input = num::i8::ANY.prop_shrink_towards(-100);
+
+        for _ in 0..256 {
+            let tree = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(tree, |v| {
+                prop_assert!(v < 100);
+                Ok(())
+            });
+
+            if let Err(TestError::Fail(_, value)) = result {
+                assert_eq!(100, value);
+            }
+        }
+
+        check_strategy_sanity(num::i8::ANY.prop_shrink_towards(-100), None);
+        check_strategy_sanity(
+            num::i64::ANY.prop_shrink_towards(i64::MIN),
+            None,
+        );
+        check_strategy_sanity(
+            num::u32::ANY.prop_shrink_towards(u32::MAX),
+            None,
+        );
+    }
+
+    #[test]
+    fn floats_shrink_towards_target() {
+        let mut runner = TestRunner::deterministic();
+        let input = (-10.0f64..10.0).prop_shrink_towards(1.0);
+
+        for _ in 0..256 {
+            let tree = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(tree, |v| {
+                prop_assert!(v < 5.0 && v > -5.0);
+                Ok(())
+            });
+
+            if let Err(TestError::Fail(_, value)) = result {
+                assert!(5.0 == value || -5.0 == value, "{}", value);
+            }
+        }
+
+        check_strategy_sanity(input, None);
+    }
+
+    #[test]
+    fn target_outside_range_rejected() {
+        let mut runner = TestRunner::deterministic();
+        assert!((0u8..10)
+            .prop_shrink_towards(200)
+            .new_tree(&mut runner)
+            .is_err());
+        assert!((0u8..10)
+            .prop_shrink_towards(200)
+            .tree_from_value(&5, &mut runner)
+            .is_err());
+        assert!((-1.0f64..1.0)
+            .prop_shrink_towards(1.0)
+            .new_tree(&mut runner)
+            .is_err());
+        assert!((0u8..=10)
+            .prop_shrink_towards(10)
+            .new_tree(&mut runner)
+            .is_ok());
+    }
+}
@@ -0,0 +1,291 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` adaptor which shrinks with a function giving simpler
+/// candidates for a value.
+///
+/// See `Strategy::prop_shrink_with()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct ShrinkWith<S, F> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for ShrinkWith<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShrinkWith")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for ShrinkWith<S, F> {
+    fn clone(&self) -> Self {
+        ShrinkWith {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+        }
+    }
+}
+
+impl<S: Strategy, F, I> Strategy for ShrinkWith<S, F>
+where
+    S::Value: Clone,
+    F: Fn(&S::Value) -> I,
+    I: Iterator<Item = S::Value>,
+{
+    type Tree = ShrinkWithValueTree<S::Tree, F, I>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source
+            .new_tree(runner)
+            .map(|source| ShrinkWithValueTree {
+                source,
+                fun: Arc::clone(&self.fun),
+                candidates: None,
+            })
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        self.source.tree_from_value(value, runner).map(|source| {
+            ShrinkWithValueTree {
+                source,
+                fun: Arc::clone(&self.fun),
+                candidates: None,
+            }
+        })
+    }
+}
+
+/// The candidates tried once the source value tree cannot shrink any
+/// further.
+#[derive(Clone)]
+struct Candidates<T, I> {
+    /// The simplest value known to fail, which the candidates came from.
+    failing: T,
+    /// The value currently being tried.
+    current: T,
+    /// The candidates which have not been tried yet.
+    rest: I,
+    /// Whether every candidate was tried, so `current` is `failing` again.
+    exhausted: bool,
+}
+
+impl<T: fmt::Debug, I> fmt::Debug for Candidates<T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Candidates")
+            .field("failing", &self.failing)
+            .field("current", &self.current)
+            .field("rest", &"<iterator>")
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
+
+/// `ValueTree` corresponding to `ShrinkWith`.
+///
+/// This first shrinks like the source value tree. Once that cannot shrink
+/// any further, it tries the candidates given by the function for the
+/// simplest failing value in turn, starting again from the candidates for a
+/// candidate which also fails, until none of them fail.
+pub struct ShrinkWithValueTree<V: ValueTree, F, I> {
+    source: V,
+    fun: Arc<F>,
+    candidates: Option<Candidates<V::Value, I>>,
+}
+
+impl<V: ValueTree + fmt::Debug, F, I> fmt::Debug
+    for ShrinkWithValueTree<V, F, I>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShrinkWithValueTree")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("candidates", &self.candidates)
+            .finish()
+    }
+}
+
+impl<V, F, I> Clone for ShrinkWithValueTree<V, F, I>
+where
+    V: ValueTree + Clone,
+    V::Value: Clone,
+    I: Clone,
+{
+    fn clone(&self) -> Self {
+        ShrinkWithValueTree {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            candidates: self.candidates.clone(),
+        }
+    }
+}
+
+impl<V, F, I> ShrinkWithValueTree<V, F, I>
+where
+    V: ValueTree,
+    V::Value: Clone,
+    F: Fn(&V::Value) -> I,
+    I: Iterator<Item = V::Value>,
+{
+    /// Start trying the candidates for `failing`, returning whether there
+    /// are any.
+    fn try_candidates(&mut self, failing: V::Value) -> bool {
+        let mut rest = (self.fun)(&failing);
+        let (current, exhausted) = match rest.next() {
+            Some(candidate) => (candidate, false),
+            None => (failing.clone(), true),
+        };
+        self.candidates = Some(Candidates {
+            failing,
+            current,
+            rest,
+            exhausted,
+        });
+        !exhausted
+    }
+}
+
+impl<V, F, I> ValueTree for ShrinkWithValueTree<V, F, I>
+where
+    V: ValueTree,
+    V::Value: Clone,
+    F: Fn(&V::Value) -> I,
+    I: Iterator<Item = V::Value>,
+{
+    type Value = V::Value;
+
+    fn current(&self) -> V::Value {
+        match self.candidates {
+            Some(ref candidates) => candidates.current.clone(),
+            None => self.source.current(),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        let failing = match self.candidates {
+            None if self.source.simplify() => return true,
+            None => self.source.current(),
+            Some(ref candidates) if candidates.exhausted => return false,
+            Some(ref candidates) => candidates.current.clone(),
+        };
+        self.try_candidates(failing)
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.candidates {
+            None if self.source.complicate() => true,
+            // The source is back at its simplest failing value.
+            None => {
+                let failing = self.source.current();
+                self.try_candidates(failing)
+            }
+            Some(ref mut candidates) => {
+                if candidates.exhausted {
+                    return false;
+                }
+
+                match candidates.rest.next() {
+                    Some(candidate) => candidates.current = candidate,
+                    // Go back to the failing value. This still returns true,
+                    // since the value changed, and the following
+                    // `simplify()` will return false.
+                    None => {
+                        candidates.current = candidates.failing.clone();
+                        candidates.exhausted = true;
+                    }
+                }
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::std_facade::{vec, Vec};
+
+    use super::*;
+
+    use crate::strategy::check_strategy_sanity;
+
+    /// Candidates for `v`: halving it, then just decrementing it.
+    fn halve_or_decrement(&v: &u32) -> impl Iterator<Item = u32> + Clone {
+        let candidates = if v > 0 { vec![v / 2, v - 1] } else { vec![] };
+        candidates.into_iter()
+    }
+
+    #[test]
+    fn shrinks_with_candidates_alone() {
+        let input = (1000u32..100_000)
+            .no_shrink()
+            .prop_shrink_with(halve_or_decrement);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..64 {
+            let tree = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(tree, |v| {
+                prop_assert!(v < 500);
+                Ok(())
+            });
+            match result {
+                Err(TestError::Fail(_, value)) => assert_eq!(500, value),
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn candidates_continue_from_source_shrinking() {
+        // The source shrinks towards 0 in steps of 10, which only gets within
+        // 10 of the simplest failing value.
+        let input = (0u32..1000)
+            .prop_map(|v| v * 10)
+            .prop_shrink_with(halve_or_decrement);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..64 {
+            let tree = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(tree, |v| {
+                prop_assert!(v < 4321);
+                Ok(())
+            });
+            match result {
+                Ok(_) => (),
+                Err(TestError::Fail(_, value)) => assert_eq!(4321, value),
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn shrink_with_upholds_contract() {
+        check_strategy_sanity(
+            (0u32..1000).prop_shrink_with(halve_or_decrement),
+            None,
+        );
+        check_strategy_sanity(
+            (0u32..1000)
+                .no_shrink()
+                .prop_shrink_with(|_: &u32| Vec::<u32>::new().into_iter()),
+            None,
+        );
+    }
+}
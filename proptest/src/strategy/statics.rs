@@ -27,6 +27,7 @@
 use crate::std_facade::fmt;
 
 use crate::strategy::traits::*;
+use crate::strategy::{domain_size, enumerate_tree, Enumerable};
 use crate::test_runner::*;
 
 //==============================================================================
@@ -178,6 +179,29 @@ impl<S: Strategy, F: Clone + MapFn<S::Value>> Strategy for Map<S, F> {
             fun: self.fun.clone(),
         })
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.source.as_enumerable().map(|_| self as _)
+    }
+}
+
+impl<S: Strategy, F: Clone + MapFn<S::Value>> Enumerable for Map<S, F> {
+    fn domain_size(&self) -> Option<u64> {
+        domain_size(&self.source)
+    }
+
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        enumerate_tree(&self.source, index, runner).map(|v| Map {
+            source: v,
+            fun: self.fun.clone(),
+        })
+    }
 }
 
 impl<S: ValueTree, F: MapFn<S::Value>> ValueTree for Map<S, F> {
@@ -101,6 +101,46 @@ pub trait Strategy: fmt::Debug {
         MapInto::new(self)
     }
 
+    /// Returns a strategy which produces values transformed by the function
+    /// `forward`, like `prop_map()`, and which maps values back to the source
+    /// with `backward` in `tree_from_value()`.
+    ///
+    /// `backward` should be the inverse of `forward`. That is, for every
+    /// value `v` of the source, `forward(v)` should be mapped back to an
+    /// equivalent of `v`. `tree_from_value()` fails unless the source can
+    /// produce the value `backward` returns and `forward` maps that back to
+    /// the value given, so values `forward` cannot produce are rejected
+    /// whatever `backward` returns for them.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::strategy::ValueTree;
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// let evens = (0..1000u32).prop_map_invertible(|v| v * 2, |v| v / 2);
+    ///
+    /// let mut runner = TestRunner::default();
+    /// let tree = evens.tree_from_value(&42, &mut runner).unwrap();
+    /// assert_eq!(42, tree.current());
+    /// ```
+    fn prop_map_invertible<O, F, G>(
+        self,
+        forward: F,
+        backward: G,
+    ) -> MapInvertible<Self, F, G>
+    where
+        Self: Sized,
+        O: fmt::Debug + PartialEq,
+        F: Fn(Self::Value) -> O,
+        G: Fn(&O) -> Self::Value,
+    {
+        MapInvertible {
+            source: self,
+            fun: Arc::new(forward),
+            inverse: Arc::new(backward),
+        }
+    }
+
     /// Returns a strategy which produces values transformed by the function
     /// `fun`, which is additionally given a random number generator.
     ///
@@ -505,6 +545,80 @@ pub trait Strategy: fmt::Debug {
         Shuffle(self)
     }
 
+    /// Shrink the numbers produced by this strategy towards `target`, rather
+    /// than towards 0 or the start of the range they come from.
+    ///
+    /// This works for any strategy whose value tree is one of the
+    /// `BinarySearch` trees in `proptest::num`, such as integer and float
+    /// ranges and `ANY`. A failing value is shrunk by binary search between
+    /// it and `target`, from either side, so values on the far side of
+    /// `target` are never produced while shrinking. `target` must be a value
+    /// the strategy can produce, or generating values fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// // The simplest port is the usual one, rather than 1024.
+    /// let port = (1024u16..).prop_shrink_towards(8080);
+    /// # let _ = port;
+    /// ```
+    fn prop_shrink_towards(self, target: Self::Value) -> ShrinkTowards<Self>
+    where
+        Self: Sized,
+        Self::Tree: ShrinkTarget,
+        Self::Value: Copy,
+    {
+        ShrinkTowards {
+            source: self,
+            target,
+        }
+    }
+
+    /// Shrink the values produced by this strategy further with `shrink`,
+    /// which returns simpler candidates for a value.
+    ///
+    /// Values are first shrunk as this strategy would shrink them. Once that
+    /// cannot go any further, the candidates for the simplest failing value
+    /// are tried in order, and the first of them which also fails is shrunk
+    /// in the same way, until none of the candidates of a value fail. To
+    /// shrink only with `shrink`, call `no_shrink()` on this strategy first.
+    ///
+    /// This is a simpler way to describe how a domain value gets simpler
+    /// than writing a `ValueTree`. The candidates should all be simpler than
+    /// the value they come from, or shrinking may not finish before
+    /// `max_shrink_iters`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// // Shrink words by removing each of their letters.
+    /// let word = "[a-z]{1,8}".prop_shrink_with(|s: &String| {
+    ///     let s = s.clone();
+    ///     (0..s.len()).map(move |ix| {
+    ///         let mut shorter = s.clone();
+    ///         shorter.remove(ix);
+    ///         shorter
+    ///     })
+    /// });
+    /// # let _ = word;
+    /// ```
+    fn prop_shrink_with<I, F>(self, shrink: F) -> ShrinkWith<Self, F>
+    where
+        Self: Sized,
+        Self::Value: Clone,
+        F: Fn(&Self::Value) -> I,
+        I: Iterator<Item = Self::Value>,
+    {
+        ShrinkWith {
+            source: self,
+            fun: Arc::new(shrink),
+        }
+    }
+
     /// Erases the type of this `Strategy` so it can be passed around as a
     /// simple trait object.
     ///
@@ -550,6 +664,62 @@ pub trait Strategy: fmt::Debug {
     {
         NoShrink(self)
     }
+
+    /// Returns this strategy as an `Enumerable` if every value it can produce
+    /// can be listed, which lets `TestRunner::run` test all of them when
+    /// there are few enough.
+    ///
+    /// The default implementation returns `None`.
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        None
+    }
+
+    /// Generate the value tree whose current value is `value`, so that a
+    /// known value, such as the input from a bug report, can be shrunk as if
+    /// this strategy had generated it.
+    ///
+    /// This fails if `value` is not one this strategy can produce, or if the
+    /// strategy cannot turn values back into value trees. The runner is only
+    /// used for the parts of the value tree which are generated lazily while
+    /// shrinking, such as the other options of a `Union`.
+    ///
+    /// This is implemented by `Just` (which compares values by their `Debug`
+    /// representation), `bool::ANY`, `bool::weighted`,
+    /// `char` strategies, the `ANY` strategies and ranges of numeric types,
+    /// `collection::vec`, `Vec`s of strategies, tuples, `Union`,
+    /// `TupleUnion` (as created by `prop_oneof!`), `prop_map_invertible`,
+    /// `prop_shrink_towards`, `prop_shrink_with`, `no_shrink` and boxed
+    /// strategies. The default implementation fails.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::{TestError, TestRunner};
+    ///
+    /// let mut runner = TestRunner::default();
+    /// let tree = prop::collection::vec(0..100u32, 0..10)
+    ///     .tree_from_value(&vec![12, 99, 3], &mut runner)
+    ///     .unwrap();
+    ///
+    /// // Shrink the value to the simplest one which still fails.
+    /// let result = runner.run_one(tree, |v| {
+    ///     prop_assert!(!v.contains(&99));
+    ///     Ok(())
+    /// });
+    /// match result {
+    ///     Err(TestError::Fail(_, minimal)) => assert_eq!(vec![99], minimal),
+    ///     result => panic!("Unexpected result: {:?}", result),
+    /// }
+    /// ```
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        let _ = (value, runner);
+        Err("strategy cannot make a value tree from a value".into())
+    }
 }
 
 /// A generated value and its associated shrinker.
@@ -639,6 +809,14 @@ impl<T: Strategy> Strategy for NoShrink<T> {
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         self.0.new_tree(runner).map(NoShrink)
     }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        self.0.tree_from_value(value, runner).map(NoShrink)
+    }
 }
 
 impl<T: ValueTree> ValueTree for NoShrink<T> {
@@ -669,6 +847,21 @@ macro_rules! proxy_strategy {
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
                 (**self).new_tree(runner)
             }
+
+            fn as_enumerable(
+                &self,
+            ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>>
+            {
+                (**self).as_enumerable()
+            }
+
+            fn tree_from_value(
+                &self,
+                value: &Self::Value,
+                runner: &mut TestRunner,
+            ) -> NewTree<Self> {
+                (**self).tree_from_value(value, runner)
+            }
         }
     };
 }
@@ -733,6 +926,20 @@ impl<T: fmt::Debug> Strategy for BoxedStrategy<T> {
         self.0.new_tree(runner)
     }
 
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.0.as_enumerable()
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        self.0.tree_from_value(value, runner)
+    }
+
     // Optimization: Don't rebox the strategy.
 
     fn boxed(self) -> BoxedStrategy<Self::Value>
@@ -751,6 +958,20 @@ impl<T: fmt::Debug> Strategy for SBoxedStrategy<T> {
         self.0.new_tree(runner)
     }
 
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.0.as_enumerable()
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        self.0.tree_from_value(value, runner)
+    }
+
     // Optimization: Don't rebox the strategy.
 
     fn sboxed(self) -> SBoxedStrategy<Self::Value>
@@ -780,6 +1001,37 @@ where
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         Ok(Box::new(self.0.new_tree(runner)?))
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        self.0.as_enumerable().map(|_| self as _)
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        Ok(Box::new(self.0.tree_from_value(value, runner)?))
+    }
+}
+
+impl<T: Strategy> Enumerable for BoxedStrategyWrapper<T>
+where
+    T::Tree: 'static,
+{
+    fn domain_size(&self) -> Option<u64> {
+        domain_size(&self.0)
+    }
+
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        Ok(Box::new(enumerate_tree(&self.0, index, runner)?))
+    }
 }
 
 //==============================================================================
@@ -15,7 +15,10 @@ use core::u32;
 use num_traits::float::FloatCore;
 
 use crate::num::sample_uniform;
-use crate::strategy::{lazy::LazyValueTree, traits::*};
+use crate::strategy::{
+    domain_size, enumerate_tree, lazy::LazyValueTree, pick_enumerated,
+    traits::*, Enumerable,
+};
 use crate::test_runner::*;
 
 /// A **relative** `weight` of a particular `Strategy` corresponding to `T`
@@ -117,6 +120,35 @@ fn pick_weighted<I: Iterator<Item = u32>>(
         .count()
 }
 
+/// Returns whether the option can be enumerated, which options with a weight
+/// of 0 trivially can since they are never picked.
+fn option_enumerable<T: Strategy>(&(weight, ref option): &WA<T>) -> bool {
+    0 == weight || option.as_enumerable().is_some()
+}
+
+/// Returns the number of values enumerated for the option.
+fn option_size<T: Strategy>(&(weight, ref option): &WA<T>) -> Option<u64> {
+    if 0 == weight {
+        Some(0)
+    } else {
+        domain_size(&**option)
+    }
+}
+
+/// Returns the value tree for `value` made by the option, unless it has a
+/// weight of 0 or cannot make one.
+fn option_tree_from_value<T: Strategy>(
+    &(weight, ref option): &WA<T>,
+    value: &T::Value,
+    runner: &mut TestRunner,
+) -> Option<T::Tree> {
+    if 0 == weight {
+        None
+    } else {
+        option.tree_from_value(value, runner).ok()
+    }
+}
+
 impl<T: Strategy> Strategy for Union<T> {
     type Tree = UnionValueTree<T>;
     type Value = T::Value;
@@ -153,6 +185,79 @@ impl<T: Strategy> Strategy for Union<T> {
             prev_pick: None,
         })
     }
+
+    fn as_enumerable(
+        &self,
+    ) -> Option<&dyn Enumerable<Tree = Self::Tree, Value = Self::Value>> {
+        if self.options.iter().all(option_enumerable) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn tree_from_value(
+        &self,
+        value: &Self::Value,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        // The first option which can make the value tree becomes the pick.
+        let (pick, tree) = self
+            .options
+            .iter()
+            .enumerate()
+            .find_map(|(pick, option)| {
+                Some((pick, option_tree_from_value(option, value, runner)?))
+            })
+            .ok_or("no option of the union can produce the value")?;
+
+        let mut options = Vec::with_capacity(pick);
+        for option in &self.options[0..pick] {
+            options.push(LazyValueTree::new(Arc::clone(&option.1), runner));
+        }
+        options.push(LazyValueTree::new_initialized(tree));
+
+        Ok(UnionValueTree {
+            options,
+            pick,
+            min_pick: 0,
+            prev_pick: None,
+        })
+    }
+}
+
+impl<T: Strategy> Enumerable for Union<T> {
+    fn domain_size(&self) -> Option<u64> {
+        self.options
+            .iter()
+            .try_fold(0u64, |sum, option| sum.checked_add(option_size(option)?))
+    }
+
+    fn enumerate_tree(
+        &self,
+        index: u64,
+        runner: &mut TestRunner,
+    ) -> NewTree<Self> {
+        let (pick, index) =
+            pick_enumerated(self.options.iter().map(option_size), index)?;
+
+        let mut options = Vec::with_capacity(pick);
+        for option in &self.options[0..pick] {
+            options.push(LazyValueTree::new(Arc::clone(&option.1), runner));
+        }
+        options.push(LazyValueTree::new_initialized(enumerate_tree(
+            &*self.options[pick].1,
+            index,
+            runner,
+        )?));
+
+        Ok(UnionValueTree {
+            options,
+            pick,
+            min_pick: 0,
+            prev_pick: None,
+        })
+    }
 }
 
 macro_rules! access_vec {
@@ -387,6 +492,112 @@ macro_rules! tuple_union {
                         } else {
                             None
                         }),*),
+                    pick,
+                    min_pick: 0,
+                    prev_pick: None,
+                })
+            }
+
+            fn as_enumerable(&self) -> Option<
+                &dyn Enumerable<Tree = Self::Tree, Value = Self::Value>>
+            {
+                if option_enumerable(&(self.0).0)
+                    $(&& option_enumerable(&(self.0).$ix))*
+                {
+                    Some(self)
+                } else {
+                    None
+                }
+            }
+
+            fn tree_from_value(&self, value: &Self::Value,
+                               runner: &mut TestRunner) -> NewTree<Self> {
+                // Each option is tried in turn until one can make the value
+                // tree, which becomes the pick.
+                let mut pick = None;
+                let trees = (
+                    match option_tree_from_value(
+                        &(self.0).0, value, runner)
+                    {
+                        Some(tree) => {
+                            pick = Some(0);
+                            Some(tree)
+                        },
+                        None => None,
+                    },
+                    $(
+                    if pick.is_some() {
+                        None
+                    } else {
+                        match option_tree_from_value(
+                            &(self.0).$ix, value, runner)
+                        {
+                            Some(tree) => {
+                                pick = Some($ix);
+                                Some(tree)
+                            },
+                            None => None,
+                        }
+                    }),*);
+                let pick = pick.ok_or(
+                    "no option of the union can produce the value")?;
+
+                Ok(TupleUnionValueTree {
+                    options: (
+                        match trees.0 {
+                            Some(tree) => LazyValueTree::new_initialized(tree),
+                            None => LazyValueTree::new(
+                                Arc::clone(&((self.0).0).1), runner),
+                        },
+                        $(
+                        match trees.$ix {
+                            Some(tree) =>
+                                Some(LazyValueTree::new_initialized(tree)),
+                            None if $ix < pick => Some(LazyValueTree::new(
+                                Arc::clone(&((self.0).$ix).1), runner)),
+                            None => None,
+                        }),*),
+                    pick,
+                    min_pick: 0,
+                    prev_pick: None,
+                })
+            }
+        }
+
+        impl<A : Strategy, $($gen: Strategy<Value = A::Value>),*>
+        Enumerable for TupleUnion<(WA<A>, $(WA<$gen>),*)> {
+            fn domain_size(&self) -> Option<u64> {
+                let sizes = [option_size(&(self.0).0),
+                             $(option_size(&(self.0).$ix)),*];
+                sizes.iter().try_fold(0u64, |sum, &size| sum.checked_add(size?))
+            }
+
+            fn enumerate_tree(&self, index: u64, runner: &mut TestRunner)
+                              -> NewTree<Self> {
+                let sizes = [option_size(&(self.0).0),
+                             $(option_size(&(self.0).$ix)),*];
+                let (pick, index) = pick_enumerated(sizes, index)?;
+
+                Ok(TupleUnionValueTree {
+                    options: (
+                        if 0 == pick {
+                            LazyValueTree::new_initialized(enumerate_tree(
+                                &*((self.0).0).1, index, runner)?)
+                        } else {
+                            LazyValueTree::new(
+                                Arc::clone(&((self.0).0).1), runner)
+                        },
+                        $(
+                        if $ix == pick {
+                            Some(LazyValueTree::new_initialized(
+                                enumerate_tree(
+                                    &*((self.0).$ix).1, index, runner)?))
+                        } else if $ix < pick {
+                            Some(LazyValueTree::new(
+                                    Arc::clone(&((self.0).$ix).1), runner))
+                        } else {
+                            None
+                        }),*),
                     pick: pick,
                     min_pick: 0,
                     prev_pick: None,
@@ -659,6 +870,56 @@ mod test {
         );
     }
 
+    #[test]
+    fn tree_from_value_picks_first_option_with_value() {
+        fn check<S: Strategy<Value = u32>>(input: S) {
+            let mut runner = TestRunner::deterministic();
+            assert!(input.tree_from_value(&25, &mut runner).is_err());
+
+            let case = input.tree_from_value(&45, &mut runner).unwrap();
+            assert_eq!(45, case.current());
+            let result = runner.run_one(case, |v| {
+                prop_assert!(v < 30);
+                Ok(())
+            });
+            match result {
+                Err(TestError::Fail(_, 30)) => (),
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        }
+
+        check(Union::new(vec![10u32..20, 30u32..50, 40u32..50]));
+        // `Just(25)` could make the value tree for 25, but is never picked
+        // with a weight of 0.
+        check(TupleUnion::new((
+            (1, Arc::new(10u32..20)),
+            (0, Arc::new(Just(25u32))),
+            (1, Arc::new(30u32..50)),
+        )));
+    }
+
+    #[test]
+    fn tree_from_value_checks_just_options() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Choice {
+            A,
+            B,
+        }
+
+        let mut runner = TestRunner::deterministic();
+        let input = prop_oneof![Just(Choice::A), Just(Choice::B)];
+        let case = input.tree_from_value(&Choice::B, &mut runner).unwrap();
+        assert_eq!(1, case.pick);
+        assert_eq!(Choice::B, case.current());
+
+        let input = prop_oneof![Just(0u32), 0..10u32];
+        assert!(input.tree_from_value(&99, &mut runner).is_err());
+        assert_eq!(
+            5,
+            input.tree_from_value(&5, &mut runner).unwrap().current()
+        );
+    }
+
     /// Test that unions work even if local filtering causes errors.
     #[test]
     fn test_filter_union_sanity() {
@@ -8,6 +8,9 @@
 // except according to those terms.
 
 use crate::std_facade::fmt;
+use crate::strategy::Strategy;
+use crate::test_runner::{TestCaseResult, TestError, TestRunner};
+use core::marker::PhantomData;
 
 /// Easily define `proptest` tests.
 ///
@@ -96,6 +99,36 @@ use crate::std_facade::fmt;
 /// # fn main() { test_addition(); }
 /// ```
 ///
+/// ## Async Tests
+///
+/// Test functions can also be `async fn`s, which can be mixed with normal
+/// ones in the same block. The future of each test case is run to
+/// completion by `Config::block_on`, which by default only works for futures
+/// that do not need a particular runtime. To test code which needs one, set
+/// it to a function which runs the future on that runtime instead. Failing
+/// cases are shrunk and persisted just as for normal tests.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// async fn add(a: u32, b: u32) -> u32 {
+///   a + b
+/// }
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   async fn test_async_addition(a in 0..10u32, b in 0..10u32) {
+///     prop_assert!(add(a, b).await <= 18);
+///   }
+/// }
+/// #
+/// # fn main() { test_async_addition(); }
+/// ```
+///
+/// This requires the `std` feature.
+///
 /// ## Closure-Style Invocation
 ///
 /// As of proptest 0.8.1, an alternative, "closure-style" invocation is
@@ -157,10 +190,11 @@ macro_rules! proptest {
         $(
             $(#[$meta])*
             fn $test_name() {
-                let mut config = $crate::test_runner::contextualize_config($config.clone());
+                let mut config = $config.clone();
                 config.test_name = Some(
                     concat!(module_path!(), "::", stringify!($test_name)));
-                $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] $body);
+                let mut config = $crate::test_runner::contextualize_config(config);
+                $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [fn] $body);
             }
         )*
     };
@@ -172,13 +206,47 @@ macro_rules! proptest {
         $(
             $(#[$meta])*
             fn $test_name() {
-                let mut config = $crate::test_runner::contextualize_config($config.clone());
+                let mut config = $config.clone();
                 config.test_name = Some(
                     concat!(module_path!(), "::", stringify!($test_name)));
-                $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] $body);
+                let mut config = $crate::test_runner::contextualize_config(config);
+                $crate::proptest_helper!(@_BODY2 config ($($arg)+) [fn] $body);
             }
         )*
     };
+    // A block containing `async fn`s is expanded one function at a time, so
+    // that they can be mixed with normal ones.
+    (#![proptest_config($config:expr)]
+     $(#[$meta:meta])*
+     async fn $test_name:ident($($arg:tt)+) $body:block
+     $($rest:tt)*) => {
+        $(#[$meta])*
+        fn $test_name() {
+            let mut config = $config.clone();
+            config.test_name = Some(
+                concat!(module_path!(), "::", stringify!($test_name)));
+            let mut config = $crate::test_runner::contextualize_config(config);
+            $crate::proptest_helper!(@_ASYNC_BODY config ($($arg)+) $body);
+        }
+        $crate::proptest! {
+            #![proptest_config($config)]
+            $($rest)*
+        }
+    };
+    (#![proptest_config($config:expr)]
+     $(#[$meta:meta])*
+     fn $test_name:ident($($arg:tt)+) $body:block
+     $($rest:tt)*) => {
+        $crate::proptest! {
+            #![proptest_config($config)]
+            $(#[$meta])*
+            fn $test_name($($arg)+) $body
+        }
+        $crate::proptest! {
+            #![proptest_config($config)]
+            $($rest)*
+        }
+    };
 
     ($(
         $(#[$meta:meta])*
@@ -198,6 +266,16 @@ macro_rules! proptest {
           fn $test_name($($arg)+) $body)*
     } };
 
+    ($(#[$meta:meta])* async fn $($rest:tt)*) => { $crate::proptest! {
+        #![proptest_config($crate::test_runner::Config::default())]
+        $(#[$meta])* async fn $($rest)*
+    } };
+
+    ($(#[$meta:meta])* fn $($rest:tt)*) => { $crate::proptest! {
+        #![proptest_config($crate::test_runner::Config::default())]
+        $(#[$meta])* fn $($rest)*
+    } };
+
     (|($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => {
         $crate::proptest!(
             $crate::test_runner::Config::default(),
@@ -274,6 +352,175 @@ macro_rules! prop_assume {
     };
 }
 
+/// Labels the current test case if a condition holds, so that the
+/// distribution of generated inputs can be inspected.
+///
+/// Used directly within a function defined with `proptest!` or in any
+/// function called from one.
+///
+/// This is invoked as `prop_classify!(condition, format, args...)`. If
+/// `condition` is true, the formatted label is attached to the current test
+/// case. Once all cases have passed, the percentage of passing cases carrying
+/// each label is included when the `TestRunner` is displayed, and printed to
+/// stderr if `Config::verbose` is at least 1 or a `prop_cover!` requirement
+/// was not met. Rejected cases and cases run while shrinking are not counted.
+///
+/// Labels are only recorded with the `std` feature (enabled by default).
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn reverse_twice(v in prop::collection::vec(0u8..4, 0..4)) {
+///     prop_classify!(v.is_empty(), "empty");
+///     prop_classify!(v.len() > 2, "long");
+///     let mut w = v.clone();
+///     w.reverse();
+///     w.reverse();
+///     prop_assert_eq!(v, w);
+///   }
+/// }
+/// #
+/// # fn main() { reverse_twice(); }
+/// ```
+#[macro_export]
+macro_rules! prop_classify {
+    ($cond:expr, $($fmt:tt)*) => {
+        if $cond {
+            $crate::sugar::record_label(&format_args!($($fmt)*));
+        }
+    };
+}
+
+/// Labels the current test case with a value, so that the distribution of
+/// that value across the run can be inspected.
+///
+/// This is invoked either as `prop_collect!(value)`, which uses the `Debug`
+/// representation of `value` as the label, or as
+/// `prop_collect!(value, bucket)`, which groups numeric values into buckets
+/// of width `bucket` and labels the case with the bucket's range, e.g.
+/// `10..20`.
+///
+/// Labels are reported in the same way as for `prop_classify!`.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn sorted_is_idempotent(mut v in prop::collection::vec(any::<u8>(), 0..100)) {
+///     prop_collect!(v.len(), 10);
+///     v.sort();
+///     let w = v.clone();
+///     v.sort();
+///     prop_assert_eq!(v, w);
+///   }
+/// }
+/// #
+/// # fn main() { sorted_is_idempotent(); }
+/// ```
+#[macro_export]
+macro_rules! prop_collect {
+    ($value:expr) => {
+        $crate::sugar::record_label(&format_args!("{:?}", $value));
+    };
+
+    ($value:expr, $bucket:expr) => {{
+        let value = $value;
+        let bucket = $bucket;
+        let low = value - value.rem_euclid(bucket);
+        $crate::sugar::record_label(&format_args!("{}..{}", low, low + bucket));
+    }};
+}
+
+/// Requires a minimum percentage of test cases to satisfy a condition.
+///
+/// This is invoked as `prop_cover!(condition, min_pct, format, args...)`. It
+/// labels the current case like `prop_classify!` does, and additionally
+/// fails the whole test with `TestError::Abort` if, once all cases have
+/// passed, fewer than `min_pct` percent of the passing cases carried the
+/// label.
+///
+/// This is useful for ensuring that a strategy actually exercises an
+/// interesting path often enough.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn division_by_small_numbers(a in 0u32..100, b in 1u32..10) {
+///     prop_cover!(b == 1, 5, "division by one");
+///     prop_assert!(a / b <= a);
+///   }
+/// }
+/// #
+/// # fn main() { division_by_small_numbers(); }
+/// ```
+#[macro_export]
+macro_rules! prop_cover {
+    ($cond:expr, $min_pct:expr, $($fmt:tt)*) => {
+        $crate::sugar::record_coverage(
+            $cond, $min_pct as f64, &format_args!($($fmt)*));
+    };
+}
+
+/// Reports a score which proptest should try to maximise.
+///
+/// This is invoked as `prop_target!(score)`, where `score` is any numeric
+/// expression. Once part of the `cases` budget has been spent on random
+/// inputs (see `Config::target_percent`), the runner takes the input with the
+/// highest score and spends the remaining cases searching its neighbourhood
+/// for inputs which score even higher. This helps to reach failures that
+/// random generation alone rarely hits, such as very large outputs or deep
+/// recursion.
+///
+/// If invoked several times in one case, the highest score counts. Targeting
+/// is disabled when forking is enabled, and `TestRunner::run_parallel` does
+/// not perform it.
+///
+/// Inputs found by searching a neighbourhood are not generated from a seed
+/// of their own, so if one of them fails, it is not persisted and the failure
+/// says so.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn sum_stays_small(v in prop::collection::vec(0u32..100, 0..10)) {
+///     let sum: u32 = v.iter().sum();
+///     prop_target!(sum);
+///     prop_assert!(sum < 1000);
+///   }
+/// }
+/// #
+/// # fn main() { sum_stays_small(); }
+/// ```
+#[macro_export]
+macro_rules! prop_target {
+    ($score:expr) => {
+        $crate::sugar::record_target($score as f64);
+    };
+}
+
 /// Produce a strategy which picks one of the listed choices.
 ///
 /// This is conceptually equivalent to calling `prop_union` on the first two
@@ -964,19 +1211,22 @@ macro_rules! proptest_helper {
         $config.source_file = Some(file!());
         let mut runner = $crate::test_runner::TestRunner::new($config);
         let names = $crate::proptest_helper!(@_WRAPSTR ($($parm),*));
-        match runner.run(
-            &$crate::strategy::Strategy::prop_map(
-                $crate::proptest_helper!(@_WRAP ($($strategy)*)),
-                |values| $crate::sugar::NamedArguments(names, values)),
-            $($mod)* |$crate::sugar::NamedArguments(
-                _, $crate::proptest_helper!(@_WRAPPAT ($($parm),*)))|
-            {
-                let (): () = $body;
-                Ok(())
-            })
+        match $crate::strategy::Strategy::prop_map(
+            $crate::proptest_helper!(@_WRAP ($($strategy)*)),
+            |values| $crate::sugar::NamedArguments(names, values))
         {
-            Ok(()) => (),
-            Err(e) => panic!("{}\n{}", e, runner),
+            strategy => match $crate::proptest_helper!(
+                @_RUN runner strategy [$($mod)*]
+                |$crate::sugar::NamedArguments(
+                    _, $crate::proptest_helper!(@_WRAPPAT ($($parm),*)))|
+                {
+                    let (): () = $body;
+                    Ok(())
+                })
+            {
+                Ok(()) => (),
+                Err(e) => panic!("{}\n{}", e, runner),
+            }
         }
     }};
     // build a property testing block that when executed, executes the full property test.
@@ -984,16 +1234,57 @@ macro_rules! proptest_helper {
         $config.source_file = Some(file!());
         let mut runner = $crate::test_runner::TestRunner::new($config);
         let names = $crate::proptest_helper!(@_EXT _STR ($($arg)*));
-        match runner.run(
+        match $crate::strategy::Strategy::prop_map(
+            $crate::proptest_helper!(@_EXT _STRAT ($($arg)*)),
+            |values| $crate::sugar::NamedArguments(names, values))
+        {
+            strategy => match $crate::proptest_helper!(
+                @_RUN runner strategy [$($mod)*]
+                |$crate::sugar::NamedArguments(
+                    _, $crate::proptest_helper!(@_EXT _PAT ($($arg)*)))|
+                {
+                    let (): () = $body;
+                    Ok(())
+                })
+            {
+                Ok(()) => (),
+                Err(e) => panic!("{}\n{}", e, runner),
+            }
+        }
+    }};
+    // run the body of a function defined by `proptest!`, on
+    // `Config::threads` threads if its inputs allow it.
+    (@_RUN $runner:ident $strategy:ident [fn] $($test:tt)*) => {{
+        #[allow(unused_imports)]
+        use $crate::sugar::{RunTestInProcess as _, RunTestParallel as _};
+        let test = $crate::sugar::test_fn(&$strategy, $($test)*);
+        (&&$crate::sugar::RunTest::new(&$strategy))
+            .run_test(&mut $runner, &$strategy, test)
+    }};
+    // run the closure of a closure-style invocation, which may capture values
+    // which cannot be shared with other threads.
+    (@_RUN $runner:ident $strategy:ident [$($mod:tt)*] $($test:tt)*) => {
+        $runner.run(&$strategy, $($mod)* $($test)*)
+    };
+    // build a property testing block for an `async fn`, whose futures are run
+    // by `Config::block_on`.
+    (@_ASYNC_BODY $config:ident ($($arg:tt)+) $body:expr) => {{
+        $config.source_file = Some(file!());
+        let block_on = $config.block_on;
+        let mut runner = $crate::test_runner::TestRunner::new($config);
+        let names = $crate::proptest_helper!(@_EXT _STR ($($arg)*));
+        match runner.run_async(
             &$crate::strategy::Strategy::prop_map(
                 $crate::proptest_helper!(@_EXT _STRAT ($($arg)*)),
                 |values| $crate::sugar::NamedArguments(names, values)),
-            $($mod)* |$crate::sugar::NamedArguments(
-                _, $crate::proptest_helper!(@_EXT _PAT ($($arg)*)))|
-            {
+            // The values are moved into the future before being matched, so
+            // that `ref` patterns borrow from it.
+            |$crate::sugar::NamedArguments(_, values)| async move {
+                let $crate::proptest_helper!(@_EXT _PAT ($($arg)*)) = values;
                 let (): () = $body;
-                Ok(())
-            })
+                $crate::test_runner::TestCaseResult::Ok(())
+            },
+            |future| block_on($crate::std_facade::Box::pin(future)))
         {
             Ok(()) => (),
             Err(e) => panic!("{}\n{}", e, runner),
@@ -1161,6 +1452,114 @@ pub fn force_no_fork(config: &mut crate::test_runner::Config) {
 #[cfg(not(feature = "std"))]
 pub fn force_no_fork(_: &mut crate::test_runner::Config) {}
 
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub fn record_label(label: &dyn fmt::Display) {
+    crate::test_runner::statistics::record_label(label);
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+pub fn record_label(_: &dyn fmt::Display) {}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub fn record_coverage(cond: bool, min_pct: f64, label: &dyn fmt::Display) {
+    crate::test_runner::statistics::record_coverage(cond, min_pct, label);
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+pub fn record_coverage(_: bool, _: f64, _: &dyn fmt::Display) {}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub fn record_target(score: f64) {
+    crate::test_runner::statistics::record_target(score);
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+pub fn record_target(_: f64) {}
+
+/// The body of a function defined by `proptest!`.
+///
+/// Such a body cannot capture anything, so it is always `Send` and `Sync`,
+/// unlike the closures given to the closure-style invocations.
+#[doc(hidden)]
+pub type TestFn<S> = fn(<S as Strategy>::Value) -> TestCaseResult;
+
+/// Gives the body of a function defined by `proptest!` the argument type of
+/// `strategy`'s values.
+#[doc(hidden)]
+pub fn test_fn<S: Strategy>(_strategy: &S, test: TestFn<S>) -> TestFn<S> {
+    test
+}
+
+/// Chooses how `proptest!` runs the functions it defines.
+///
+/// This relies on method resolution preferring `RunTestParallel`, which
+/// needs one less auto-ref, whenever its `Send` bound holds, so it is invoked
+/// as `(&&RunTest::new(&strategy)).run_test(..)`.
+#[doc(hidden)]
+pub struct RunTest<S>(PhantomData<fn() -> S>);
+
+impl<S: Strategy> RunTest<S> {
+    #[doc(hidden)]
+    pub fn new(_strategy: &S) -> Self {
+        RunTest(PhantomData)
+    }
+}
+
+/// Runs the test with `TestRunner::run`.
+#[doc(hidden)]
+pub trait RunTestInProcess<S: Strategy> {
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        strategy: &S,
+        test: TestFn<S>,
+    ) -> Result<(), TestError<S::Value>>;
+}
+
+impl<S: Strategy> RunTestInProcess<S> for RunTest<S> {
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        strategy: &S,
+        test: TestFn<S>,
+    ) -> Result<(), TestError<S::Value>> {
+        runner.run(strategy, test)
+    }
+}
+
+/// Runs the test with `TestRunner::run_parallel`, which needs the test's
+/// inputs to be `Send`.
+#[doc(hidden)]
+pub trait RunTestParallel<S: Strategy> {
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        strategy: &S,
+        test: TestFn<S>,
+    ) -> Result<(), TestError<S::Value>>;
+}
+
+#[cfg(feature = "std")]
+impl<S: Strategy> RunTestParallel<S> for &RunTest<S>
+where
+    S::Value: Send,
+{
+    fn run_test(
+        &self,
+        runner: &mut TestRunner,
+        strategy: &S,
+        test: TestFn<S>,
+    ) -> Result<(), TestError<S::Value>> {
+        runner.run_parallel(strategy, test)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::strategy::Just;
@@ -1483,6 +1882,100 @@ mod test_timeout {
     }
 }
 
+#[cfg(all(test, feature = "std"))]
+mod async_tests {
+    use std::cell::Cell;
+
+    use crate::test_runner::{block_on, Config};
+
+    async fn add(a: u32, b: u32) -> u32 {
+        a + b
+    }
+
+    proptest! {
+        #[test]
+        async fn async_fn_body_is_awaited(a in 0u32..100, b: u8) {
+            let sum = add(a, u32::from(b)).await;
+            prop_assert!(sum >= a);
+        }
+
+        #[test]
+        fn sync_fn_mixed_with_async_fns(a in 0u32..100) {
+            prop_assert!(a < 100);
+        }
+
+        #[test]
+        async fn async_fn_after_sync_fn(ref s in "[a-z]*") {
+            prop_assert_eq!(s.len(), async { s.chars().count() }.await);
+        }
+    }
+
+    thread_local! {
+        static IN_CUSTOM_EXECUTOR: Cell<bool> = Cell::new(false);
+    }
+
+    proptest! {
+        #![proptest_config(Config {
+            block_on: |future| {
+                IN_CUSTOM_EXECUTOR.with(|flag| flag.set(true));
+                let result = block_on(future);
+                IN_CUSTOM_EXECUTOR.with(|flag| flag.set(false));
+                result
+            },
+            .. Config::default()
+        })]
+
+        #[test]
+        async fn async_fn_uses_configured_block_on(_ in 0u32..10) {
+            prop_assert!(IN_CUSTOM_EXECUTOR.with(|flag| flag.get()));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod thread_tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::strategy::Just;
+    use crate::test_runner::Config;
+
+    thread_local! {
+        static ON_TEST_THREAD: Cell<bool> = Cell::new(false);
+    }
+
+    proptest! {
+        #![proptest_config(Config {
+            threads: 4,
+            .. Config::default()
+        })]
+
+        fn runs_on_worker_threads(_: u32) {
+            prop_assert!(!ON_TEST_THREAD.with(Cell::get));
+        }
+
+        #[test]
+        fn non_send_inputs_run_on_test_thread(x in Just(Rc::new(1u32))) {
+            prop_assert_eq!(1, *x);
+        }
+    }
+
+    #[test]
+    fn functions_run_on_config_threads() {
+        ON_TEST_THREAD.with(|flag| flag.set(true));
+        runs_on_worker_threads();
+    }
+
+    #[test]
+    fn closures_run_on_test_thread() {
+        let not_sync = Rc::new(Cell::new(0u32));
+        proptest!(Config { threads: 4, .. Config::default() }, |(_ in 0u32..100)| {
+            not_sync.set(not_sync.get() + 1);
+        });
+        assert!(not_sync.get() > 0);
+    }
+}
+
 #[cfg(test)]
 mod another_test {
     use crate::sugar;
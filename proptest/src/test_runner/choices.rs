@@ -0,0 +1,105 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Mutations used to shrink the choice sequence (the bytes drawn from the
+//! RNG) which generated a failing test case.
+
+use crate::std_facade::Vec;
+
+/// The ways in which a block of a choice sequence can be simplified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ChoicePass {
+    /// Remove the block entirely.
+    Delete,
+    /// Replace every byte in the block with 0.
+    Zero,
+    /// Sort the bytes in the block in ascending order.
+    Sort,
+}
+
+/// Every pass tried by the shrinker, in order, along with the block sizes
+/// they are applied to.
+///
+/// Larger blocks come first since they make the most progress per test run,
+/// and 4 and 8 bytes are what `next_u32` and `next_u64` draw at a time.
+pub(crate) const CHOICE_PASSES: &[(ChoicePass, usize)] = &[
+    (ChoicePass::Delete, 8),
+    (ChoicePass::Delete, 4),
+    (ChoicePass::Delete, 2),
+    (ChoicePass::Delete, 1),
+    (ChoicePass::Zero, 8),
+    (ChoicePass::Zero, 4),
+    (ChoicePass::Zero, 2),
+    (ChoicePass::Zero, 1),
+    (ChoicePass::Sort, 8),
+    (ChoicePass::Sort, 4),
+    (ChoicePass::Sort, 2),
+];
+
+/// Apply `pass` to the `block` bytes of `choices` starting at `at`.
+///
+/// Any result is shorter than or lexicographically smaller than `choices`.
+/// Returns `None` if the block extends past the end of `choices` or if the
+/// pass would not change anything.
+pub(crate) fn mutate_choices(
+    choices: &[u8],
+    pass: ChoicePass,
+    block: usize,
+    at: usize,
+) -> Option<Vec<u8>> {
+    let end = at.checked_add(block).filter(|&end| end <= choices.len())?;
+    let mut candidate = choices.to_vec();
+    match pass {
+        ChoicePass::Delete => {
+            candidate.drain(at..end);
+        }
+        ChoicePass::Zero => {
+            if choices[at..end].iter().all(|&b| 0 == b) {
+                return None;
+            }
+            for b in &mut candidate[at..end] {
+                *b = 0;
+            }
+        }
+        ChoicePass::Sort => {
+            candidate[at..end].sort_unstable();
+            if candidate[..] == *choices {
+                return None;
+            }
+        }
+    }
+
+    Some(candidate)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mutations_only_returned_when_they_change_something() {
+        let choices = [3, 0, 0, 2, 1];
+
+        assert_eq!(
+            Some(vec![3, 1]),
+            mutate_choices(&choices, ChoicePass::Delete, 3, 1)
+        );
+        assert_eq!(None, mutate_choices(&choices, ChoicePass::Delete, 3, 3));
+        assert_eq!(
+            Some(vec![0, 0, 0, 2, 1]),
+            mutate_choices(&choices, ChoicePass::Zero, 2, 0)
+        );
+        assert_eq!(None, mutate_choices(&choices, ChoicePass::Zero, 2, 1));
+        assert_eq!(
+            Some(vec![3, 0, 0, 1, 2]),
+            mutate_choices(&choices, ChoicePass::Sort, 2, 3)
+        );
+        assert_eq!(None, mutate_choices(&choices, ChoicePass::Sort, 2, 1));
+    }
+}
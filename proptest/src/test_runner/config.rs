@@ -13,17 +13,34 @@ use core::u32;
 #[cfg(feature = "std")]
 use std::env;
 #[cfg(feature = "std")]
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 #[cfg(feature = "std")]
 use std::fmt;
 #[cfg(feature = "std")]
+use std::path::PathBuf;
+#[cfg(feature = "std")]
 use std::str::FromStr;
 
+#[cfg(feature = "std")]
+use crate::std_facade::String;
+#[cfg(feature = "std")]
+use crate::test_runner::config_file::{ConfigFile, Setting, CONFIG_FILE};
+#[cfg(feature = "std")]
+use crate::test_runner::executor::{block_on, BoxedTestFuture};
 use crate::test_runner::result_cache::{noop_result_cache, ResultCache};
 use crate::test_runner::rng::RngAlgorithm;
 use crate::test_runner::FailurePersistence;
 #[cfg(feature = "std")]
 use crate::test_runner::FileFailurePersistence;
+use crate::test_runner::PersistedSeed;
+#[cfg(feature = "std")]
+use crate::test_runner::ReportFormat;
+#[cfg(feature = "std")]
+use crate::test_runner::RunObserver;
+#[cfg(feature = "std")]
+use crate::test_runner::TestCaseResult;
+#[cfg(feature = "serde")]
+use crate::test_runner::ValuePersistence;
 
 #[cfg(feature = "std")]
 const CASES: &str = "PROPTEST_CASES";
@@ -36,108 +53,329 @@ const MAX_FLAT_MAP_REGENS: &str = "PROPTEST_MAX_FLAT_MAP_REGENS";
 #[cfg(feature = "std")]
 const MAX_SHRINK_TIME: &str = "PROPTEST_MAX_SHRINK_TIME";
 #[cfg(feature = "std")]
+const MAX_RUN_TIME: &str = "PROPTEST_MAX_RUN_TIME";
+#[cfg(feature = "std")]
+const MIN_CASES: &str = "PROPTEST_MIN_CASES";
+#[cfg(feature = "std")]
+const RAMP_SIZE: &str = "PROPTEST_RAMP_SIZE";
+#[cfg(feature = "std")]
 const MAX_SHRINK_ITERS: &str = "PROPTEST_MAX_SHRINK_ITERS";
+#[cfg(feature = "std")]
+const CHOICE_SHRINKING: &str = "PROPTEST_CHOICE_SHRINKING";
+#[cfg(feature = "std")]
+const FLAKY_RERUNS: &str = "PROPTEST_FLAKY_RERUNS";
+#[cfg(feature = "std")]
+const MAX_DISTINCT_FAILURES: &str = "PROPTEST_MAX_DISTINCT_FAILURES";
 #[cfg(feature = "fork")]
 const FORK: &str = "PROPTEST_FORK";
 #[cfg(feature = "timeout")]
 const TIMEOUT: &str = "PROPTEST_TIMEOUT";
+#[cfg(feature = "fork")]
+const MEMORY_LIMIT: &str = "PROPTEST_MEMORY_LIMIT";
+#[cfg(feature = "fork")]
+const CPU_TIME_LIMIT: &str = "PROPTEST_CPU_TIME_LIMIT";
+#[cfg(feature = "fork")]
+const OPEN_FILES_LIMIT: &str = "PROPTEST_OPEN_FILES_LIMIT";
+#[cfg(feature = "fork")]
+const OUTPUT_LIMIT: &str = "PROPTEST_OUTPUT_LIMIT";
+#[cfg(feature = "std")]
+const TARGET_PERCENT: &str = "PROPTEST_TARGET_PERCENT";
+#[cfg(feature = "std")]
+const EDGE_CASE_PERCENT: &str = "PROPTEST_EDGE_CASE_PERCENT";
+#[cfg(feature = "std")]
+const EXHAUSTIVE_THRESHOLD: &str = "PROPTEST_EXHAUSTIVE_THRESHOLD";
+#[cfg(feature = "std")]
+const THREADS: &str = "PROPTEST_THREADS";
+#[cfg(feature = "std")]
+const WATCHDOG_TIMEOUT: &str = "PROPTEST_WATCHDOG_TIMEOUT";
 #[cfg(feature = "std")]
 const VERBOSE: &str = "PROPTEST_VERBOSE";
 #[cfg(feature = "std")]
 const RNG_ALGORITHM: &str = "PROPTEST_RNG_ALGORITHM";
 #[cfg(feature = "std")]
+const SEED: &str = "PROPTEST_SEED";
+#[cfg(feature = "std")]
+const REPORT_DIR: &str = "PROPTEST_REPORT_DIR";
+#[cfg(feature = "std")]
+const SHRINK_TRACE_DIR: &str = "PROPTEST_SHRINK_TRACE_DIR";
+#[cfg(feature = "std")]
+const REPORT_FORMAT: &str = "PROPTEST_REPORT_FORMAT";
+#[cfg(feature = "std")]
 const DISABLE_FAILURE_PERSISTENCE: &str =
     "PROPTEST_DISABLE_FAILURE_PERSISTENCE";
 
-/// Override the config fields from environment variables, if any are set.
-/// Without the `std` feature this function returns config unchanged.
+/// Where a setting applied by `contextualize_config` comes from, for use in
+/// warnings about it.
 #[cfg(feature = "std")]
-pub fn contextualize_config(mut result: Config) -> Config {
-    fn parse_or_warn<T: FromStr + fmt::Display>(
-        src: &OsString,
-        dst: &mut T,
-        typ: &str,
-        var: &str,
-    ) {
-        if let Some(src) = src.to_str() {
-            if let Ok(value) = src.parse() {
-                *dst = value;
-            } else {
-                eprintln!(
-                    "proptest: The env-var {}={} can't be parsed as {}, \
-                     using default of {}.",
-                    var, src, typ, *dst
-                );
-            }
+enum SettingSource<'a> {
+    Env(&'a str),
+    File(&'a ConfigFile, &'a Setting),
+}
+
+#[cfg(feature = "std")]
+impl<'a> SettingSource<'a> {
+    /// Describe the setting along with its `value`.
+    fn describe(&self, value: &dyn fmt::Display) -> String {
+        match *self {
+            SettingSource::Env(var) => format!("env-var {}={}", var, value),
+            SettingSource::File(file, setting) => format!(
+                "setting {}={} at {}:{}",
+                setting.key,
+                value,
+                file.path.display(),
+                setting.line
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> fmt::Display for SettingSource<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SettingSource::Env(var) => write!(f, "env-var {}", var),
+            SettingSource::File(file, setting) => write!(
+                f,
+                "setting {} at {}:{}",
+                setting.key,
+                file.path.display(),
+                setting.line
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn parse_or_warn<T: FromStr + fmt::Display>(
+    src: &OsStr,
+    dst: &mut T,
+    typ: &str,
+    source: &SettingSource,
+) {
+    if let Some(src) = src.to_str() {
+        if let Ok(value) = src.parse() {
+            *dst = value;
         } else {
             eprintln!(
-                "proptest: The env-var {} is not valid, using \
-                 default of {}.",
-                var, *dst
+                "proptest: The {} can't be parsed as {}, using default of {}.",
+                source.describe(&src),
+                typ,
+                *dst
             );
         }
+    } else {
+        eprintln!(
+            "proptest: The {} is not valid, using default of {}.",
+            source, *dst
+        );
     }
+}
 
-    result.failure_persistence =
-        Some(Box::new(FileFailurePersistence::default()));
-    for (var, value) in
-        env::vars_os().filter_map(|(k, v)| k.into_string().ok().map(|k| (k, v)))
-    {
-        match var.as_str() {
-            CASES => parse_or_warn(&value, &mut result.cases, "u32", CASES),
-            MAX_LOCAL_REJECTS => parse_or_warn(
-                &value,
-                &mut result.max_local_rejects,
-                "u32",
-                MAX_LOCAL_REJECTS,
-            ),
-            MAX_GLOBAL_REJECTS => parse_or_warn(
-                &value,
-                &mut result.max_global_rejects,
-                "u32",
-                MAX_GLOBAL_REJECTS,
-            ),
-            MAX_FLAT_MAP_REGENS => parse_or_warn(
-                &value,
-                &mut result.max_flat_map_regens,
-                "u32",
-                MAX_FLAT_MAP_REGENS,
+/// Set the config field named by the environment variable `var` to `value`.
+#[cfg(feature = "std")]
+fn apply_setting(
+    config: &mut Config,
+    var: &str,
+    value: &OsStr,
+    source: &SettingSource,
+) {
+    match var {
+        CASES => parse_or_warn(value, &mut config.cases, "u32", source),
+        MAX_LOCAL_REJECTS => {
+            parse_or_warn(value, &mut config.max_local_rejects, "u32", source)
+        }
+        MAX_GLOBAL_REJECTS => {
+            parse_or_warn(value, &mut config.max_global_rejects, "u32", source)
+        }
+        MAX_FLAT_MAP_REGENS => {
+            parse_or_warn(value, &mut config.max_flat_map_regens, "u32", source)
+        }
+        #[cfg(feature = "fork")]
+        FORK => parse_or_warn(value, &mut config.fork, "bool", source),
+        #[cfg(feature = "timeout")]
+        TIMEOUT => parse_or_warn(value, &mut config.timeout, "timeout", source),
+        #[cfg(feature = "fork")]
+        MEMORY_LIMIT => {
+            parse_or_warn(value, &mut config.memory_limit, "u32", source)
+        }
+        #[cfg(feature = "fork")]
+        CPU_TIME_LIMIT => {
+            parse_or_warn(value, &mut config.cpu_time_limit, "u32", source)
+        }
+        #[cfg(feature = "fork")]
+        OPEN_FILES_LIMIT => {
+            parse_or_warn(value, &mut config.open_files_limit, "u32", source)
+        }
+        #[cfg(feature = "fork")]
+        OUTPUT_LIMIT => {
+            parse_or_warn(value, &mut config.output_limit, "u32", source)
+        }
+        MAX_SHRINK_TIME => {
+            parse_or_warn(value, &mut config.max_shrink_time, "u32", source)
+        }
+        MAX_RUN_TIME => {
+            parse_or_warn(value, &mut config.max_run_time, "u32", source)
+        }
+        MIN_CASES => parse_or_warn(value, &mut config.min_cases, "u32", source),
+        RAMP_SIZE => {
+            parse_or_warn(value, &mut config.ramp_size, "bool", source)
+        }
+        MAX_SHRINK_ITERS => {
+            parse_or_warn(value, &mut config.max_shrink_iters, "u32", source)
+        }
+        CHOICE_SHRINKING => {
+            parse_or_warn(value, &mut config.choice_shrinking, "bool", source)
+        }
+        FLAKY_RERUNS => {
+            parse_or_warn(value, &mut config.flaky_reruns, "u32", source)
+        }
+        MAX_DISTINCT_FAILURES => parse_or_warn(
+            value,
+            &mut config.max_distinct_failures,
+            "u32",
+            source,
+        ),
+        TARGET_PERCENT => {
+            parse_or_warn(value, &mut config.target_percent, "u32", source)
+        }
+        EDGE_CASE_PERCENT => {
+            parse_or_warn(value, &mut config.edge_case_percent, "u32", source)
+        }
+        EXHAUSTIVE_THRESHOLD => parse_or_warn(
+            value,
+            &mut config.exhaustive_threshold,
+            "u32",
+            source,
+        ),
+        THREADS => parse_or_warn(value, &mut config.threads, "u32", source),
+        WATCHDOG_TIMEOUT => {
+            parse_or_warn(value, &mut config.watchdog_timeout, "u32", source)
+        }
+        VERBOSE => parse_or_warn(value, &mut config.verbose, "u32", source),
+        RNG_ALGORITHM => parse_or_warn(
+            value,
+            &mut config.rng_algorithm,
+            "RngAlgorithm",
+            source,
+        ),
+        SEED => match value.to_str().and_then(|s| s.parse().ok()) {
+            Some(seed) => config.rng_seed = Some(seed),
+            None => eprintln!(
+                "proptest: The {} can't be parsed as a seed, ignoring it.",
+                source.describe(&format_args!("{:?}", value))
             ),
-            #[cfg(feature = "fork")]
-            FORK => parse_or_warn(&value, &mut result.fork, "bool", FORK),
-            #[cfg(feature = "timeout")]
-            TIMEOUT => {
-                parse_or_warn(&value, &mut result.timeout, "timeout", TIMEOUT)
+        },
+        REPORT_DIR => config.report_dir = Some(PathBuf::from(value)),
+        SHRINK_TRACE_DIR => {
+            config.shrink_trace_dir = Some(PathBuf::from(value))
+        }
+        REPORT_FORMAT => parse_or_warn(
+            value,
+            &mut config.report_format,
+            "ReportFormat",
+            source,
+        ),
+        DISABLE_FAILURE_PERSISTENCE => {
+            config.failure_persistence = None;
+            #[cfg(feature = "serde")]
+            {
+                config.value_persistence = None;
             }
-            MAX_SHRINK_TIME => parse_or_warn(
-                &value,
-                &mut result.max_shrink_time,
-                "u32",
-                MAX_SHRINK_TIME,
-            ),
-            MAX_SHRINK_ITERS => parse_or_warn(
-                &value,
-                &mut result.max_shrink_iters,
-                "u32",
-                MAX_SHRINK_ITERS,
-            ),
-            VERBOSE => {
-                parse_or_warn(&value, &mut result.verbose, "u32", VERBOSE)
+        }
+        _ => {
+            if var.starts_with("PROPTEST_") {
+                eprintln!("proptest: Ignoring unknown {}.", source);
             }
-            RNG_ALGORITHM => parse_or_warn(
-                &value,
-                &mut result.rng_algorithm,
-                "RngAlgorithm",
-                RNG_ALGORITHM,
-            ),
-            DISABLE_FAILURE_PERSISTENCE => result.failure_persistence = None,
+        }
+    }
+}
 
-            _ => {
-                if var.starts_with("PROPTEST_") {
-                    eprintln!("proptest: Ignoring unknown env-var {}.", var);
+/// Apply `settings`, which were read from `file`, to `config`.
+///
+/// The keys are the names of the environment variables without the
+/// `PROPTEST_` prefix, in lower case.
+#[cfg(feature = "std")]
+fn apply_file_settings<'a>(
+    config: &mut Config,
+    file: &ConfigFile,
+    settings: impl IntoIterator<Item = &'a Setting>,
+) {
+    for setting in settings {
+        let var = format!("PROPTEST_{}", setting.key.to_ascii_uppercase());
+        let mut value = OsString::from(&setting.value);
+        match var.as_str() {
+            // Unlike the environment variable, this can be turned off again,
+            // such as for particular tests.
+            DISABLE_FAILURE_PERSISTENCE if "false" == setting.value => continue,
+            // Relative paths are relative to the file rather than to the
+            // directory the tests run in.
+            REPORT_DIR | SHRINK_TRACE_DIR => {
+                if let Some(dir) = file.path.parent() {
+                    value = dir.join(&setting.value).into();
                 }
             }
+            _ => (),
         }
+        apply_setting(
+            config,
+            &var,
+            &value,
+            &SettingSource::File(file, setting),
+        );
+    }
+}
+
+/// Apply the settings of the `[test."<glob>"]` tables in `file` which match
+/// the name of the test `config` is for.
+#[cfg(feature = "std")]
+fn apply_test_settings(config: &mut Config, file: &ConfigFile) {
+    if let Some(test_name) = config.test_name {
+        apply_file_settings(config, file, file.test_settings(test_name));
+    }
+}
+
+/// Override the config fields from the `proptest.toml` file and environment
+/// variables, if there are any. Without the `std` feature this function
+/// returns config unchanged.
+///
+/// The file is the first one named `proptest.toml` found in the directory
+/// given by the `CARGO_MANIFEST_DIR` environment variable, which Cargo sets
+/// to the directory of the crate being tested, or one of its ancestors. It
+/// can set any field that has an environment variable, using the name of
+/// that variable without the `PROPTEST_` prefix, in lower case, as the key.
+/// Settings in a `[test."<glob>"]` table only apply when `config.test_name`
+/// matches the glob, in which `*` matches any sequence of characters and `?`
+/// any one character. For example:
+///
+/// ```toml
+/// cases = 512
+/// max_shrink_time = 10000
+///
+/// [test."*::fuzz_*"]
+/// cases = 10000
+/// ```
+///
+/// Settings outside of any table are defaults: they are part of
+/// `Config::default()`, so they only apply to the fields `config` leaves at
+/// their default. Settings in matching `[test."<glob>"]` tables take
+/// precedence over the fields of `config`, and environment variables over
+/// all of these.
+#[cfg(feature = "std")]
+pub fn contextualize_config(mut result: Config) -> Config {
+    result.failure_persistence =
+        Some(Box::new(FileFailurePersistence::default()));
+    #[cfg(feature = "serde")]
+    {
+        result.value_persistence =
+            Some(Box::new(FileFailurePersistence::default()));
+    }
+    if let Some(ref file) = *CONFIG_FILE {
+        apply_test_settings(&mut result, file);
+    }
+    for (var, value) in
+        env::vars_os().filter_map(|(k, v)| k.into_string().ok().map(|k| (k, v)))
+    {
+        apply_setting(&mut result, &var, &value, &SettingSource::Env(&var));
     }
 
     result
@@ -156,33 +394,74 @@ fn default_default_config() -> Config {
         max_global_rejects: 1024,
         max_flat_map_regens: 1_000_000,
         failure_persistence: None,
+        #[cfg(feature = "serde")]
+        value_persistence: None,
         source_file: None,
         test_name: None,
         #[cfg(feature = "fork")]
         fork: false,
         #[cfg(feature = "timeout")]
         timeout: 0,
+        #[cfg(feature = "fork")]
+        memory_limit: 0,
+        #[cfg(feature = "fork")]
+        cpu_time_limit: 0,
+        #[cfg(feature = "fork")]
+        open_files_limit: 0,
+        #[cfg(feature = "fork")]
+        output_limit: 0,
         #[cfg(feature = "std")]
         max_shrink_time: 0,
+        #[cfg(feature = "std")]
+        max_run_time: 0,
+        #[cfg(feature = "std")]
+        min_cases: 0,
+        ramp_size: true,
         max_shrink_iters: u32::MAX,
+        choice_shrinking: false,
+        flaky_reruns: 0,
+        max_distinct_failures: 1,
         result_cache: noop_result_cache,
         #[cfg(feature = "std")]
+        block_on,
+        target_percent: 50,
+        edge_case_percent: 0,
+        exhaustive_threshold: 256,
+        #[cfg(feature = "std")]
+        threads: 1,
+        #[cfg(feature = "std")]
+        watchdog_timeout: 0,
+        #[cfg(feature = "std")]
         verbose: 0,
         rng_algorithm: RngAlgorithm::default(),
+        rng_seed: None,
+        #[cfg(feature = "std")]
+        report_dir: None,
+        #[cfg(feature = "std")]
+        shrink_trace_dir: None,
+        #[cfg(feature = "std")]
+        report_format: ReportFormat::JsonLines,
+        #[cfg(feature = "std")]
+        observer: None,
         _non_exhaustive: (),
     }
 }
 
-// The default config, computed by combining environment variables and
-// defaults.
+// The default config, computed by combining environment variables, the
+// settings outside of any table in the config file, and defaults.
 #[cfg(feature = "std")]
 lazy_static! {
-    static ref DEFAULT_CONFIG: Config =
-        contextualize_config(default_default_config());
+    static ref DEFAULT_CONFIG: Config = {
+        let mut config = default_default_config();
+        if let Some(ref file) = *CONFIG_FILE {
+            apply_file_settings(&mut config, file, file.global_settings());
+        }
+        contextualize_config(config)
+    };
 }
 
 /// Configuration for how a proptest test should be run.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Config {
     /// The number of successful test cases that must execute for the test as a
     /// whole to pass.
@@ -238,6 +517,22 @@ pub struct Config {
     /// default.)
     pub failure_persistence: Option<Box<dyn FailurePersistence>>,
 
+    /// Indicates whether and how to persist the values of failed test cases.
+    ///
+    /// This is only used by `TestRunner::run_with_value_persistence`, which
+    /// tests the persisted values before any other case. Seeds are still
+    /// persisted through `failure_persistence` as well.
+    ///
+    /// The default is `Some(Box::new(FileFailurePersistence::default()))`,
+    /// which stores the values next to the persisted seeds. It is disabled
+    /// along with `failure_persistence` by the
+    /// `PROPTEST_DISABLE_FAILURE_PERSISTENCE` environment variable.
+    ///
+    /// This requires the "serde" feature.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub value_persistence: Option<Box<dyn ValuePersistence>>,
+
     /// File location of the current test, relevant for persistence
     /// and debugging.
     ///
@@ -251,7 +546,9 @@ pub struct Config {
     /// The fully-qualified name of the test being run, as would be passed to
     /// the test executable to run just that test.
     ///
-    /// This must be set if `fork` is `true`. Otherwise, it is unused. It is
+    /// This must be set if `fork` is `true`. It is also passed to
+    /// `failure_persistence`, so that each test only replays the failures it
+    /// saved itself (plus any saved without a test name). It is
     /// automatically set by `proptest!`.
     ///
     /// This must include the crate name at the beginning, as produced by
@@ -299,11 +596,90 @@ pub struct Config {
     #[cfg_attr(docsrs, doc(cfg(feature = "timeout")))]
     pub timeout: u32,
 
+    /// If non-zero, tests are run in a subprocess whose address space is
+    /// limited to this number of MiB, so that a case which allocates more
+    /// fails with a message saying so, instead of exhausting the memory of
+    /// the machine.
+    ///
+    /// Like the other resource limits, this implicitly enables forking. The
+    /// limit applies to the whole subprocess, which also runs the test harness
+    /// and typically needs a few dozen MiB of address space for that alone.
+    /// Resource limits are only enforced on Linux.
+    ///
+    /// This requires the "fork" feature, enabled by default.
+    ///
+    /// The default is `0` (i.e., no limit), which can be overridden by setting
+    /// the `PROPTEST_MEMORY_LIMIT` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    #[cfg(feature = "fork")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
+    pub memory_limit: u32,
+
+    /// If non-zero, tests are run in a subprocess in which each case may use
+    /// at most this number of seconds of CPU time, after which it fails.
+    ///
+    /// Unlike `timeout`, this does not count time spent waiting. It also
+    /// counts any threads the case leaves running, and is only checked to the
+    /// second, so a case may get up to a second more. Like `memory_limit`,
+    /// this implicitly enables forking and is only enforced on Linux.
+    ///
+    /// This requires the "fork" feature, enabled by default.
+    ///
+    /// The default is `0` (i.e., no limit), which can be overridden by setting
+    /// the `PROPTEST_CPU_TIME_LIMIT` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    #[cfg(feature = "fork")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
+    pub cpu_time_limit: u32,
+
+    /// If non-zero, tests are run in a subprocess which may have at most this
+    /// number of files open at once.
+    ///
+    /// Opening more files fails with an error the test sees, rather than
+    /// ending the subprocess. The test harness needs a handful of files open
+    /// itself, so very low limits keep the subprocess from starting at all.
+    /// Like `memory_limit`, this implicitly enables forking and is only
+    /// enforced on Linux.
+    ///
+    /// This requires the "fork" feature, enabled by default.
+    ///
+    /// The default is `0` (i.e., no limit), which can be overridden by setting
+    /// the `PROPTEST_OPEN_FILES_LIMIT` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    #[cfg(feature = "fork")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
+    pub open_files_limit: u32,
+
+    /// If non-zero, tests are run in a subprocess which may write files of at
+    /// most this number of MiB, after which the case being run fails.
+    ///
+    /// This covers the output of the subprocess, which is captured in a file,
+    /// as well as the file it reports results to the parent through. Since the
+    /// subprocess runs many cases, both keep growing from one case to the
+    /// next, so the case which fails is not necessarily the one that wrote
+    /// the most. Like `memory_limit`, this implicitly enables forking and is
+    /// only enforced on Linux.
+    ///
+    /// This requires the "fork" feature, enabled by default.
+    ///
+    /// The default is `0` (i.e., no limit), which can be overridden by setting
+    /// the `PROPTEST_OUTPUT_LIMIT` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    #[cfg(feature = "fork")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
+    pub output_limit: u32,
+
     /// If non-zero, give up the shrinking process after this many milliseconds
     /// have elapsed since the start of the shrinking process.
     ///
     /// This will not cause currently running test cases to be interrupted.
     ///
+    /// When this is `0` but `max_run_time` is set, generating cases and
+    /// shrinking all of the failures found share the `max_run_time` budget
+    /// instead, so each shrink only gets whatever is left of it.
+    ///
     /// This configuration is only available when the `std` feature is enabled
     /// (which it is by default).
     ///
@@ -315,6 +691,67 @@ pub struct Config {
     #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
     pub max_shrink_time: u32,
 
+    /// If non-zero, keep generating new cases until this many milliseconds
+    /// have been spent on the run, instead of stopping after `cases` cases.
+    ///
+    /// If `max_shrink_time` is set, time spent shrinking failing cases does
+    /// not count towards this budget, since it is limited by
+    /// `max_shrink_time` instead, so a failure found late in the run still
+    /// gets shrunk, and with `max_distinct_failures`, shrinking one failure
+    /// does not use up the time to look for others. Otherwise, shrinking
+    /// counts towards this budget too and stops once it is used up, so the
+    /// whole run takes about this long. Cases which are already running when
+    /// the budget runs out are not interrupted.
+    ///
+    /// The number of cases which actually ran is reported at the end of the
+    /// run, and is included in the reports written to `report_dir`.
+    ///
+    /// This is ignored when forking, since the parent process could not
+    /// reproduce the decisions the child made based on time.
+    ///
+    /// This configuration is only available when the `std` feature is enabled
+    /// (which it is by default).
+    ///
+    /// The default is `0` (i.e., run `cases` cases), which can be overridden
+    /// by setting the `PROPTEST_MAX_RUN_TIME` environment variable. (The
+    /// variable is only considered when the `std` feature is enabled, which
+    /// it is by default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub max_run_time: u32,
+
+    /// The minimum number of cases to run when `max_run_time` is set, even if
+    /// the time budget runs out first.
+    ///
+    /// This configuration is only available when the `std` feature is enabled
+    /// (which it is by default).
+    ///
+    /// The default is `0`, which can be overridden by setting the
+    /// `PROPTEST_MIN_CASES` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub min_cases: u32,
+
+    /// Whether to start a run with small inputs and let them grow as it goes
+    /// on, rather than drawing them from the full range of sizes from the
+    /// first case.
+    ///
+    /// When enabled, `TestRunner::size` ramps up from close to 0 to 1 over
+    /// the first half of the run, as measured by `cases` or, if it is set, by
+    /// `max_run_time`. The sizes of collections, the repetitions of
+    /// `string_regex` and `bytes_regex` and the depth of `prop_recursive`
+    /// have the upper bound of their range scaled down by it. The second
+    /// half of the run uses the full ranges.
+    ///
+    /// The size a failing case was generated at is persisted along with its
+    /// seed, so that replaying the seed generates the same case.
+    ///
+    /// The default is `true`, which can be overridden by setting the
+    /// `PROPTEST_RAMP_SIZE` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub ramp_size: bool,
+
     /// Give up on shrinking if more than this number of iterations of the test
     /// code are run.
     ///
@@ -331,6 +768,74 @@ pub struct Config {
     /// considered when the `std` feature is enabled, which it is by default.)
     pub max_shrink_iters: u32,
 
+    /// Whether to shrink failing cases by minimising the sequence of random
+    /// bytes that generated them before shrinking their value trees.
+    ///
+    /// When enabled, the bytes each new case draws from the RNG are recorded.
+    /// If the case fails, the runner repeatedly deletes, zeroes and sorts
+    /// blocks of those bytes and regenerates the input from the result
+    /// through the strategy, keeping any change that still fails. This makes
+    /// shrinking effective for strategies whose value trees shrink poorly or
+    /// not at all, such as those built with `prop_flat_map`, `prop_filter`,
+    /// `prop_perturb` or custom `Strategy` implementations. The ordinary
+    /// value tree shrinking then continues from the smallest such input.
+    ///
+    /// Each attempt counts towards `max_shrink_iters` and `max_shrink_time`
+    /// in the same way as a value tree shrinking step, but the two phases are
+    /// limited separately.
+    ///
+    /// This has no effect when forking is enabled, when the RNG algorithm is
+    /// `RngAlgorithm::PassThrough`, or in `TestRunner::run_parallel` and
+    /// `TestRunner::run_one`.
+    ///
+    /// The default is `false`, which can be overridden by setting the
+    /// `PROPTEST_CHOICE_SHRINKING` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub choice_shrinking: bool,
+
+    /// How many times to re-run a failing case, and the minimal failing case
+    /// found by shrinking it, to check that they fail consistently.
+    ///
+    /// The original failing case is re-run before shrinking and the minimal
+    /// one after it, bypassing the result cache. If any of these runs passes
+    /// or is rejected, the test fails with `TestError::Flaky`, giving how
+    /// often each of them passed and failed, instead of `TestError::Fail`.
+    /// This is meant for tests which depend on things like the filesystem or
+    /// the clock, where a "minimal failing input" that does not fail again
+    /// would be misleading.
+    ///
+    /// Setting this value to `0` disables the check.
+    ///
+    /// The default is `0`, which can be overridden by setting the
+    /// `PROPTEST_FLAKY_RERUNS` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub flaky_reruns: u32,
+
+    /// The number of distinct failures to look for before ending the run.
+    ///
+    /// When this is greater than `1`, `TestRunner::run` keeps generating new
+    /// cases after a failure instead of returning it. Failures are told apart
+    /// by the location `prop_assert!` and friends put in their message or
+    /// where the test panicked, which is added to the message of a panic, or
+    /// by the first line of the message if it has no location. The first case
+    /// to fail in each such bucket is shrunk, without straying into a
+    /// different bucket, and its seed is persisted, while later cases in the
+    /// same bucket are skipped. Failing cases count towards `cases` just like
+    /// passing ones do.
+    ///
+    /// The run ends when `cases` cases have been run or this many distinct
+    /// failures have been found. If more than one was found, the test fails
+    /// with `TestError::MultipleFailures`, holding the minimal failing input
+    /// of each bucket in the order they were found.
+    ///
+    /// `TestRunner::run_parallel` falls back to `run` when this is enabled.
+    ///
+    /// The default is `1`, which stops at the first failure and can be
+    /// overridden by setting the `PROPTEST_MAX_DISTINCT_FAILURES` environment
+    /// variable. (The variable is only considered when the `std` feature is
+    /// enabled, which it is by default.)
+    pub max_distinct_failures: u32,
+
     /// A function to create new result caches.
     ///
     /// The default is to do no caching. The easiest way to enable caching is
@@ -349,6 +854,120 @@ pub struct Config {
     /// more slowly.
     pub result_cache: fn() -> Box<dyn ResultCache>,
 
+    /// The function which runs the futures of `async fn` tests in
+    /// `proptest!` to completion.
+    ///
+    /// The default is [`block_on`](fn.block_on.html), which works for futures
+    /// that do not need a particular runtime. Code which needs one, such as
+    /// code using Tokio's I/O or timers, can be tested by starting it here,
+    /// for example with `block_on: |future|
+    /// tokio::runtime::Runtime::new().unwrap().block_on(future)`.
+    ///
+    /// This requires the "std" feature.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub block_on: fn(BoxedTestFuture) -> TestCaseResult,
+
+    /// The percentage of `cases` to spend searching for inputs with a higher
+    /// `prop_target!` score.
+    ///
+    /// The remaining cases are generated at random as usual. Once they have
+    /// passed, if any of them reported a score, the rest of the budget is
+    /// spent exploring the neighbourhood of the best-scoring input. If no case
+    /// reports a score, all cases are generated at random regardless of this
+    /// setting. Values above 100 are treated as 100.
+    ///
+    /// Targeting is disabled when forking is enabled.
+    ///
+    /// The default is 50, which can be overridden by setting the
+    /// `PROPTEST_TARGET_PERCENT` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub target_percent: u32,
+
+    /// The percentage of values generated by integer strategies which are
+    /// picked from the edges of their range rather than uniformly.
+    ///
+    /// The edge values are the ends of the range and the values next to
+    /// them, `0`, `1`, `-1`, the type's `MIN` and `MAX`, and the powers of
+    /// two and their negations, as far as they lie within the range. Values
+    /// above 100 are treated as 100. At 0, integer strategies sample their
+    /// range uniformly.
+    ///
+    /// Any other value changes the values generated from a given seed, so
+    /// seeds persisted with edge cases turned off may no longer reproduce
+    /// their failures, and vice versa.
+    ///
+    /// The default is 0, which can be overridden by setting the
+    /// `PROPTEST_EDGE_CASE_PERCENT` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    pub edge_case_percent: u32,
+
+    /// The largest number of values a strategy may have for the test to be
+    /// run on every one of them instead of on random cases.
+    ///
+    /// This applies when the strategy implements `Enumerable`, such as a
+    /// tuple of `bool`s and small integer ranges, and has no more values than
+    /// `cases`. Each value is then tested exactly once, in order, and
+    /// `max_run_time` and targeting are ignored. Failures are still shrunk, but are not persisted, since they
+    /// will be found again by the next run anyway. Setting this to 0 disables
+    /// exhaustive testing.
+    ///
+    /// The default is 256, which can be overridden by setting the
+    /// `PROPTEST_EXHAUSTIVE_THRESHOLD` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    pub exhaustive_threshold: u32,
+
+    /// The number of threads on which to run test cases.
+    ///
+    /// Inputs are still generated on the calling thread, each from its own
+    /// seed derived from the runner's RNG, so the cases tried and the failure
+    /// reported do not depend on the number of threads. Only the test bodies
+    /// themselves are run concurrently.
+    ///
+    /// This is honoured by `TestRunner::run_parallel`, since running the test
+    /// on other threads requires it to be `Sync` and the input values to be
+    /// `Send`. Functions defined with `proptest!` use it whenever their inputs
+    /// are `Send`; `async fn`s and the closure-style invocations, which may
+    /// capture values that cannot be shared between threads, always run on
+    /// the calling thread. It is also ignored when forking is enabled. Values
+    /// of `0` and `1` run everything on the calling thread.
+    ///
+    /// This is only available with the `std` feature (enabled by default).
+    ///
+    /// The default is `1`, which can be overridden by setting the
+    /// `PROPTEST_THREADS` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub threads: u32,
+
+    /// If non-zero, each test case is run on a separate thread and fails if
+    /// it takes longer than this number of milliseconds, without needing to
+    /// fork.
+    ///
+    /// The thread running a case which overruns cannot be killed, so it is
+    /// left running in the background and the whole run ends with that case
+    /// as its failure, without shrinking it or running any further cases.
+    ///
+    /// This is only honoured by `TestRunner::run_with_watchdog`, since running
+    /// the test on another thread requires it and the input values to be
+    /// `Send` and `'static`. In particular, tests written with `proptest!`
+    /// never use it and ignore this setting; use `timeout` for them instead.
+    /// A warning is printed when a run ignores it. It is also ignored when
+    /// forking is enabled, in which case `timeout` can be used instead.
+    ///
+    /// This is only available with the `std` feature (enabled by default).
+    ///
+    /// The default is `0` (i.e., no timeout), which can be overridden by
+    /// setting the `PROPTEST_WATCHDOG_TIMEOUT` environment variable. (The
+    /// variable is only considered when the `std` feature is enabled, which it
+    /// is by default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub watchdog_timeout: u32,
+
     /// Set to non-zero values to cause proptest to emit human-targeted
     /// messages to stderr as it runs.
     ///
@@ -382,11 +1001,216 @@ pub struct Config {
     /// which it is by default.)
     pub rng_algorithm: RngAlgorithm,
 
+    /// The seed for `TestRunner::new` to start its RNG from, instead of a
+    /// random one.
+    ///
+    /// When a test fails, the seed which generated the failing case is
+    /// reported along with the failure, in the same format as the persistence
+    /// file, including the `# size: N` comment for cases generated below the
+    /// full size. Setting this to that seed makes the first new case of the
+    /// run be exactly that case again, for any `RngAlgorithm`, so the failure
+    /// can be reproduced without editing the persistence file. The algorithm
+    /// is the one the seed is for, regardless of `rng_algorithm`. Persisted
+    /// failures are still replayed before it.
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_SEED` environment variable to a seed as printed, such as
+    /// `PROPTEST_SEED="xs 1742513254 1290331240 2954473734 4125424227"`. (The
+    /// variable is only considered when the `std` feature is enabled, which it
+    /// is by default.)
+    pub rng_seed: Option<PersistedSeed>,
+
+    /// The directory to write a machine-readable report of every run to, if
+    /// any.
+    ///
+    /// Each report holds the test name, the outcome, the number of cases run,
+    /// the local and global rejections, the number of shrink iterations, the
+    /// elapsed time, and for failures, the seed of the failing case and the
+    /// `Debug` representation of the minimal failing value. See
+    /// `report_format` for how they are written. Reports are not written by
+    /// forked child processes.
+    ///
+    /// This is only available with the `std` feature (enabled by default).
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_REPORT_DIR` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub report_dir: Option<PathBuf>,
+
+    /// The format of the reports written to `report_dir`.
+    ///
+    /// This is only available with the `std` feature (enabled by default).
+    ///
+    /// The default is `ReportFormat::JsonLines`, which can be overridden by
+    /// setting the `PROPTEST_REPORT_FORMAT` environment variable to one of
+    /// the following:
+    ///
+    /// - `json` — `ReportFormat::JsonLines`
+    /// - `junit` — `ReportFormat::JUnit`
+    ///
+    /// (The variable is only considered when the `std` feature is enabled,
+    /// which it is by default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub report_format: ReportFormat,
+
+    /// The directory to write a trace of each shrinking of a failing case
+    /// to, if any.
+    ///
+    /// The trace records every step `TestRunner` takes while shrinking: the
+    /// `Debug` representation of the value tried, whether it was reached by
+    /// simplifying or complicating the previous one or regenerated from a
+    /// simpler choice sequence (see `choice_shrinking`), whether it passed,
+    /// failed or was rejected, whether it was kept as the new simplest
+    /// failure, and the time elapsed since shrinking began. It ends with why
+    /// shrinking stopped. This shows where shrinking got stuck, which the
+    /// "Test case failed" messages of `verbose` do not.
+    ///
+    /// Each trace is written as lines of JSON to a file named after the
+    /// test, the path of which is added to the reason of the resulting
+    /// `TestError`. When forking, the trace is written by the parent as it
+    /// replays the results from the child, so the times it records do not
+    /// reflect how long the test took.
+    ///
+    /// This is only available with the `std` feature (enabled by default).
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_SHRINK_TRACE_DIR` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub shrink_trace_dir: Option<PathBuf>,
+
+    /// An observer to notify of the events of every run, such as each case
+    /// being generated, passing, failing or being shrunk, and the run
+    /// finishing.
+    ///
+    /// See [`RunObserver`](trait.RunObserver.html) for the events and when
+    /// they occur.
+    ///
+    /// This is only available with the `std` feature (enabled by default).
+    ///
+    /// The default is `None`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub observer: Option<Box<dyn RunObserver>>,
+
     // Needs to be public so FRU syntax can be used.
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        // Function pointers have no meaningful identity, since a function may
+        // have several addresses and distinct functions may share one. So
+        // `block_on` is not compared, and `result_cache` only by address as
+        // the derived implementation used to.
+        let Config {
+            cases,
+            max_local_rejects,
+            max_global_rejects,
+            max_flat_map_regens,
+            failure_persistence,
+            #[cfg(feature = "serde")]
+            value_persistence,
+            source_file,
+            test_name,
+            #[cfg(feature = "fork")]
+            fork,
+            #[cfg(feature = "timeout")]
+            timeout,
+            #[cfg(feature = "fork")]
+            memory_limit,
+            #[cfg(feature = "fork")]
+            cpu_time_limit,
+            #[cfg(feature = "fork")]
+            open_files_limit,
+            #[cfg(feature = "fork")]
+            output_limit,
+            #[cfg(feature = "std")]
+            max_shrink_time,
+            #[cfg(feature = "std")]
+            max_run_time,
+            #[cfg(feature = "std")]
+            min_cases,
+            ramp_size,
+            max_shrink_iters,
+            choice_shrinking,
+            flaky_reruns,
+            max_distinct_failures,
+            result_cache,
+            #[cfg(feature = "std")]
+                block_on: _,
+            target_percent,
+            edge_case_percent,
+            exhaustive_threshold,
+            #[cfg(feature = "std")]
+            threads,
+            #[cfg(feature = "std")]
+            watchdog_timeout,
+            #[cfg(feature = "std")]
+            verbose,
+            rng_algorithm,
+            rng_seed,
+            #[cfg(feature = "std")]
+            report_dir,
+            #[cfg(feature = "std")]
+            report_format,
+            #[cfg(feature = "std")]
+            shrink_trace_dir,
+            #[cfg(feature = "std")]
+            observer,
+            _non_exhaustive: (),
+        } = self;
+        let same = *cases == other.cases
+            && *max_local_rejects == other.max_local_rejects
+            && *max_global_rejects == other.max_global_rejects
+            && *max_flat_map_regens == other.max_flat_map_regens
+            && *failure_persistence == other.failure_persistence
+            && *source_file == other.source_file
+            && *test_name == other.test_name
+            && *ramp_size == other.ramp_size
+            && *max_shrink_iters == other.max_shrink_iters
+            && *choice_shrinking == other.choice_shrinking
+            && *flaky_reruns == other.flaky_reruns
+            && *max_distinct_failures == other.max_distinct_failures
+            && *result_cache as usize == other.result_cache as usize
+            && *target_percent == other.target_percent
+            && *edge_case_percent == other.edge_case_percent
+            && *exhaustive_threshold == other.exhaustive_threshold
+            && *rng_algorithm == other.rng_algorithm
+            && *rng_seed == other.rng_seed;
+        #[cfg(feature = "serde")]
+        let same = same && *value_persistence == other.value_persistence;
+        #[cfg(feature = "fork")]
+        let same = same
+            && *fork == other.fork
+            && *memory_limit == other.memory_limit
+            && *cpu_time_limit == other.cpu_time_limit
+            && *open_files_limit == other.open_files_limit
+            && *output_limit == other.output_limit;
+        #[cfg(feature = "timeout")]
+        let same = same && *timeout == other.timeout;
+        #[cfg(feature = "std")]
+        let same = same
+            && *max_shrink_time == other.max_shrink_time
+            && *max_run_time == other.max_run_time
+            && *min_cases == other.min_cases
+            && *threads == other.threads
+            && *watchdog_timeout == other.watchdog_timeout
+            && *verbose == other.verbose
+            && *report_dir == other.report_dir
+            && *report_format == other.report_format
+            && *shrink_trace_dir == other.shrink_trace_dir
+            && *observer == other.observer;
+        same
+    }
+}
+
 impl Config {
     /// Constructs a `Config` only differing from the `default()` in the
     /// number of test cases required to pass the test successfully.
@@ -458,7 +1282,7 @@ impl Config {
     /// This method exists even if the "fork" feature is disabled, in which
     /// case it simply returns false.
     pub fn fork(&self) -> bool {
-        self._fork() || self.timeout() > 0
+        self._fork() || self.timeout() > 0 || self.has_resource_limits()
     }
 
     #[cfg(feature = "fork")]
@@ -471,6 +1295,19 @@ impl Config {
         false
     }
 
+    #[cfg(feature = "fork")]
+    fn has_resource_limits(&self) -> bool {
+        0 != self.memory_limit
+            || 0 != self.cpu_time_limit
+            || 0 != self.open_files_limit
+            || 0 != self.output_limit
+    }
+
+    #[cfg(not(feature = "fork"))]
+    fn has_resource_limits(&self) -> bool {
+        false
+    }
+
     /// Returns the configured timeout.
     ///
     /// This method exists even if the "timeout" feature is disabled, in which
@@ -489,6 +1326,19 @@ impl Config {
         0
     }
 
+    /// Returns the configured time budget for the run in milliseconds, or `0`
+    /// if the run is limited to `cases` cases instead.
+    ///
+    /// This takes into account that the budget is ignored when forking.
+    #[cfg(feature = "std")]
+    pub fn max_run_time(&self) -> u32 {
+        if self.fork() {
+            0
+        } else {
+            self.max_run_time
+        }
+    }
+
     /// Returns the configured limit on shrinking iterations.
     ///
     /// This takes into account the special "automatic" behaviour.
@@ -522,3 +1372,85 @@ impl Default for Config {
         default_default_config()
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn configs_equal_regardless_of_block_on() {
+        let config = Config {
+            block_on: |_| Ok(()),
+            ..Config::default()
+        };
+        assert_eq!(Config::default(), config);
+        assert_ne!(Config::with_cases(1), config);
+    }
+
+    #[test]
+    fn config_file_settings_apply_to_matching_tests() {
+        let file = ConfigFile::parse(
+            PathBuf::from("/project/proptest.toml"),
+            r#"
+cases = 512
+report_dir = "reports"
+
+[test."*::fuzz_*"]
+cases = 10000
+"#,
+        );
+
+        // What `Config::default()` is built from.
+        let mut defaults = default_default_config();
+        apply_file_settings(&mut defaults, &file, file.global_settings());
+        assert_eq!(512, defaults.cases);
+        assert_eq!(
+            Some(PathBuf::from("/project/reports")),
+            defaults.report_dir
+        );
+
+        let mut config = defaults.clone();
+        config.test_name = Some("my_crate::tests::parse");
+        apply_test_settings(&mut config, &file);
+        assert_eq!(512, config.cases);
+
+        let mut config = defaults.clone();
+        config.test_name = Some("my_crate::tests::fuzz_parse");
+        apply_test_settings(&mut config, &file);
+        assert_eq!(10000, config.cases);
+    }
+
+    #[test]
+    fn config_file_defaults_do_not_override_explicit_config() {
+        let file = ConfigFile::parse(
+            PathBuf::from("/project/proptest.toml"),
+            r#"
+cases = 512
+verbose = 1
+
+[test."*::fuzz_*"]
+cases = 10000
+"#,
+        );
+
+        let mut defaults = default_default_config();
+        apply_file_settings(&mut defaults, &file, file.global_settings());
+
+        let mut config = Config {
+            cases: 100,
+            test_name: Some("my_crate::tests::parse"),
+            ..defaults.clone()
+        };
+        apply_test_settings(&mut config, &file);
+        assert_eq!(100, config.cases);
+        assert_eq!(1, config.verbose);
+
+        let mut config = Config {
+            cases: 100,
+            test_name: Some("my_crate::tests::fuzz_parse"),
+            ..defaults
+        };
+        apply_test_settings(&mut config, &file);
+        assert_eq!(10000, config.cases);
+    }
+}
@@ -0,0 +1,357 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Loading of settings from a `proptest.toml` file.
+//!
+//! Only the subset of TOML such a file needs is understood: comments,
+//! `key = value` lines whose values are integers, booleans or strings, and
+//! `[test."<glob>"]` table headers, after which the keys only apply to tests
+//! whose names match the glob.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::vec::Vec;
+
+/// The name of the file `ConfigFile::discover` looks for.
+const CONFIG_FILE_NAME: &str = "proptest.toml";
+
+lazy_static! {
+    /// The config file of the crate under test, if it has one.
+    pub(crate) static ref CONFIG_FILE: Option<ConfigFile> =
+        ConfigFile::discover();
+}
+
+/// A `key = value` line of a config file.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Setting {
+    pub(crate) key: String,
+    /// The value, with the quotes and escapes of a string removed.
+    pub(crate) value: String,
+    /// The line number of the setting, starting from 1.
+    pub(crate) line: usize,
+}
+
+/// The settings read from a config file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ConfigFile {
+    pub(crate) path: PathBuf,
+    /// The settings before the first table, which apply to every test.
+    global: Vec<Setting>,
+    /// The glob and settings of each `[test."<glob>"]` table, in order.
+    tests: Vec<(String, Vec<Setting>)>,
+}
+
+impl ConfigFile {
+    /// Load the first `proptest.toml` found in `CARGO_MANIFEST_DIR` or one
+    /// of its ancestors.
+    fn discover() -> Option<Self> {
+        let dir = env::var_os("CARGO_MANIFEST_DIR")?;
+        let path = Path::new(&dir)
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|path| path.is_file())?;
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Some(ConfigFile::parse(path, &text)),
+            Err(err) => {
+                eprintln!(
+                    "proptest: Failed to read {}: {}",
+                    path.display(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    /// Parse the `text` of the config file at `path`.
+    ///
+    /// Lines which cannot be parsed are skipped with a warning, as are the
+    /// keys of any table other than `[test."<glob>"]`.
+    pub(crate) fn parse(path: PathBuf, text: &str) -> Self {
+        let mut file = ConfigFile {
+            path,
+            ..ConfigFile::default()
+        };
+        // Whether keys belong to the last entry of `tests` rather than to
+        // `global`, or `None` if they are in an unknown table.
+        let mut in_test = Some(false);
+
+        for (ix, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                in_test = match parse_header(line) {
+                    Some(glob) => {
+                        file.tests.push((glob, Vec::new()));
+                        Some(true)
+                    }
+                    None => {
+                        file.warn(ix + 1, "Ignoring unknown table");
+                        None
+                    }
+                };
+                continue;
+            }
+
+            let setting = match parse_setting(line) {
+                Some((key, value)) => Setting {
+                    key,
+                    value,
+                    line: ix + 1,
+                },
+                None => {
+                    file.warn(ix + 1, "Ignoring line which can't be parsed");
+                    continue;
+                }
+            };
+            match in_test {
+                Some(false) => file.global.push(setting),
+                Some(true) => file.tests.last_mut().unwrap().1.push(setting),
+                None => (),
+            }
+        }
+
+        file
+    }
+
+    fn warn(&self, line: usize, message: &str) {
+        eprintln!("proptest: {}:{}: {}.", self.path.display(), line, message);
+    }
+
+    /// Returns the settings which apply to every test.
+    pub(crate) fn global_settings(&self) -> &[Setting] {
+        &self.global
+    }
+
+    /// Returns the settings of every `[test."<glob>"]` table whose glob
+    /// matches `test_name`, in the order they appear in the file.
+    pub(crate) fn test_settings<'a>(
+        &'a self,
+        test_name: &'a str,
+    ) -> impl Iterator<Item = &'a Setting> + 'a {
+        self.tests
+            .iter()
+            .filter(move |(glob, _)| glob_matches(glob, test_name))
+            .flat_map(|(_, settings)| settings)
+    }
+}
+
+/// Returns `line` up to the first `#` which is not in a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (ix, ch) in line.char_indices() {
+        match quote {
+            None if '#' == ch => return &line[..ix],
+            None if '"' == ch || '\'' == ch => quote = Some(ch),
+            Some('"') if escaped => escaped = false,
+            Some('"') if '\\' == ch => escaped = true,
+            Some(q) if q == ch => quote = None,
+            _ => (),
+        }
+    }
+    line
+}
+
+/// Parse a `[test."<glob>"]` header, returning the glob.
+fn parse_header(line: &str) -> Option<String> {
+    if line.starts_with("[[") || !line.ends_with(']') {
+        return None;
+    }
+    let (table, glob) = line[1..line.len() - 1].split_once('.')?;
+    let glob = glob.trim();
+    if "test" != table.trim() || !glob.starts_with(&['"', '\''][..]) {
+        return None;
+    }
+    parse_value(glob)
+}
+
+/// Parse a `key = value` line.
+fn parse_setting(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || '_' == ch || '-' == ch)
+    {
+        return None;
+    }
+    Some((key.into(), parse_value(value.trim())?))
+}
+
+/// Parse a string, boolean or integer, returning it as it would be written
+/// in an environment variable.
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(literal) = value.strip_prefix('\'') {
+        let literal = literal.strip_suffix('\'')?;
+        return if literal.contains('\'') {
+            None
+        } else {
+            Some(literal.into())
+        };
+    }
+
+    if let Some(basic) = value.strip_prefix('"') {
+        let mut result = String::new();
+        let mut chars = basic.chars();
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => result.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    ch @ ('"' | '\\') => ch,
+                    _ => return None,
+                }),
+                ch => result.push(ch),
+            }
+        }
+        return if chars.as_str().is_empty() {
+            Some(result)
+        } else {
+            None
+        };
+    }
+
+    if "true" == value || "false" == value {
+        return Some(value.into());
+    }
+
+    let digits = value.strip_prefix(&['+', '-'][..]).unwrap_or(value);
+    if !digits.is_empty()
+        && digits.chars().all(|ch| ch.is_ascii_digit() || '_' == ch)
+    {
+        return Some(value.trim_start_matches('+').replace('_', ""));
+    }
+
+    None
+}
+
+/// Returns whether `text` matches `glob`, in which `*` matches any sequence
+/// of characters, including `::`, and `?` matches any one character.
+pub(crate) fn glob_matches(glob: &str, text: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut g, mut t) = (0, 0);
+    // The position in `glob` after the last `*` seen, and the position in
+    // `text` that `*` currently matches up to.
+    let mut star = None;
+
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                g += 1;
+                star = Some((g, t));
+            }
+            Some(&ch) if '?' == ch || text[t] == ch => {
+                g += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` match one more character and try again.
+                Some((star_g, star_t)) => {
+                    g = star_g;
+                    t = star_t + 1;
+                    star = Some((star_g, t));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|&ch| '*' == ch)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn globs_match_test_names() {
+        assert!(glob_matches("*::fuzz_*", "my_crate::tests::fuzz_parser"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("a?c", "abc"));
+        assert!(glob_matches("*a*b", "xaxxab"));
+        assert!(glob_matches("my_crate::*", "my_crate::a::b"));
+        assert!(!glob_matches("*::fuzz_*", "my_crate::tests::parse_fuzz"));
+        assert!(!glob_matches("a?c", "ac"));
+        assert!(!glob_matches("my_crate", "my_crate::a"));
+    }
+
+    #[test]
+    fn parses_settings_and_test_tables() {
+        let file = ConfigFile::parse(
+            PathBuf::from("proptest.toml"),
+            r#"
+# Shared settings
+cases = 1_000 # more than the default
+fork = true
+rng_algorithm = "chacha"
+
+[test."*::fuzz_*"]
+cases = 10000
+report_dir = 'target/#fuzz'
+
+[other]
+cases = 1
+
+[test.'*::fuzz_slow']
+max_shrink_time = 60000
+"#,
+        );
+
+        let setting = |key: &str, value: &str, line| Setting {
+            key: key.into(),
+            value: value.into(),
+            line,
+        };
+        assert_eq!(
+            &[
+                setting("cases", "1000", 3),
+                setting("fork", "true", 4),
+                setting("rng_algorithm", "chacha", 5),
+            ],
+            file.global_settings()
+        );
+        assert_eq!(
+            vec![
+                &setting("cases", "10000", 8),
+                &setting("report_dir", "target/#fuzz", 9),
+                &setting("max_shrink_time", "60000", 15),
+            ],
+            file.test_settings("my_crate::fuzz_slow")
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, file.test_settings("my_crate::slow").count());
+    }
+
+    #[test]
+    fn skips_lines_which_cannot_be_parsed() {
+        let file = ConfigFile::parse(
+            PathBuf::from("proptest.toml"),
+            "cases\nverbose = 1.5\nseed = \"unterminated\nverbose = 2\n",
+        );
+        assert_eq!(
+            &[Setting {
+                key: "verbose".into(),
+                value: "2".into(),
+                line: 4,
+            }],
+            file.global_settings()
+        );
+    }
+}
@@ -7,7 +7,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::fmt;
+use crate::std_facade::{fmt, Vec};
 
 #[cfg(feature = "std")]
 use std::string::ToString;
@@ -48,6 +48,7 @@ pub(crate) enum TestCaseOk {
     ReplayFromForkSuccess,
     CacheHitSuccess,
     Reject,
+    KnownFailure,
 }
 
 /// Convenience for the type returned by test cases.
@@ -108,6 +109,56 @@ pub enum TestError<T> {
     /// the test failed. The `T` is the minimal input found to reproduce the
     /// failure.
     Fail(Reason, T),
+    /// A failing test case was found, but it or the minimal input found by
+    /// shrinking it did not fail every time it was re-run. As for `Fail`,
+    /// the string indicates where and/or why the test failed and the `T` is
+    /// the minimal input found, while the `FlakyRuns` gives the outcomes of
+    /// the re-runs.
+    ///
+    /// This is only reported when `Config::flaky_reruns` is non-zero.
+    Flaky(Reason, T, FlakyRuns),
+    /// Several distinct failures were found. Each of them is either a
+    /// `Fail` or a `Flaky`, in the order they were found.
+    ///
+    /// This is only reported when `Config::max_distinct_failures` is greater
+    /// than `1`.
+    MultipleFailures(Vec<TestError<T>>),
+}
+
+/// How often the cases re-run to check for flakiness passed and failed.
+///
+/// Rejections count as passes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlakyRuns {
+    /// How many times the original failing case passed.
+    pub original_passes: u32,
+    /// How many times the original failing case failed.
+    pub original_failures: u32,
+    /// How many times the minimal failing case passed.
+    pub minimal_passes: u32,
+    /// How many times the minimal failing case failed.
+    pub minimal_failures: u32,
+}
+
+impl FlakyRuns {
+    /// Return whether any of the re-runs passed.
+    pub fn is_flaky(&self) -> bool {
+        self.original_passes > 0 || self.minimal_passes > 0
+    }
+}
+
+impl fmt::Display for FlakyRuns {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the original failing input failed {} of {} re-runs, and the \
+             minimal failing input failed {} of {} re-runs",
+            self.original_failures,
+            self.original_passes + self.original_failures,
+            self.minimal_failures,
+            self.minimal_passes + self.minimal_failures
+        )
+    }
 }
 
 impl<T: fmt::Debug> fmt::Display for TestError<T> {
@@ -118,6 +169,18 @@ impl<T: fmt::Debug> fmt::Display for TestError<T> {
                 writeln!(f, "Test failed: {}.", why)?;
                 write!(f, "minimal failing input: {:#?}", what)
             }
+            TestError::Flaky(ref why, ref what, ref runs) => {
+                writeln!(f, "Test failed inconsistently: {}.", why)?;
+                writeln!(f, "When re-run, {}.", runs)?;
+                write!(f, "minimal input found: {:#?}", what)
+            }
+            TestError::MultipleFailures(ref failures) => {
+                write!(f, "Test failed in {} distinct ways:", failures.len())?;
+                for (ix, failure) in failures.iter().enumerate() {
+                    write!(f, "\n\nFailure {}: {}", ix + 1, failure)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -129,6 +192,8 @@ impl<T: fmt::Debug> ::std::error::Error for TestError<T> {
         match *self {
             TestError::Abort(..) => "Abort",
             TestError::Fail(..) => "Fail",
+            TestError::Flaky(..) => "Flaky",
+            TestError::MultipleFailures(..) => "MultipleFailures",
         }
     }
 }
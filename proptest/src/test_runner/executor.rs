@@ -0,0 +1,93 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A minimal executor for `async` tests.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+use std::sync::Arc;
+use std::task::Wake;
+use std::thread::{self, Thread};
+
+use crate::std_facade::Box;
+use crate::test_runner::TestCaseResult;
+
+/// The future of an `async fn` test case, as passed to `Config::block_on`.
+pub type BoxedTestFuture = Pin<Box<dyn Future<Output = TestCaseResult>>>;
+
+/// Wakes a thread parked in `block_on`.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Run `future` to completion on the current thread.
+///
+/// This is the default `Config::block_on`. The thread is parked while the
+/// future is pending, so this works for futures which are woken by other
+/// threads, but not for those which need a particular runtime, such as ones
+/// using Tokio's I/O or timers.
+///
+/// ```
+/// use proptest::test_runner::block_on;
+///
+/// assert_eq!(3, block_on(async { 1 + 2 }));
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            // A wake-up before parking makes `park` return straight away, and
+            // spurious wake-ups just poll the future again.
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    /// A future which is ready once another thread has woken it.
+    struct WokenByOtherThread(Arc<AtomicBool>);
+
+    impl Future for WokenByOtherThread {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+            if self.0.load(Ordering::SeqCst) {
+                return Poll::Ready(42);
+            }
+
+            let done = Arc::clone(&self.0);
+            let waker = cx.waker().clone();
+            thread::spawn(move || {
+                done.store(true, Ordering::SeqCst);
+                waker.wake();
+            });
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn waits_for_wake_from_other_thread() {
+        let future = WokenByOtherThread(Arc::new(AtomicBool::new(false)));
+        assert_eq!(42, block_on(future));
+    }
+}
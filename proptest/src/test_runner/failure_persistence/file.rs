@@ -15,11 +15,13 @@ use std::env;
 use std::fs;
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
-use std::string::{String, ToString};
+use std::string::String;
 use std::sync::RwLock;
 use std::vec::Vec;
 
 use self::FileFailurePersistence::*;
+#[cfg(feature = "serde")]
+use crate::test_runner::failure_persistence::ValuePersistence;
 use crate::test_runner::failure_persistence::{
     FailurePersistence, PersistedSeed,
 };
@@ -85,6 +87,170 @@ impl FailurePersistence for FileFailurePersistence {
         &self,
         source_file: Option<&'static str>,
     ) -> Vec<PersistedSeed> {
+        self.load_seeds(source_file)
+            .into_iter()
+            .map(|(seed, _)| seed)
+            .collect()
+    }
+
+    fn load_persisted_failures3(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        self.load_seeds(source_file)
+            .into_iter()
+            .filter(|(_, tag)| tag.is_none() || tag.as_deref() == test_name)
+            .map(|(seed, _)| seed)
+            .collect()
+    }
+
+    fn save_persisted_failure2(
+        &mut self,
+        source_file: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn Debug,
+    ) {
+        self.save_seed(source_file, None, seed, shrunken_value);
+    }
+
+    fn save_persisted_failure3(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn Debug,
+    ) {
+        self.save_seed(source_file, test_name, seed, shrunken_value);
+    }
+
+    fn box_clone(&self) -> Box<dyn FailurePersistence> {
+        Box::new(*self)
+    }
+
+    fn eq(&self, other: &dyn FailurePersistence) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |x| x == self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Values are saved in a file next to the one holding the seeds, with
+/// `values` inserted before its extension, so that
+/// `proptest-regressions/foo/bar.txt` is accompanied by
+/// `proptest-regressions/foo/bar.values.txt`.
+///
+/// Each line of that file holds a JSON object with the serialized value under
+/// `"value"`, and the name of the test which saved it under `"test"`, if
+/// there is one.
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl ValuePersistence for FileFailurePersistence {
+    fn load_persisted_values(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<String> {
+        let path = match self.resolve_values(source_file.map(Path::new)) {
+            Some(path) => path,
+            None => return vec![],
+        };
+
+        let result: io::Result<Vec<_>> = {
+            // .ok() instead of .unwrap() so we don't propagate panics here
+            let _lock = PERSISTENCE_LOCK.read().ok();
+            fs::File::open(&path).and_then(|file| {
+                io::BufReader::new(file)
+                    .lines()
+                    .enumerate()
+                    .filter_map(|(lineno, line)| match line {
+                        Err(err) => Some(Err(err)),
+                        Ok(line) => {
+                            parse_value_line(&line, &path, lineno).map(Ok)
+                        }
+                    })
+                    .collect()
+            })
+        };
+
+        let values = unwrap_or!(result, err => {
+            if io::ErrorKind::NotFound != err.kind() {
+                eprintln!(
+                    "proptest: failed to open {}: {}",
+                    path.display(),
+                    err
+                );
+            }
+            vec![]
+        });
+        values
+            .into_iter()
+            .filter(|(tag, _)| tag.is_none() || tag.as_deref() == test_name)
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    fn save_persisted_value(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        value: &str,
+    ) {
+        if let Some(path) = self.resolve_values(source_file.map(Path::new)) {
+            // .ok() instead of .unwrap() so we don't propagate panics here
+            let _lock = PERSISTENCE_LOCK.write().ok();
+
+            let mut to_write = Vec::<u8>::new();
+            if !path.is_file() {
+                write_values_header(&mut to_write)
+                    .expect("proptest: couldn't write header.");
+            }
+            writeln!(to_write, "{}", format_value_line(test_name, value))
+                .expect("proptest: couldn't write value line.");
+
+            if let Err(e) = write_seed_data_to_file(&path, &to_write) {
+                eprintln!(
+                    "proptest: failed to append to {}: {}",
+                    path.display(),
+                    e
+                );
+            } else {
+                eprintln!(
+                    "proptest: Saving the failing value in {}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn ValuePersistence> {
+        Box::new(*self)
+    }
+
+    fn eq(&self, other: &dyn ValuePersistence) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |x| x == self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl FileFailurePersistence {
+    /// Read every seed in the persistence file for `source_file`, along with
+    /// the name of the test which saved it, if the line was tagged with one.
+    fn load_seeds(
+        &self,
+        source_file: Option<&'static str>,
+    ) -> Vec<(PersistedSeed, Option<String>)> {
         let p = self.resolve(
             source_file
                 .and_then(|s| absolutize_source_file(Path::new(s)))
@@ -93,7 +259,7 @@ impl FailurePersistence for FileFailurePersistence {
         );
 
         let path: Option<&PathBuf> = p.as_ref();
-        let result: io::Result<Vec<PersistedSeed>> = path.map_or_else(
+        let result: io::Result<Vec<_>> = path.map_or_else(
             || Ok(vec![]),
             |path| {
                 // .ok() instead of .unwrap() so we don't propagate panics here
@@ -123,9 +289,12 @@ impl FailurePersistence for FileFailurePersistence {
         })
     }
 
-    fn save_persisted_failure2(
-        &mut self,
+    /// Append `seed` to the persistence file for `source_file`, tagged with
+    /// `test_name` if there is one.
+    fn save_seed(
+        &self,
         source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
         seed: PersistedSeed,
         shrunken_value: &dyn Debug,
     ) {
@@ -141,6 +310,7 @@ impl FailurePersistence for FileFailurePersistence {
                     .expect("proptest: couldn't write header.");
             }
 
+            let seed = format_seed(&seed, test_name);
             write_seed_line(&mut to_write, &seed, shrunken_value)
                 .expect("proptest: couldn't write seed line.");
 
@@ -162,21 +332,6 @@ impl FailurePersistence for FileFailurePersistence {
             }
         }
     }
-
-    fn box_clone(&self) -> Box<dyn FailurePersistence> {
-        Box::new(*self)
-    }
-
-    fn eq(&self, other: &dyn FailurePersistence) -> bool {
-        other
-            .as_any()
-            .downcast_ref::<Self>()
-            .map_or(false, |x| x == self)
-    }
-
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
 }
 
 /// Ensure that the source file to use for resolving the location of the persisted
@@ -249,17 +404,26 @@ fn absolutize_source_file_with_cwd<'a>(
     }
 }
 
+/// Lines saved by a named test carry a comment starting with this tag,
+/// followed by the name. Since it is a comment, versions of proptest which do
+/// not know about it simply replay the seed for every test in the file.
+const TEST_NAME_TAG: &str = "test:";
+
 fn parse_seed_line(
-    mut line: String,
+    line: String,
     path: &Path,
     lineno: usize,
-) -> Option<PersistedSeed> {
-    // Remove anything after and including '#':
+) -> Option<(PersistedSeed, Option<String>)> {
+    // Ignore anything after and including '#', except for taking note of
+    // which test saved the seed. `PersistedSeed` itself picks up the size.
+    let mut test_name = None;
+    let mut seed_len = line.len();
     if let Some(comment_start) = line.find('#') {
-        line.truncate(comment_start);
+        test_name = parse_test_name_tag(&line[comment_start + 1..]);
+        seed_len = comment_start;
     }
 
-    if line.len() > 0 {
+    if seed_len > 0 {
         let ret = line.parse::<PersistedSeed>().ok();
         if !ret.is_some() {
             eprintln!(
@@ -268,19 +432,45 @@ fn parse_seed_line(
                 lineno + 1
             );
         }
-        return ret;
+        return ret.map(|seed| (seed, test_name));
     }
 
     None
 }
 
+/// Extract the test name from a `# test: name # ...` comment, possibly after
+/// a size tag, if that is what `comment` (the part of a line after the first
+/// `#`) is.
+fn parse_test_name_tag(comment: &str) -> Option<String> {
+    let tag = comment
+        .split('#')
+        .find(|tag| PersistedSeed::parse_size_tag(tag).is_none())?;
+    let name = tag.trim_start().strip_prefix(TEST_NAME_TAG)?.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_owned())
+    }
+}
+
+/// Format `seed` the way it is written to the persistence file, with its size
+/// and tagged with `test_name` if there is one.
+fn format_seed(seed: &PersistedSeed, test_name: Option<&str>) -> String {
+    match test_name {
+        Some(test_name) => {
+            format!("{} # {} {}", seed, TEST_NAME_TAG, test_name)
+        }
+        None => format!("{}", seed),
+    }
+}
+
 fn write_seed_line(
     buf: &mut Vec<u8>,
-    seed: &PersistedSeed,
+    seed: &str,
     shrunken_value: &dyn Debug,
 ) -> io::Result<()> {
     // Write the seed itself
-    write!(buf, "{}", seed.to_string())?;
+    write!(buf, "{}", seed)?;
 
     // Write out comment:
     let debug_start = buf.len();
@@ -298,6 +488,66 @@ fn write_seed_line(
     Ok(())
 }
 
+/// Parse a line of the values file into the name of the test which saved it
+/// and the serialized value.
+#[cfg(feature = "serde")]
+fn parse_value_line(
+    line: &str,
+    path: &Path,
+    lineno: usize,
+) -> Option<(Option<String>, String)> {
+    use serde_json::value::RawValue;
+    use std::collections::BTreeMap;
+
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let record = serde_json::from_str::<BTreeMap<String, &RawValue>>(line)
+        .ok()
+        .and_then(|record| {
+            let test_name = match record.get("test") {
+                Some(test) => Some(serde_json::from_str(test.get()).ok()?),
+                None => None,
+            };
+            let value = record.get("value")?.get().to_owned();
+            Some((test_name, value))
+        });
+    if record.is_none() {
+        eprintln!(
+            "proptest: {}:{}: unparsable line, ignoring",
+            path.display(),
+            lineno + 1
+        );
+    }
+    record
+}
+
+/// Format the line of the values file holding `value`, tagged with
+/// `test_name` if there is one.
+#[cfg(feature = "serde")]
+fn format_value_line(test_name: Option<&str>, value: &str) -> String {
+    match test_name.and_then(|test| serde_json::to_string(test).ok()) {
+        Some(test) => format!("{{\"test\":{},\"value\":{}}}", test, value),
+        None => format!("{{\"value\":{}}}", value),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn write_values_header(buf: &mut Vec<u8>) -> io::Result<()> {
+    writeln!(
+        buf,
+        "\
+# Values of failure cases proptest has generated in the past, serialized as
+# JSON. They are automatically read and these particular values re-tested
+# before any novel cases are generated.
+#
+# It is recommended to check this file in to source control so that
+# everyone who runs the test benefits from these saved cases."
+    )
+}
+
 fn write_header(buf: &mut Vec<u8>) -> io::Result<()> {
     writeln!(
         buf,
@@ -325,6 +575,21 @@ fn write_seed_data_to_file(dst: &Path, data: &[u8]) -> io::Result<()> {
 }
 
 impl FileFailurePersistence {
+    /// Given the nominal source path, determine the location of the file of
+    /// persisted values, if any.
+    #[cfg(feature = "serde")]
+    fn resolve_values(&self, source: Option<&Path>) -> Option<PathBuf> {
+        let mut path = self.resolve(source)?;
+        let extension = match path.extension() {
+            Some(extension) => {
+                format!("values.{}", extension.to_string_lossy())
+            }
+            None => "values".to_owned(),
+        };
+        path.set_extension(extension);
+        Some(path)
+    }
+
     /// Given the nominal source path, determine the location of the failure
     /// persistence file, if any.
     pub(super) fn resolve(&self, source: Option<&Path>) -> Option<PathBuf> {
@@ -440,6 +705,115 @@ mod tests {
         };
     }
 
+    #[test]
+    fn seed_lines_tagged_with_test_name() {
+        let path = Path::new("regressions.txt");
+        let seed = "xs 1 2 3 4".parse::<PersistedSeed>().unwrap();
+
+        let mut buf = Vec::new();
+        write_seed_line(&mut buf, &format_seed(&seed, Some("a::b")), &"# 1")
+            .unwrap();
+        let line = String::from_utf8(buf).unwrap();
+        assert_eq!("xs 1 2 3 4 # test: a::b # shrinks to \"# 1\"\n", line);
+        assert_eq!(
+            Some((seed.clone(), Some("a::b".to_owned()))),
+            parse_seed_line(line.trim_end().to_owned(), path, 0)
+        );
+
+        // Lines written before tagging existed, or without a test name, are
+        // still read and belong to no test in particular.
+        assert_eq!(
+            Some((seed.clone(), None)),
+            parse_seed_line(
+                "xs 1 2 3 4 # shrinks to test: 5".to_owned(),
+                path,
+                0
+            )
+        );
+        assert_eq!(
+            Some((seed.clone(), None)),
+            parse_seed_line("xs 1 2 3 4".to_owned(), path, 0)
+        );
+        assert_eq!(None, parse_seed_line("# test: a::b".to_owned(), path, 0));
+
+        // The size of cases generated below the full size goes in a comment
+        // of its own, so that older versions can still read the seed.
+        let small = PersistedSeed(seed.0.clone(), 250);
+        let mut buf = Vec::new();
+        write_seed_line(&mut buf, &format_seed(&small, Some("a::b")), &1)
+            .unwrap();
+        let line = String::from_utf8(buf).unwrap();
+        assert_eq!(
+            "xs 1 2 3 4 # size: 250 # test: a::b # shrinks to 1\n",
+            line
+        );
+        assert_eq!(
+            Some((small, Some("a::b".to_owned()))),
+            parse_seed_line(line.trim_end().to_owned(), path, 0)
+        );
+    }
+
+    #[test]
+    fn persisted_failures_scoped_to_test_name() {
+        let path = env::temp_dir().join(format!(
+            "proptest-scoped-persistence-{}.txt",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let path_str: &'static str =
+            Box::leak(path.to_str().unwrap().to_owned().into_boxed_str());
+        let mut persistence = Direct(path_str);
+
+        let old: PersistedSeed = "xs 1 2 3 4".parse().unwrap();
+        let foo: PersistedSeed = "xs 5 6 7 8".parse().unwrap();
+        persistence.save_persisted_failure2(None, old.clone(), &0);
+        persistence.save_persisted_failure3(None, Some("foo"), foo.clone(), &0);
+
+        assert_eq!(
+            vec![old.clone(), foo.clone()],
+            persistence.load_persisted_failures3(None, Some("foo"))
+        );
+        assert_eq!(
+            vec![old.clone()],
+            persistence.load_persisted_failures3(None, Some("bar"))
+        );
+        assert_eq!(
+            vec![old.clone(), foo.clone()],
+            persistence.load_persisted_failures2(None)
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn persisted_values_scoped_to_test_name() {
+        let dir = env::temp_dir();
+        let name = format!("proptest-value-persistence-{}", std::process::id());
+        let path = dir.join(format!("{}.values.txt", name));
+        let _ = fs::remove_file(&path);
+        let seeds_path = dir.join(format!("{}.txt", name));
+        let seeds_str: &'static str =
+            Box::leak(seeds_path.to_str().unwrap().to_owned().into_boxed_str());
+        let mut persistence = Direct(seeds_str);
+        assert_eq!(Some(path.clone()), persistence.resolve_values(None));
+
+        persistence.save_persisted_value(None, None, "[1,\"#\"]");
+        persistence.save_persisted_value(None, Some("foo"), "{\"a\":2}");
+
+        assert_eq!(
+            vec!["[1,\"#\"]", "{\"a\":2}"],
+            persistence.load_persisted_values(None, Some("foo"))
+        );
+        assert_eq!(
+            vec!["[1,\"#\"]"],
+            persistence.load_persisted_values(None, Some("bar"))
+        );
+        assert!(!seeds_path.exists());
+
+        let _ = fs::remove_file(&path);
+    }
+
     #[test]
     fn persistence_file_location_resolved_correctly() {
         // If off, there is never a file
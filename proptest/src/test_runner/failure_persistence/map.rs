@@ -17,6 +17,10 @@ use crate::test_runner::failure_persistence::PersistedSeed;
 /// on the heap. This may be useful when accumulating test failures
 /// across multiple `TestRunner` instances for external reporting
 /// or batched persistence.
+///
+/// Seeds are scoped to their source file only, so every test in a file
+/// replays the seeds of every other. Use `PerTestMapFailurePersistence` to
+/// scope them to the test which found them.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct MapFailurePersistence {
     /// Backing map, keyed by source_file.
@@ -64,6 +68,95 @@ impl FailurePersistence for MapFailurePersistence {
     }
 }
 
+/// Like `MapFailurePersistence`, but scopes seeds to the test which found
+/// them, as given by `Config::test_name`.
+///
+/// A test replays the seeds saved under its own name, plus those saved
+/// without a test name in its source file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PerTestMapFailurePersistence {
+    /// Backing map, keyed by source_file and test_name. Seeds saved without
+    /// a test name are keyed by `None`.
+    pub map:
+        BTreeMap<(&'static str, Option<&'static str>), BTreeSet<PersistedSeed>>,
+}
+
+impl FailurePersistence for PerTestMapFailurePersistence {
+    fn load_persisted_failures2(
+        &self,
+        source_file: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        let source = match source_file {
+            Some(sf) => sf,
+            None => return Vec::new(),
+        };
+        self.map
+            .range((source, None)..)
+            .take_while(|((sf, _), _)| *sf == source)
+            .flat_map(|(_, seeds)| seeds.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn load_persisted_failures3(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        let source = match source_file {
+            Some(sf) => sf,
+            None => return Vec::new(),
+        };
+        let mut seeds =
+            self.map.get(&(source, None)).cloned().unwrap_or_default();
+        if let Some(test_seeds) =
+            test_name.and_then(|test| self.map.get(&(source, Some(test))))
+        {
+            seeds.extend(test_seeds.iter().cloned());
+        }
+        seeds.into_iter().collect()
+    }
+
+    fn save_persisted_failure2(
+        &mut self,
+        source_file: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn fmt::Debug,
+    ) {
+        self.save_persisted_failure3(source_file, None, seed, shrunken_value)
+    }
+
+    fn save_persisted_failure3(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        seed: PersistedSeed,
+        _shrunken_value: &dyn fmt::Debug,
+    ) {
+        let s = match source_file {
+            Some(sf) => sf,
+            None => return,
+        };
+        self.map.entry((s, test_name)).or_default().insert(seed);
+    }
+
+    fn box_clone(&self) -> Box<dyn FailurePersistence> {
+        Box::new(self.clone())
+    }
+
+    fn eq(&self, other: &dyn FailurePersistence) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |x| x == self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +189,46 @@ mod tests {
         let restored = p.load_persisted_failures2(HI_PATH);
         assert_eq!(1, restored.len());
     }
+
+    #[test]
+    fn map_seeds_shared_by_every_test() {
+        let mut p = MapFailurePersistence::default();
+        p.save_persisted_failure3(HI_PATH, FOO_TEST, INC_SEED, &"");
+        assert_eq!(
+            vec![INC_SEED],
+            p.load_persisted_failures3(HI_PATH, BAR_TEST)
+        );
+    }
+
+    #[test]
+    fn seeds_scoped_to_test_name() {
+        let other_seed: PersistedSeed = "xs 1 2 3 4".parse().unwrap();
+
+        let mut p = PerTestMapFailurePersistence::default();
+        p.save_persisted_failure3(HI_PATH, FOO_TEST, INC_SEED, &"");
+        p.save_persisted_failure3(HI_PATH, None, other_seed.clone(), &"");
+
+        assert_eq!(
+            vec![INC_SEED, other_seed.clone()],
+            p.load_persisted_failures3(HI_PATH, FOO_TEST)
+        );
+        // Seeds saved without a test name apply to every test.
+        assert_eq!(
+            vec![other_seed.clone()],
+            p.load_persisted_failures3(HI_PATH, BAR_TEST)
+        );
+        assert_eq!(
+            vec![other_seed.clone()],
+            p.load_persisted_failures3(HI_PATH, None)
+        );
+        assert!(p.load_persisted_failures3(UNREL_PATH, FOO_TEST).is_empty());
+        // The test-agnostic API still sees everything.
+        assert_eq!(2, p.load_persisted_failures2(HI_PATH).len());
+        assert!(p.load_persisted_failures2(UNREL_PATH).is_empty());
+
+        assert_eq!(
+            Some(&vec![INC_SEED].into_iter().collect()),
+            p.map.get(&(HI_PATH.unwrap(), FOO_TEST))
+        );
+    }
 }
@@ -18,24 +18,59 @@ use core::str::FromStr;
 mod file;
 mod map;
 mod noop;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+mod value;
 
 #[cfg(feature = "std")]
 pub use self::file::*;
 pub use self::map::*;
 pub use self::noop::*;
+#[cfg(feature = "serde")]
+pub use self::value::*;
 
+use crate::test_runner::runner::FULL_SIZE;
 use crate::test_runner::Seed;
 
 /// Opaque struct representing a seed which can be persisted.
 ///
+/// Besides the seed of the RNG, this records the size the case was generated
+/// at (see `TestRunner::size`), so that the same case is generated when the
+/// seed is replayed.
+///
 /// The `Display` and `FromStr` implementations go to and from the format
-/// Proptest uses for its persistence file.
+/// Proptest uses for its persistence file. Unless it is the full size, the
+/// size follows the seed in a `# size: N` comment.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct PersistedSeed(pub(crate) Seed);
+pub struct PersistedSeed(pub(crate) Seed, pub(crate) u16);
+
+/// Seeds of cases generated below the full size are followed by a comment
+/// starting with this tag, followed by the size in thousandths of the full
+/// size. Since it is a comment, versions of proptest which do not know about
+/// it still read the seed; they just generate the case at the full size.
+pub(crate) const SIZE_TAG: &str = "size:";
+
+impl PersistedSeed {
+    /// Parse `tag`, a part of a comment between two `#`s, into a size if it
+    /// is a size tag.
+    ///
+    /// Returns `Some(Err(()))` for a size tag with an invalid size.
+    pub(crate) fn parse_size_tag(tag: &str) -> Option<Result<u16, ()>> {
+        let size = tag.trim().strip_prefix(SIZE_TAG)?;
+        Some(match size.trim().parse() {
+            Ok(size) if 0 < size && size <= FULL_SIZE => Ok(size),
+            _ => Err(()),
+        })
+    }
+}
 
 impl Display for PersistedSeed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0.to_persistence())
+        write!(f, "{}", self.0.to_persistence())?;
+        if self.1 < FULL_SIZE {
+            write!(f, " # {} {}", SIZE_TAG, self.1)?;
+        }
+        Ok(())
     }
 }
 
@@ -43,7 +78,15 @@ impl FromStr for PersistedSeed {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, ()> {
-        Seed::from_persistence(s).map(PersistedSeed).ok_or(())
+        let mut parts = s.split('#');
+        let seed = parts.next().unwrap_or("").trim();
+        let size = match parts.next().and_then(Self::parse_size_tag) {
+            Some(size) => size?,
+            None => FULL_SIZE,
+        };
+        Seed::from_persistence(seed)
+            .map(|seed| PersistedSeed(seed, size))
+            .ok_or(())
     }
 }
 
@@ -52,8 +95,47 @@ impl FromStr for PersistedSeed {
 /// **Note**: Implementing `load_persisted_failures` and
 /// `save_persisted_failures` is **deprecated** and these methods will be
 /// removed in proptest 0.10.0. Instead, implement `load_persisted_failures2`
-/// and `save_persisted_failures2`.
+/// and `save_persisted_failures2`, and optionally `load_persisted_failures3`
+/// and `save_persisted_failure3` to keep seeds separate for each test.
 pub trait FailurePersistence: Send + Sync + fmt::Debug {
+    /// Supply seeds associated with the given `source_file` and `test_name`
+    /// that may be used by a `TestRunner`'s random number generator in order
+    /// to consistently recreate a previously-failing `Strategy`-provided
+    /// value.
+    ///
+    /// This is what `TestRunner` calls, passing `Config::test_name`. Seeds
+    /// which were saved without a test name should be supplied to every
+    /// test.
+    ///
+    /// The default implementation is **for backwards compatibility**. It
+    /// ignores `test_name` and delegates to `load_persisted_failures2`.
+    #[allow(unused_variables)]
+    fn load_persisted_failures3(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        self.load_persisted_failures2(source_file)
+    }
+
+    /// Store a new failure-generating seed associated with the given
+    /// `source_file` and `test_name`.
+    ///
+    /// This is what `TestRunner` calls, passing `Config::test_name`.
+    ///
+    /// The default implementation is **for backwards compatibility**. It
+    /// ignores `test_name` and delegates to `save_persisted_failure2`.
+    #[allow(unused_variables)]
+    fn save_persisted_failure3(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn fmt::Debug,
+    ) {
+        self.save_persisted_failure2(source_file, seed, shrunken_value)
+    }
+
     /// Supply seeds associated with the given `source_file` that may be used
     /// by a `TestRunner`'s random number generator in order to consistently
     /// recreate a previously-failing `Strategy`-provided value.
@@ -68,7 +150,7 @@ pub trait FailurePersistence: Send + Sync + fmt::Debug {
     ) -> Vec<PersistedSeed> {
         self.load_persisted_failures(source_file)
             .into_iter()
-            .map(|seed| PersistedSeed(Seed::XorShift(seed)))
+            .map(|seed| PersistedSeed(Seed::XorShift(seed), FULL_SIZE))
             .collect()
     }
 
@@ -147,11 +229,42 @@ impl Clone for Box<dyn FailurePersistence> {
 mod tests {
     use super::PersistedSeed;
     use crate::test_runner::rng::Seed;
+    use crate::test_runner::runner::FULL_SIZE;
 
-    pub const INC_SEED: PersistedSeed = PersistedSeed(Seed::XorShift([
-        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
-    ]));
+    pub const INC_SEED: PersistedSeed = PersistedSeed(
+        Seed::XorShift([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]),
+        FULL_SIZE,
+    );
 
     pub const HI_PATH: Option<&str> = Some("hi");
     pub const UNREL_PATH: Option<&str> = Some("unrelated");
+
+    pub const FOO_TEST: Option<&str> = Some("foo");
+    pub const BAR_TEST: Option<&str> = Some("bar");
+
+    #[test]
+    fn size_is_persisted_below_full_size() {
+        let full = format!("{}", INC_SEED);
+        assert!(!full.contains("size"));
+
+        let seed = PersistedSeed(INC_SEED.0.clone(), 250);
+        assert_eq!(format!("{} # size: 250", full), format!("{}", seed));
+        assert_eq!(Ok(seed.clone()), format!("{}", seed).parse());
+        assert_eq!(
+            Ok(seed),
+            format!("{} # size: 250 # test: a::b # shrinks to 1", full).parse()
+        );
+        assert_eq!(Ok(INC_SEED), full.parse());
+        assert_eq!(
+            Ok(INC_SEED),
+            format!("{} # shrinks to size: 250", full).parse()
+        );
+
+        assert!(format!("{} # size: 0", full)
+            .parse::<PersistedSeed>()
+            .is_err());
+        assert!(format!("{} # size: 1001", full)
+            .parse::<PersistedSeed>()
+            .is_err());
+    }
 }
@@ -0,0 +1,157 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::any::Any;
+use std::boxed::Box;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+
+/// Provides external persistence for historical test failures by storing the
+/// failing values themselves.
+///
+/// Unlike a seed saved through
+/// [`FailurePersistence`](trait.FailurePersistence.html), a saved value keeps
+/// reproducing the failure when the strategy which generated it changes, as
+/// long as the value can still be deserialized. It is only used by
+/// [`TestRunner::run_with_value_persistence`](struct.TestRunner.html#method.run_with_value_persistence),
+/// which replays the saved values before any other cases, and still persists
+/// the seed of a new failure so that it can fall back on that.
+///
+/// Values are passed around as their JSON serialization, on a single line.
+pub trait ValuePersistence: Send + Sync + fmt::Debug {
+    /// Supply the serialized values associated with the given `source_file`
+    /// and `test_name`, which previously caused a test to fail.
+    ///
+    /// Values which were saved without a test name should be supplied to
+    /// every test.
+    fn load_persisted_values(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<String>;
+
+    /// Store the serialized form of a new minimal failing value associated
+    /// with the given `source_file` and `test_name`.
+    fn save_persisted_value(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        value: &str,
+    );
+
+    /// Delegate method for producing a trait object usable with `Clone`
+    fn box_clone(&self) -> Box<dyn ValuePersistence>;
+
+    /// Equality testing delegate required due to constraints of trait objects.
+    fn eq(&self, other: &dyn ValuePersistence) -> bool;
+
+    /// Assistant method for trait object comparison.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<'a, 'b> PartialEq<dyn ValuePersistence + 'b>
+    for dyn ValuePersistence + 'a
+{
+    fn eq(&self, other: &(dyn ValuePersistence + 'b)) -> bool {
+        ValuePersistence::eq(self, other)
+    }
+}
+
+impl Clone for Box<dyn ValuePersistence> {
+    fn clone(&self) -> Box<dyn ValuePersistence> {
+        self.box_clone()
+    }
+}
+
+/// Value persistence option that loads and saves serialized values in memory
+/// on the heap.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MapValuePersistence {
+    /// Backing map, keyed by source_file and then by test_name. Values saved
+    /// without a test name are under `None`.
+    pub map:
+        BTreeMap<&'static str, BTreeMap<Option<&'static str>, Vec<String>>>,
+}
+
+impl ValuePersistence for MapValuePersistence {
+    fn load_persisted_values(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<String> {
+        let tests = match source_file.and_then(|sf| self.map.get(sf)) {
+            Some(tests) => tests,
+            None => return Vec::new(),
+        };
+
+        let mut values = tests.get(&None).cloned().unwrap_or_default();
+        if test_name.is_some() {
+            if let Some(test_values) = tests.get(&test_name) {
+                values.extend(test_values.iter().cloned());
+            }
+        }
+        values
+    }
+
+    fn save_persisted_value(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        value: &str,
+    ) {
+        let s = match source_file {
+            Some(sf) => sf,
+            None => return,
+        };
+        let values =
+            self.map.entry(s).or_default().entry(test_name).or_default();
+        if !values.iter().any(|v| v == value) {
+            values.push(value.into());
+        }
+    }
+
+    fn box_clone(&self) -> Box<dyn ValuePersistence> {
+        Box::new(self.clone())
+    }
+
+    fn eq(&self, other: &dyn ValuePersistence) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |x| x == self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_runner::failure_persistence::tests::*;
+
+    #[test]
+    fn values_scoped_to_source_and_test_name() {
+        let mut p = MapValuePersistence::default();
+        p.save_persisted_value(HI_PATH, None, "0");
+        p.save_persisted_value(HI_PATH, FOO_TEST, "1");
+        p.save_persisted_value(HI_PATH, FOO_TEST, "1");
+        p.save_persisted_value(HI_PATH, BAR_TEST, "2");
+        p.save_persisted_value(None, FOO_TEST, "3");
+
+        assert_eq!(vec!["0", "1"], p.load_persisted_values(HI_PATH, FOO_TEST));
+        assert_eq!(vec!["0", "2"], p.load_persisted_values(HI_PATH, BAR_TEST));
+        assert_eq!(vec!["0"], p.load_persisted_values(HI_PATH, None));
+        assert!(p.load_persisted_values(UNREL_PATH, FOO_TEST).is_empty());
+        assert!(p.load_persisted_values(None, FOO_TEST).is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Resource limits for forked children.
+
+use std::format;
+use std::process::Command;
+use std::string::String;
+
+use crate::test_runner::Config;
+
+/// The resource limits from `Config` to apply to a forked child.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct ResourceLimits {
+    /// The address space limit in MiB, or 0.
+    memory: u32,
+    /// The CPU time limit in seconds, or 0.
+    cpu_time: u32,
+    /// The limit on open file descriptors, or 0.
+    open_files: u32,
+    /// The file size limit in MiB, or 0.
+    output: u32,
+}
+
+impl ResourceLimits {
+    pub(crate) fn new(config: &Config) -> Self {
+        ResourceLimits {
+            memory: config.memory_limit,
+            cpu_time: config.cpu_time_limit,
+            open_files: config.open_files_limit,
+            output: config.output_limit,
+        }
+    }
+
+    /// Arrange for the limits to be set in the child spawned by `cmd`.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn apply(self, cmd: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        if ResourceLimits::default() == self {
+            return;
+        }
+
+        // Safety: the closure runs between `fork` and `exec`, where only
+        // async-signal-safe functions may be called, which `getrlimit` and
+        // `setrlimit` are. It does not allocate.
+        unsafe {
+            cmd.pre_exec(move || self.set());
+        }
+    }
+
+    /// Arrange for the limits to be set in the child spawned by `cmd`.
+    ///
+    /// Resource limits are only supported on Linux, so this does nothing.
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn apply(self, _: &mut Command) {}
+
+    /// Set the limits on the current process.
+    ///
+    /// The CPU time limit is left to `start_case`, since it is accounted for
+    /// each case separately.
+    #[cfg(target_os = "linux")]
+    fn set(self) -> std::io::Result<()> {
+        const MIB: libc::rlim_t = 1024 * 1024;
+
+        let limits = [
+            (libc::RLIMIT_AS, libc::rlim_t::from(self.memory) * MIB),
+            (libc::RLIMIT_NOFILE, self.open_files.into()),
+            (libc::RLIMIT_FSIZE, libc::rlim_t::from(self.output) * MIB),
+        ];
+        for &(resource, limit) in &limits {
+            if 0 == limit {
+                continue;
+            }
+
+            let mut rlimit = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            // Safety: `rlimit` is a valid pointer for the duration of the
+            // calls.
+            unsafe {
+                if 0 != libc::getrlimit(resource, &mut rlimit) {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // An unprivileged process cannot raise its hard limit.
+                rlimit.rlim_cur = limit.min(rlimit.rlim_max);
+                rlimit.rlim_max = rlimit.rlim_cur;
+                if 0 != libc::setrlimit(resource, &rlimit) {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Give the case about to run in this (forked) process the full CPU time
+    /// limit, rather than whatever the cases before it left over.
+    ///
+    /// Only the soft limit is moved, so exceeding it sends `SIGXCPU`. Errors
+    /// are ignored, leaving the case with the limit of the previous one.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn start_case(self) {
+        if 0 == self.cpu_time {
+            return;
+        }
+
+        // Safety: `usage` and `rlimit` are valid pointers for the duration
+        // of the calls, and an all-zero `rusage` is a valid value.
+        unsafe {
+            let mut usage: libc::rusage = std::mem::zeroed();
+            let mut rlimit = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            if 0 != libc::getrusage(libc::RUSAGE_SELF, &mut usage)
+                || 0 != libc::getrlimit(libc::RLIMIT_CPU, &mut rlimit)
+            {
+                return;
+            }
+
+            // The limit is in whole seconds, so round what was used up.
+            let used = [usage.ru_utime, usage.ru_stime]
+                .iter()
+                .map(|time| {
+                    time.tv_sec as libc::rlim_t
+                        + libc::rlim_t::from(0 != time.tv_usec)
+                })
+                .sum::<libc::rlim_t>();
+            rlimit.rlim_cur = used
+                .saturating_add(self.cpu_time.into())
+                .min(rlimit.rlim_max);
+            libc::setrlimit(libc::RLIMIT_CPU, &rlimit);
+        }
+    }
+
+    /// Give the case about to run the full CPU time limit.
+    ///
+    /// Resource limits are only supported on Linux, so this does nothing.
+    #[cfg(not(target_os = "linux"))]
+    pub(crate) fn start_case(self) {}
+
+    /// Describe why a child which was terminated by `signal` after writing
+    /// `output` failed, if that was due to one of the limits.
+    pub(crate) fn describe_signal(
+        self,
+        signal: Option<i32>,
+        output: &str,
+    ) -> Option<String> {
+        // The signal numbers are the same on all Linux architectures proptest
+        // supports, and resource limits are not applied elsewhere.
+        const SIGABRT: i32 = 6;
+        const SIGXCPU: i32 = 24;
+        const SIGXFSZ: i32 = 25;
+
+        match signal? {
+            // Running out of memory aborts the process after the standard
+            // allocation error handler reports it, but so do plenty of other
+            // things.
+            SIGABRT
+                if 0 != self.memory
+                    && output.contains("memory allocation of") =>
+            {
+                Some(format!(
+                    "Child process aborted, most likely because it exceeded \
+                     {} MiB of memory",
+                    self.memory
+                ))
+            }
+            SIGXCPU if 0 != self.cpu_time => Some(format!(
+                "Child process exceeded {} s of CPU time",
+                self.cpu_time
+            )),
+            SIGXFSZ if 0 != self.output => Some(format!(
+                "Child process exceeded {} MiB of output",
+                self.output
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::borrow::ToOwned;
+
+    const SIGABRT: i32 = 6;
+    const SIGXCPU: i32 = 24;
+
+    fn limits() -> ResourceLimits {
+        ResourceLimits {
+            memory: 64,
+            cpu_time: 2,
+            ..ResourceLimits::default()
+        }
+    }
+
+    #[test]
+    fn abort_blamed_on_memory_only_after_allocation_failure() {
+        let oom = "memory allocation of 1048576 bytes failed";
+        assert_eq!(
+            Some(
+                "Child process aborted, most likely because it exceeded \
+                 64 MiB of memory"
+                    .to_owned()
+            ),
+            limits().describe_signal(Some(SIGABRT), oom)
+        );
+        assert_eq!(
+            None,
+            limits().describe_signal(Some(SIGABRT), "assertion failed")
+        );
+        assert_eq!(
+            None,
+            ResourceLimits::default().describe_signal(Some(SIGABRT), oom)
+        );
+    }
+
+    #[test]
+    fn cpu_time_signal_described_when_limited() {
+        assert_eq!(
+            Some("Child process exceeded 2 s of CPU time".to_owned()),
+            limits().describe_signal(Some(SIGXCPU), "")
+        );
+        assert_eq!(None, limits().describe_signal(None, ""));
+    }
+}
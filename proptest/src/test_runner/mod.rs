@@ -12,20 +12,40 @@
 //! You do not normally need to access things in this module directly except
 //! when implementing new low-level strategies.
 
+mod choices;
 mod config;
+#[cfg(feature = "std")]
+mod config_file;
 mod errors;
+#[cfg(feature = "std")]
+mod executor;
 mod failure_persistence;
+#[cfg(feature = "fork")]
+mod limits;
+#[cfg(feature = "std")]
+mod observer;
 mod reason;
 #[cfg(feature = "fork")]
 mod replay;
+#[cfg(feature = "std")]
+mod report;
 mod result_cache;
 mod rng;
 mod runner;
+#[cfg(feature = "std")]
+mod shrink_trace;
+pub(crate) mod statistics;
 
 pub use self::config::*;
 pub use self::errors::*;
+#[cfg(feature = "std")]
+pub use self::executor::*;
 pub use self::failure_persistence::*;
+#[cfg(feature = "std")]
+pub use self::observer::*;
 pub use self::reason::*;
+#[cfg(feature = "std")]
+pub use self::report::*;
 pub use self::result_cache::*;
 pub use self::rng::*;
 pub use self::runner::*;
@@ -0,0 +1,88 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::any::Any;
+use std::boxed::Box;
+use std::fmt;
+
+use crate::test_runner::{Reason, RunReport};
+
+/// Receives events from a `TestRunner` as it runs a test, for example to
+/// drive a progress bar or to export metrics.
+///
+/// An observer is set through `Config::observer`. Every method has a default
+/// implementation which does nothing, so implementations only need to
+/// override the events they are interested in, in addition to the trait
+/// object helpers `box_clone`, `eq` and `as_any`.
+///
+/// Values are passed as their `Debug` representation, since the observer is
+/// not generic over the type of the values being tested.
+///
+/// When the `fork` feature is in use, the forked child process is given its
+/// own copy of the observer, and the parent also sees every case the child
+/// runs, so an observer which has effects outside the process it is in may
+/// see each event twice.
+pub trait RunObserver: Send + Sync + fmt::Debug {
+    /// Called when a new case is about to be run, whether it was generated at
+    /// random, from a persisted failure or by `prop_target!`.
+    #[allow(unused_variables)]
+    fn case_generated(&mut self, value: &dyn fmt::Debug) {}
+
+    /// Called when the test passes for a case reported to `case_generated`.
+    #[allow(unused_variables)]
+    fn case_passed(&mut self, value: &dyn fmt::Debug) {}
+
+    /// Called when the test rejects a case reported to `case_generated`.
+    ///
+    /// Values rejected by the strategy itself, such as by `prop_filter`, are
+    /// never generated, so they are not reported.
+    #[allow(unused_variables)]
+    fn case_rejected(&mut self, value: &dyn fmt::Debug, reason: &Reason) {}
+
+    /// Called when the test fails for a case reported to `case_generated`,
+    /// before it is shrunk.
+    #[allow(unused_variables)]
+    fn case_failed(&mut self, value: &dyn fmt::Debug, reason: &Reason) {}
+
+    /// Called when a simpler value tried while shrinking still fails, so that
+    /// shrinking continues from it.
+    #[allow(unused_variables)]
+    fn shrink_accepted(&mut self, value: &dyn fmt::Debug, reason: &Reason) {}
+
+    /// Called when a simpler value tried while shrinking no longer fails, so
+    /// that shrinking does not continue from it.
+    #[allow(unused_variables)]
+    fn shrink_rejected(&mut self, value: &dyn fmt::Debug) {}
+
+    /// Called once `TestRunner::run` or `TestRunner::run_parallel` is over,
+    /// with the same summary that is written to `Config::report_dir`.
+    #[allow(unused_variables)]
+    fn run_finished(&mut self, report: &RunReport) {}
+
+    /// Delegate method for producing a trait object usable with `Clone`
+    fn box_clone(&self) -> Box<dyn RunObserver>;
+
+    /// Equality testing delegate required due to constraints of trait objects.
+    fn eq(&self, other: &dyn RunObserver) -> bool;
+
+    /// Assistant method for trait object comparison.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<'a, 'b> PartialEq<dyn RunObserver + 'b> for dyn RunObserver + 'a {
+    fn eq(&self, other: &(dyn RunObserver + 'b)) -> bool {
+        RunObserver::eq(self, other)
+    }
+}
+
+impl Clone for Box<dyn RunObserver> {
+    fn clone(&self) -> Box<dyn RunObserver> {
+        self.box_clone()
+    }
+}
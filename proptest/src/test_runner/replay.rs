@@ -15,6 +15,7 @@ use std::path::Path;
 use std::string::String;
 use std::vec::Vec;
 
+use crate::test_runner::statistics::CaseLabels;
 use crate::test_runner::{Seed, TestCaseError, TestCaseResult};
 
 const SENTINEL: &'static str = "proptest-forkfile";
@@ -36,9 +37,10 @@ const SENTINEL: &'static str = "proptest-forkfile";
 /// containing the values of `seed`, then an unterminated line consisting of
 /// `+`, `-`, and `!` characters to indicate test case passes/failures/rejects,
 /// `.` to indicate termination of the test run, or ` ` as a dummy "I'm alive"
-/// signal. This format makes it easy for the child process to blindly append
-/// to the file without having to worry about the possibility of appends being
-/// non-atomic.
+/// signal. Each step may be followed by the labels its test case recorded, in
+/// the encoding of `CaseLabels::to_replay`. This format makes it easy for the
+/// child process to blindly append to the file without having to worry about
+/// the possibility of appends being non-atomic.
 #[derive(Clone, Debug)]
 pub(crate) struct Replay {
     /// The seed of the RNG used to start running the test cases.
@@ -46,6 +48,8 @@ pub(crate) struct Replay {
     /// A log of whether certain test cases passed or failed. The runner will
     /// assume the same results occur without actually running the test cases.
     pub(crate) steps: Vec<TestCaseResult>,
+    /// The labels recorded by the test case of each step.
+    pub(crate) labels: Vec<CaseLabels>,
 }
 
 impl Replay {
@@ -54,6 +58,7 @@ impl Replay {
         if other.steps.len() > self.steps.len() {
             let sl = self.steps.len();
             self.steps.extend_from_slice(&other.steps[sl..]);
+            self.labels.extend_from_slice(&other.labels[sl..]);
         }
     }
 }
@@ -87,12 +92,19 @@ fn step_to_char(step: &TestCaseResult) -> char {
     }
 }
 
-/// Append the given step to the given output.
+/// Append the given step and the labels its test case recorded to the given
+/// output.
 pub(crate) fn append(
     mut file: impl Write,
     step: &TestCaseResult,
+    labels: &CaseLabels,
 ) -> io::Result<()> {
-    write!(file, "{}", step_to_char(step))
+    let mut data = String::new();
+    data.push(step_to_char(step));
+    if !labels.is_empty() {
+        data.push_str(&labels.to_replay());
+    }
+    file.write_all(data.as_bytes())
 }
 
 /// Append a no-op step to the given output.
@@ -111,13 +123,10 @@ impl Replay {
         writeln!(file, "{}", SENTINEL)?;
         writeln!(file, "{}", self.seed.to_persistence())?;
 
-        let mut step_data = Vec::<u8>::new();
-        for step in &self.steps {
-            step_data.push(step_to_char(step) as u8);
+        for (step, labels) in self.steps.iter().zip(&self.labels) {
+            append(&mut file, step, labels)?;
         }
 
-        file.write_all(&step_data)?;
-
         Ok(())
     }
 
@@ -165,7 +174,9 @@ impl Replay {
         reader.read_line(&mut line)?;
 
         let mut steps = Vec::new();
-        for ch in line.chars() {
+        let mut labels = Vec::new();
+        let mut chars = line.chars().peekable();
+        while let Some(ch) = chars.next() {
             match ch {
                 '+' => steps.push(Ok(())),
                 '-' => steps
@@ -177,13 +188,28 @@ impl Replay {
                     return Ok(ReplayFileStatus::Terminated(Replay {
                         seed,
                         steps,
+                        labels,
                     }))
                 }
-                ' ' => (),
+                ' ' => continue,
                 _ => return Ok(ReplayFileStatus::Corrupt),
             }
+
+            let mut encoded = String::new();
+            while let Some(ch) =
+                chars.next_if(|&ch| CaseLabels::is_replay_char(ch))
+            {
+                encoded.push(ch);
+            }
+            // A child killed while writing labels leaves them incomplete,
+            // which only loses the labels.
+            labels.push(CaseLabels::from_replay(&encoded).unwrap_or_default());
         }
 
-        Ok(ReplayFileStatus::InProgress(Replay { seed, steps }))
+        Ok(ReplayFileStatus::InProgress(Replay {
+            seed,
+            steps,
+            labels,
+        }))
     }
 }
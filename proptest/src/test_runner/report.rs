@@ -0,0 +1,436 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Machine-readable reports of test runs, written to `Config::report_dir`.
+
+use core::fmt::{self, Write as _};
+use core::str::FromStr;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::string::{String, ToString};
+use std::sync::Mutex;
+use std::time::Duration;
+use std::vec::Vec;
+
+use crate::test_runner::{PersistedSeed, Reason};
+
+/// The format of the reports written to `Config::report_dir`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Every run appends one JSON object, on its own line, to
+    /// `proptest-report.jsonl` in the report directory.
+    #[default]
+    JsonLines,
+    /// Every run writes a JUnit XML file holding a single test suite with a
+    /// single test case to the report directory, named after the test.
+    JUnit,
+}
+
+impl FromStr for ReportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "json" | "jsonl" => Ok(ReportFormat::JsonLines),
+            "junit" | "xml" => Ok(ReportFormat::JUnit),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReportFormat::JsonLines => f.write_str("json"),
+            ReportFormat::JUnit => f.write_str("junit"),
+        }
+    }
+}
+
+/// How a test run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// All the required cases passed.
+    Passed,
+    /// A failing case was found.
+    Failed,
+    /// A failing case was found, but it did not fail every time it was
+    /// re-run.
+    Flaky,
+    /// The run was aborted, for example due to too many rejections.
+    Aborted,
+}
+
+impl RunOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            RunOutcome::Passed => "passed",
+            RunOutcome::Failed => "failed",
+            RunOutcome::Flaky => "flaky",
+            RunOutcome::Aborted => "aborted",
+        }
+    }
+}
+
+/// A summary of one run of a property test.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunReport {
+    /// `Config::test_name` of the run.
+    pub test_name: Option<&'static str>,
+    /// `Config::source_file` of the run.
+    pub source_file: Option<&'static str>,
+    /// How the run ended.
+    pub outcome: RunOutcome,
+    /// Why the run failed or was aborted.
+    pub message: Option<String>,
+    /// The number of cases which passed.
+    pub cases: u32,
+    /// The number of local rejections.
+    pub local_rejects: u32,
+    /// The number of global rejections.
+    pub global_rejects: u32,
+    /// The reasons for local rejections, and how often each occurred.
+    pub local_reject_detail: Vec<(Reason, u32)>,
+    /// The reasons for global rejections, and how often each occurred.
+    pub global_reject_detail: Vec<(Reason, u32)>,
+    /// The number of times the test was run while shrinking.
+    pub shrink_iters: u32,
+    /// How long the run took.
+    pub elapsed: Duration,
+    /// The seed which generated the failing case, if there is one which
+    /// reproduces it.
+    pub failure_seed: Option<PersistedSeed>,
+    /// The `Debug` representation of the minimal failing value.
+    pub minimal_value: Option<String>,
+    // Needs to be public so FRU syntax can be used.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl RunReport {
+    /// Format this report as a single line of JSON, without the trailing
+    /// newline.
+    pub fn to_json_line(&self) -> String {
+        fn detail(out: &mut String, detail: &[(Reason, u32)]) {
+            out.push('{');
+            for (ix, (reason, count)) in detail.iter().enumerate() {
+                if ix > 0 {
+                    out.push(',');
+                }
+                json_string(out, reason.message());
+                let _ = write!(out, ":{}", count);
+            }
+            out.push('}');
+        }
+
+        let mut out = String::new();
+        out.push_str("{\"test\":");
+        json_opt_string(&mut out, self.test_name);
+        out.push_str(",\"source_file\":");
+        json_opt_string(&mut out, self.source_file);
+        out.push_str(",\"outcome\":");
+        json_string(&mut out, self.outcome.as_str());
+        out.push_str(",\"message\":");
+        json_opt_string(&mut out, self.message.as_deref());
+        let _ = write!(
+            out,
+            ",\"cases\":{},\"local_rejects\":{},\"global_rejects\":{}",
+            self.cases, self.local_rejects, self.global_rejects
+        );
+        out.push_str(",\"local_reject_detail\":");
+        detail(&mut out, &self.local_reject_detail);
+        out.push_str(",\"global_reject_detail\":");
+        detail(&mut out, &self.global_reject_detail);
+        let _ = write!(
+            out,
+            ",\"shrink_iters\":{},\"elapsed_secs\":{}",
+            self.shrink_iters,
+            self.elapsed.as_secs_f64()
+        );
+        out.push_str(",\"seed\":");
+        json_opt_string(
+            &mut out,
+            self.failure_seed.as_ref().map(|s| s.to_string()).as_deref(),
+        );
+        out.push_str(",\"minimal_value\":");
+        json_opt_string(&mut out, self.minimal_value.as_deref());
+        out.push('}');
+        out
+    }
+
+    /// Format this report as a JUnit XML document with a single test suite
+    /// holding a single test case.
+    pub fn to_junit_xml(&self) -> String {
+        let full_name = self.test_name.unwrap_or("<unnamed test>");
+        let (class_name, name) = match full_name.rfind("::") {
+            Some(ix) => (&full_name[..ix], &full_name[ix + 2..]),
+            None => ("", full_name),
+        };
+        let time = self.elapsed.as_secs_f64();
+
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            out,
+            "<testsuite name=\"{}\" tests=\"1\" failures=\"{}\" \
+             errors=\"{}\" time=\"{}\">",
+            xml_escape(full_name),
+            (self.outcome == RunOutcome::Failed
+                || self.outcome == RunOutcome::Flaky) as u8,
+            (self.outcome == RunOutcome::Aborted) as u8,
+            time
+        );
+
+        out.push_str("  <properties>\n");
+        let mut property = |name: &str, value: &str| {
+            let _ = writeln!(
+                out,
+                "    <property name=\"{}\" value=\"{}\"/>",
+                name,
+                xml_escape(value)
+            );
+        };
+        property("cases", &self.cases.to_string());
+        property("local_rejects", &self.local_rejects.to_string());
+        property("global_rejects", &self.global_rejects.to_string());
+        property("shrink_iters", &self.shrink_iters.to_string());
+        if let Some(ref seed) = self.failure_seed {
+            property("seed", &seed.to_string());
+        }
+
+        out.push_str("  </properties>\n");
+
+        let _ = write!(
+            out,
+            "  <testcase name=\"{}\" classname=\"{}\"",
+            xml_escape(name),
+            xml_escape(class_name)
+        );
+        if let Some(file) = self.source_file {
+            let _ = write!(out, " file=\"{}\"", xml_escape(file));
+        }
+        let _ = writeln!(out, " time=\"{}\">", time);
+
+        let message = self.message.as_deref().unwrap_or("");
+        match self.outcome {
+            RunOutcome::Passed => (),
+            RunOutcome::Failed | RunOutcome::Flaky => {
+                let _ = write!(
+                    out,
+                    "    <failure message=\"{}\" type=\"{}\">",
+                    xml_escape(message),
+                    if self.outcome == RunOutcome::Flaky {
+                        "proptest-flaky"
+                    } else {
+                        "proptest"
+                    }
+                );
+                if let Some(ref value) = self.minimal_value {
+                    let _ = write!(
+                        out,
+                        "minimal failing input: {}",
+                        xml_escape(value)
+                    );
+                }
+                out.push_str("</failure>\n");
+            }
+            RunOutcome::Aborted => {
+                let _ = writeln!(
+                    out,
+                    "    <error message=\"{}\" type=\"proptest\"/>",
+                    xml_escape(message)
+                );
+            }
+        }
+
+        if !self.local_reject_detail.is_empty()
+            || !self.global_reject_detail.is_empty()
+        {
+            out.push_str("    <system-out>");
+            for (kind, detail) in &[
+                ("local", &self.local_reject_detail),
+                ("global", &self.global_reject_detail),
+            ] {
+                for (reason, count) in detail.iter() {
+                    let _ = writeln!(
+                        out,
+                        "{} reject: {} times at {}",
+                        kind,
+                        count,
+                        xml_escape(reason.message())
+                    );
+                }
+            }
+            out.push_str("</system-out>\n");
+        }
+
+        out.push_str("  </testcase>\n</testsuite>\n");
+        out
+    }
+}
+
+/// The file in the report directory which JSON-lines reports are appended
+/// to.
+const JSON_LINES_FILE: &str = "proptest-report.jsonl";
+
+lazy_static! {
+    /// Used so that tests running on different threads don't interleave
+    /// their writes to the same report file.
+    static ref REPORT_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Write `report` to `dir` in the given format, creating the directory if
+/// necessary.
+pub(crate) fn write_report(
+    dir: &Path,
+    format: ReportFormat,
+    report: &RunReport,
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    // .ok() instead of .unwrap() so we don't propagate panics here
+    let _lock = REPORT_LOCK.lock().ok();
+
+    match format {
+        ReportFormat::JsonLines => {
+            let mut line = report.to_json_line();
+            line.push('\n');
+            fs::OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(dir.join(JSON_LINES_FILE))?
+                .write_all(line.as_bytes())
+        }
+        ReportFormat::JUnit => fs::write(
+            dir.join(format!("TEST-{}.xml", file_name_part(report.test_name))),
+            report.to_junit_xml(),
+        ),
+    }
+}
+
+/// Returns `test_name` with every character which is not alphanumeric
+/// replaced by `_`, for use in a file name.
+pub(crate) fn file_name_part(test_name: Option<&str>) -> String {
+    test_name
+        .unwrap_or("unnamed")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+pub(crate) fn json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn json_opt_string(out: &mut String, s: Option<&str>) {
+    match s {
+        Some(s) => json_string(out, s),
+        None => out.push_str("null"),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            '\t' => out.push_str("&#9;"),
+            // Other control characters are not allowed in XML 1.0 at all.
+            c if (c as u32) < 0x20 => out.push('\u{fffd}'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn failed_report() -> RunReport {
+        RunReport {
+            test_name: Some("my_crate::tests::adds"),
+            source_file: Some("src/lib.rs"),
+            outcome: RunOutcome::Failed,
+            message: Some("assertion \"failed\"\n<here>".into()),
+            cases: 3,
+            local_rejects: 0,
+            global_rejects: 2,
+            local_reject_detail: vec![],
+            global_reject_detail: vec![("odd".into(), 2)],
+            shrink_iters: 7,
+            elapsed: Duration::from_millis(1500),
+            failure_seed: Some("xs 1 2 3 4".parse().unwrap()),
+            minimal_value: Some("(0, \"\\u{1}\")".into()),
+            _non_exhaustive: (),
+        }
+    }
+
+    #[test]
+    fn json_line_is_escaped() {
+        assert_eq!(
+            "{\"test\":\"my_crate::tests::adds\",\"source_file\":\"src/lib.rs\",\
+             \"outcome\":\"failed\",\
+             \"message\":\"assertion \\\"failed\\\"\\n<here>\",\
+             \"cases\":3,\"local_rejects\":0,\"global_rejects\":2,\
+             \"local_reject_detail\":{},\"global_reject_detail\":{\"odd\":2},\
+             \"shrink_iters\":7,\"elapsed_secs\":1.5,\"seed\":\"xs 1 2 3 4\",\
+             \"minimal_value\":\"(0, \\\"\\\\u{1}\\\")\"}",
+            failed_report().to_json_line()
+        );
+    }
+
+    #[test]
+    fn junit_xml_is_escaped() {
+        let xml = failed_report().to_junit_xml();
+        assert!(xml.contains(
+            "<testsuite name=\"my_crate::tests::adds\" tests=\"1\" \
+             failures=\"1\" errors=\"0\" time=\"1.5\">"
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"adds\" classname=\"my_crate::tests\" \
+             file=\"src/lib.rs\" time=\"1.5\">"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"assertion &quot;failed&quot;&#10;&lt;here&gt;\" \
+             type=\"proptest\">minimal failing input: (0, &quot;\\u{1}&quot;)\
+             </failure>"
+        ));
+        assert!(xml.contains("<property name=\"seed\" value=\"xs 1 2 3 4\"/>"));
+        assert!(xml.contains("global reject: 2 times at odd\n"));
+    }
+
+    #[test]
+    fn report_format_round_trips() {
+        for format in &[ReportFormat::JsonLines, ReportFormat::JUnit] {
+            assert_eq!(Ok(*format), format.to_string().parse());
+        }
+        assert_eq!(Err(()), "yaml".parse::<ReportFormat>());
+    }
+}
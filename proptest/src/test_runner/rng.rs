@@ -7,9 +7,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{Arc, String, ToOwned, Vec};
+use crate::std_facade::{Arc, Box, String, ToOwned, Vec};
 use core::result::Result;
-use core::{fmt, str, u8, convert::TryInto};
+use core::{convert::TryInto, fmt, mem, str, u8};
 
 use rand::{self, Rng, RngCore, SeedableRng};
 use rand_chacha::ChaChaRng;
@@ -124,6 +124,22 @@ enum TestRngImpl {
         rng: ChaChaRng,
         record: Vec<u8>,
     },
+    /// Wraps another RNG, recording every byte drawn from it as a choice
+    /// sequence which `Choices` can replay.
+    Recording {
+        inner: Box<TestRng>,
+        choices: Vec<u8>,
+    },
+    /// Replays a (possibly modified) choice sequence recorded by `Recording`.
+    ///
+    /// This reads data the same way `PassThrough` does, but derived RNGs take
+    /// their seed from the sequence instead of splitting it, so that a
+    /// recorded sequence generates the same values as the original RNG.
+    Choices {
+        off: usize,
+        data: Arc<[u8]>,
+        algorithm: RngAlgorithm,
+    },
 }
 
 impl RngCore for TestRng {
@@ -133,7 +149,8 @@ impl RngCore for TestRng {
 
             &mut TestRngImpl::ChaCha(ref mut rng) => rng.next_u32(),
 
-            &mut TestRngImpl::PassThrough { .. } => {
+            &mut TestRngImpl::PassThrough { .. }
+            | &mut TestRngImpl::Choices { .. } => {
                 let mut buf = [0; 4];
                 self.fill_bytes(&mut buf[..]);
                 u32::from_le_bytes(buf)
@@ -147,6 +164,15 @@ impl RngCore for TestRng {
                 record.extend_from_slice(&read.to_le_bytes());
                 read
             }
+
+            &mut TestRngImpl::Recording {
+                ref mut inner,
+                ref mut choices,
+            } => {
+                let read = inner.next_u32();
+                choices.extend_from_slice(&read.to_le_bytes());
+                read
+            }
         }
     }
 
@@ -156,7 +182,8 @@ impl RngCore for TestRng {
 
             &mut TestRngImpl::ChaCha(ref mut rng) => rng.next_u64(),
 
-            &mut TestRngImpl::PassThrough { .. } => {
+            &mut TestRngImpl::PassThrough { .. }
+            | &mut TestRngImpl::Choices { .. } => {
                 let mut buf = [0; 8];
                 self.fill_bytes(&mut buf[..]);
                 u64::from_le_bytes(buf)
@@ -170,6 +197,15 @@ impl RngCore for TestRng {
                 record.extend_from_slice(&read.to_le_bytes());
                 read
             }
+
+            &mut TestRngImpl::Recording {
+                ref mut inner,
+                ref mut choices,
+            } => {
+                let read = inner.next_u64();
+                choices.extend_from_slice(&read.to_le_bytes());
+                read
+            }
         }
     }
 
@@ -201,6 +237,28 @@ impl RngCore for TestRng {
                 record.extend_from_slice(&dest);
                 res
             }
+
+            &mut TestRngImpl::Recording {
+                ref mut inner,
+                ref mut choices,
+            } => {
+                inner.fill_bytes(dest);
+                choices.extend_from_slice(dest);
+            }
+
+            &mut TestRngImpl::Choices {
+                ref mut off,
+                ref data,
+                ..
+            } => {
+                let bytes_to_copy = dest.len().min(data.len() - *off);
+                dest[..bytes_to_copy]
+                    .copy_from_slice(&data[*off..*off + bytes_to_copy]);
+                *off += bytes_to_copy;
+                for b in &mut dest[bytes_to_copy..] {
+                    *b = 0;
+                }
+            }
         }
     }
 
@@ -210,7 +268,7 @@ impl RngCore for TestRng {
 
             TestRngImpl::ChaCha(ref mut rng) => rng.try_fill_bytes(dest),
 
-            TestRngImpl::PassThrough { .. } => {
+            TestRngImpl::PassThrough { .. } | TestRngImpl::Choices { .. } => {
                 self.fill_bytes(dest);
                 Ok(())
             }
@@ -225,6 +283,17 @@ impl RngCore for TestRng {
                 }
                 res
             }
+
+            TestRngImpl::Recording {
+                ref mut inner,
+                ref mut choices,
+            } => {
+                let res = inner.try_fill_bytes(dest);
+                if res.is_ok() {
+                    choices.extend_from_slice(dest);
+                }
+                res
+            }
         }
     }
 }
@@ -400,6 +469,20 @@ impl Seed {
     }
 }
 
+/// Directly using XorShiftRng::from_seed() on bytes drawn from another
+/// XorShiftRng would result in both producing exactly the same values. Perturb
+/// the seed with some arbitrary values to prevent this.
+fn perturb_xor_shift_seed(mut seed: [u8; 16]) -> [u8; 16] {
+    for word in seed.chunks_mut(4) {
+        word[3] ^= 0xde;
+        word[2] ^= 0xad;
+        word[1] ^= 0xbe;
+        word[0] ^= 0xef;
+    }
+
+    seed
+}
+
 impl TestRng {
     /// Create a new RNG with the given algorithm and seed.
     ///
@@ -575,19 +658,7 @@ impl TestRng {
     pub(crate) fn new_rng_seed(&mut self) -> Seed {
         match self.rng {
             TestRngImpl::XorShift(ref mut rng) => {
-                let mut seed = rng.gen::<[u8; 16]>();
-
-                // Directly using XorShiftRng::from_seed() at this point would
-                // result in rng and the returned value being exactly the same.
-                // Perturb the seed with some arbitrary values to prevent this.
-                for word in seed.chunks_mut(4) {
-                    word[3] ^= 0xde;
-                    word[2] ^= 0xad;
-                    word[1] ^= 0xbe;
-                    word[0] ^= 0xef;
-                }
-
-                Seed::XorShift(seed)
+                Seed::XorShift(perturb_xor_shift_seed(rng.gen()))
             }
 
             TestRngImpl::ChaCha(ref mut rng) => Seed::ChaCha(rng.gen()),
@@ -610,11 +681,92 @@ impl TestRng {
             TestRngImpl::Recorder { ref mut rng, .. } => {
                 Seed::Recorder(rng.gen())
             }
+
+            // Draw the seed through the choice sequence itself, exactly as
+            // the wrapped RNG would have drawn it.
+            TestRngImpl::Recording { .. } | TestRngImpl::Choices { .. } => {
+                match self.algorithm() {
+                    RngAlgorithm::XorShift => {
+                        Seed::XorShift(perturb_xor_shift_seed(self.gen()))
+                    }
+                    RngAlgorithm::Recorder => Seed::Recorder(self.gen()),
+                    _ => Seed::ChaCha(self.gen()),
+                }
+            }
+        }
+    }
+
+    /// Return the algorithm this RNG was created with.
+    pub(crate) fn algorithm(&self) -> RngAlgorithm {
+        match self.rng {
+            TestRngImpl::XorShift(..) => RngAlgorithm::XorShift,
+            TestRngImpl::ChaCha(..) => RngAlgorithm::ChaCha,
+            TestRngImpl::PassThrough { .. } => RngAlgorithm::PassThrough,
+            TestRngImpl::Recorder { .. } => RngAlgorithm::Recorder,
+            TestRngImpl::Recording { ref inner, .. } => inner.algorithm(),
+            TestRngImpl::Choices { algorithm, .. } => algorithm,
+        }
+    }
+
+    /// Start recording every byte drawn from this RNG as a choice sequence.
+    ///
+    /// The RNG continues to produce exactly the same values as it would
+    /// otherwise.
+    pub(crate) fn start_recording_choices(&mut self) {
+        let inner =
+            mem::replace(self, TestRng::from_choices(self.algorithm(), &[]));
+        self.rng = TestRngImpl::Recording {
+            inner: Box::new(inner),
+            choices: Vec::new(),
+        };
+    }
+
+    /// Stop recording started by `start_recording_choices`, returning the
+    /// choice sequence drawn since.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if this RNG is not recording.
+    pub(crate) fn finish_recording_choices(&mut self) -> Vec<u8> {
+        let algorithm = self.algorithm();
+        match mem::replace(self, TestRng::from_choices(algorithm, &[])).rng {
+            TestRngImpl::Recording { inner, choices } => {
+                *self = *inner;
+                choices
+            }
+            _ => {
+                panic!("finish_recording_choices() called on non-recording RNG")
+            }
+        }
+    }
+
+    /// Create an RNG which replays the given choice sequence, as recorded from
+    /// an RNG using `algorithm`.
+    ///
+    /// Once the sequence is exhausted, the RNG returns 0s forever.
+    pub(crate) fn from_choices(algorithm: RngAlgorithm, data: &[u8]) -> Self {
+        TestRng {
+            rng: TestRngImpl::Choices {
+                off: 0,
+                data: data.into(),
+                algorithm,
+            },
+        }
+    }
+
+    /// Return how many bytes of the choice sequence have been consumed so far.
+    ///
+    /// Zeroes returned after the end of the sequence are not counted. Returns
+    /// 0 if this RNG does not replay a choice sequence.
+    pub(crate) fn choices_used(&self) -> usize {
+        match self.rng {
+            TestRngImpl::Choices { off, .. } => off,
+            _ => 0,
         }
     }
 
     /// Construct a TestRng from a given seed.
-    fn from_seed_internal(seed: Seed) -> Self {
+    pub(crate) fn from_seed_internal(seed: Seed) -> Self {
         Self {
             rng: match seed {
                 Seed::XorShift(seed) => {
@@ -707,6 +859,40 @@ mod test {
         }
     }
 
+    #[test]
+    fn recorded_choices_replay_identically() {
+        for &algorithm in &[
+            RngAlgorithm::XorShift,
+            RngAlgorithm::ChaCha,
+            RngAlgorithm::Recorder,
+        ] {
+            fn draw(rng: &mut TestRng) -> (u32, u64, [u8; 3], u64) {
+                let a = rng.next_u32();
+                let b = rng.next_u64();
+                let mut c = [0u8; 3];
+                rng.fill_bytes(&mut c);
+                let d = rng.gen_rng().next_u64();
+                (a, b, c, d)
+            }
+
+            let mut expected_rng = TestRng::deterministic_rng(algorithm);
+            let expected = draw(&mut expected_rng);
+
+            let mut rng = TestRng::deterministic_rng(algorithm);
+            rng.start_recording_choices();
+            assert_eq!(expected, draw(&mut rng));
+            let choices = rng.finish_recording_choices();
+            assert_eq!(algorithm, rng.algorithm());
+            // Recording must not disturb the RNG it wraps.
+            assert_eq!(expected_rng.next_u64(), rng.next_u64());
+
+            let mut replay = TestRng::from_choices(algorithm, &choices);
+            assert_eq!(expected, draw(&mut replay));
+            assert_eq!(choices.len(), replay.choices_used());
+            assert_eq!(0, replay.next_u32());
+        }
+    }
+
     #[test]
     fn passthrough_rng_behaves_properly() {
         let mut rng = TestRng::from_seed(
@@ -7,15 +7,22 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{Arc, BTreeMap, Box, String, Vec};
+use crate::std_facade::{Arc, BTreeMap, BTreeSet, Box, String, Vec};
+use core::future::Future;
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicBool;
 use core::sync::atomic::AtomicUsize;
 use core::sync::atomic::Ordering::SeqCst;
-use core::{fmt, iter};
+use core::{fmt, iter, mem};
 #[cfg(feature = "std")]
 use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "fork")]
 use rusty_fork;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
 #[cfg(feature = "fork")]
 use std::cell::{Cell, RefCell};
 #[cfg(feature = "fork")]
@@ -26,17 +33,29 @@ use std::fs;
 use tempfile;
 
 use crate::strategy::*;
+use crate::test_runner::choices::{mutate_choices, CHOICE_PASSES};
 use crate::test_runner::config::*;
 use crate::test_runner::errors::*;
 use crate::test_runner::failure_persistence::PersistedSeed;
+#[cfg(feature = "fork")]
+use crate::test_runner::limits::ResourceLimits;
 use crate::test_runner::reason::*;
 #[cfg(feature = "fork")]
 use crate::test_runner::replay;
+#[cfg(feature = "std")]
+use crate::test_runner::report::{write_report, RunOutcome, RunReport};
 use crate::test_runner::result_cache::*;
-use crate::test_runner::rng::TestRng;
+use crate::test_runner::rng::{RngAlgorithm, TestRng};
+#[cfg(feature = "std")]
+use crate::test_runner::shrink_trace::{ShrinkMove, ShrinkTrace};
+use crate::test_runner::statistics::{CaseLabels, LabelStatistics};
 
 #[cfg(feature = "fork")]
 const ENV_FORK_FILE: &'static str = "_PROPTEST_FORKFILE";
+/// How many bytes from the end of a crashed child's output to include in the
+/// failure reason.
+#[cfg(feature = "fork")]
+const CHILD_OUTPUT_TAIL: u64 = 4096;
 
 const ALWAYS: u32 = 0;
 /// Verbose level 1 to show failures. In state machine tests this level is used
@@ -64,8 +83,27 @@ macro_rules! verbose_message {
     };
 }
 
+/// Notify `Config::observer`, if any, of an event.
+#[cfg(feature = "std")]
+macro_rules! observe {
+    ($runner:expr, |$observer:ident| $event:expr) => {
+        if let Some(ref mut $observer) = $runner.config.observer {
+            $event;
+        }
+    };
+}
+
+#[cfg(not(feature = "std"))]
+macro_rules! observe {
+    ($runner:expr, |$observer:ident| $event:expr) => {};
+}
+
 type RejectionDetail = BTreeMap<Reason, u32>;
 
+/// The value of `TestRunner::size` at which strategies generate values from
+/// their full range, as the size is stored in thousandths.
+pub(crate) const FULL_SIZE: u16 = 1000;
+
 /// State used when running a proptest test.
 #[derive(Clone)]
 pub struct TestRunner {
@@ -78,6 +116,50 @@ pub struct TestRunner {
 
     local_reject_detail: RejectionDetail,
     global_reject_detail: RejectionDetail,
+    shrink_iters: u32,
+
+    case_labels: CaseLabels,
+    label_statistics: LabelStatistics,
+
+    /// The size of the case being generated, in thousandths. See `size`.
+    size: u16,
+    /// The seed from `Config::rng_seed`, used as is for the first new case.
+    start_seed: Option<PersistedSeed>,
+    /// The seed which generated the most recent failing case, if known.
+    failure_seed: Option<PersistedSeed>,
+
+    /// The buckets of the failures found so far when collecting distinct
+    /// failures, as given by `failure_bucket`.
+    failure_buckets: BTreeSet<String>,
+    /// How many cases failed when collecting distinct failures.
+    failing_cases: u32,
+    /// The bucket of the failure being shrunk, when collecting distinct
+    /// failures.
+    shrink_bucket: Option<String>,
+
+    /// When the current run started, for `Config::max_run_time`.
+    #[cfg(feature = "std")]
+    run_start: Option<Instant>,
+    /// The time spent shrinking during the current run, which does not count
+    /// towards `Config::max_run_time`.
+    #[cfg(feature = "std")]
+    shrink_time: Duration,
+    /// Set by `run_with_watchdog` for the duration of the run, and raised
+    /// once a case overruns `Config::watchdog_timeout`.
+    #[cfg(feature = "std")]
+    watchdog_tripped: Option<Arc<AtomicBool>>,
+    /// How many shrink traces this runner has written to
+    /// `Config::shrink_trace_dir`.
+    #[cfg(feature = "std")]
+    shrink_traces: u32,
+    /// The trace of the shrinking in progress, while `shrink_choices` hands
+    /// it on to `shrink`.
+    #[cfg(feature = "std")]
+    shrink_trace: Option<ShrinkTrace>,
+    /// The labels recorded by the test cases of the replay steps which have
+    /// not been replayed yet.
+    #[cfg(feature = "fork")]
+    replay_labels: std::vec::IntoIter<CaseLabels>,
 }
 
 impl fmt::Debug for TestRunner {
@@ -91,6 +173,14 @@ impl fmt::Debug for TestRunner {
             .field("flat_map_regens", &self.flat_map_regens)
             .field("local_reject_detail", &self.local_reject_detail)
             .field("global_reject_detail", &self.global_reject_detail)
+            .field("shrink_iters", &self.shrink_iters)
+            .field("label_statistics", &self.label_statistics)
+            .field("size", &self.size)
+            .field("start_seed", &self.start_seed)
+            .field("failure_seed", &self.failure_seed)
+            .field("failure_buckets", &self.failure_buckets)
+            .field("failing_cases", &self.failing_cases)
+            .field("shrink_bucket", &self.shrink_bucket)
             .finish()
     }
 }
@@ -110,6 +200,9 @@ impl fmt::Display for TestRunner {
         for (whence, count) in &self.global_reject_detail {
             writeln!(f, "\t\t{} times at {}", count, whence)?;
         }
+        if !self.label_statistics.is_empty() {
+            write!(f, "{}", self.label_statistics)?;
+        }
 
         Ok(())
     }
@@ -130,9 +223,9 @@ struct ForkOutput {
 
 #[cfg(feature = "fork")]
 impl ForkOutput {
-    fn append(&mut self, result: &TestCaseResult) {
+    fn append(&mut self, result: &TestCaseResult, labels: &CaseLabels) {
         if let Some(ref mut file) = self.file {
-            replay::append(file, result)
+            replay::append(file, result, labels)
                 .expect("Failed to append to replay file");
         }
     }
@@ -164,7 +257,7 @@ struct ForkOutput;
 
 #[cfg(not(feature = "fork"))]
 impl ForkOutput {
-    fn append(&mut self, _result: &TestCaseResult) {}
+    fn append(&mut self, _result: &TestCaseResult, _labels: &CaseLabels) {}
     fn ping(&mut self) {}
     fn terminate(&mut self) {}
     fn empty() -> Self {
@@ -225,11 +318,12 @@ where
     F: Fn(V) -> TestCaseResult,
     R: Iterator<Item = TestCaseResult>,
 {
-    use std::time;
+    use crate::test_runner::statistics;
 
     let timeout = runner.config.timeout();
 
     if let Some(result) = replay_from_fork.next() {
+        runner.case_labels = runner.next_replay_labels();
         return result.map(|_| TestCaseOk::ReplayFromForkSuccess);
     }
 
@@ -237,6 +331,10 @@ where
     // concerned), ping the replay file so the parent process can determine
     // that we made it this far.
     fork_output.ping();
+    #[cfg(feature = "fork")]
+    if fork_output.is_in_fork() {
+        ResourceLimits::new(&runner.config).start_case();
+    }
 
     verbose_message!(runner, TRACE, "Next test input: {:?}", case);
 
@@ -250,34 +348,38 @@ where
         return result.clone().map(|_| TestCaseOk::CacheHitSuccess);
     }
 
-    let time_start = time::Instant::now();
-
-    let mut result = unwrap_or!(
-        panic::catch_unwind(AssertUnwindSafe(|| test(case))),
-        what => Err(TestCaseError::Fail(
-            what.downcast::<&'static str>().map(|s| (*s).into())
-                .or_else(|what| what.downcast::<String>().map(|b| (*b).into()))
-                .or_else(|what| what.downcast::<Box<str>>().map(|b| (*b).into()))
-                .unwrap_or_else(|_| "<unknown panic value>".into()))));
+    // The input cannot be printed once it has been given to a watchdog thread
+    // which does not finish.
+    let watched_input = runner
+        .watchdog_tripped
+        .as_ref()
+        .map(|_| format!("{:?}", case));
+
+    let time_start = Instant::now();
+
+    let (result, labels) =
+        statistics::collect_labels(|| call_test_body(test, case));
+    runner.case_labels = labels;
+
+    if let Some(input) = watched_input {
+        if runner.watchdog_tripped() {
+            verbose_message!(
+                runner,
+                ALWAYS,
+                "Test case exceeded the watchdog timeout and is still \
+                 running; ending the run. Input: {}",
+                input
+            );
+        }
+    }
 
     // If there is a timeout and we exceeded it, fail the test here so we get
     // consistent behaviour. (The parent process cannot precisely time the test
     // cases itself.)
-    if timeout > 0 && result.is_ok() {
-        let elapsed = time_start.elapsed();
-        let elapsed_millis = elapsed.as_secs() as u32 * 1000
-            + elapsed.subsec_nanos() / 1_000_000;
-
-        if elapsed_millis > timeout {
-            result = Err(TestCaseError::fail(format!(
-                "Timeout of {} ms exceeded: test took {} ms",
-                timeout, elapsed_millis
-            )));
-        }
-    }
+    let result = check_timeout(result, timeout, time_start);
 
     result_cache.put(cache_key, &result);
-    fork_output.append(&result);
+    fork_output.append(&result, &runner.case_labels);
 
     match result {
         Ok(()) => verbose_message!(runner, TRACE, "Test case passed"),
@@ -298,20 +400,378 @@ where
     })
 }
 
+/// Fail `result` if the test passed but took longer than `timeout`
+/// milliseconds since `time_start`. A `timeout` of `0` means no timeout.
+#[cfg(feature = "std")]
+fn check_timeout(
+    result: TestCaseResult,
+    timeout: u32,
+    time_start: Instant,
+) -> TestCaseResult {
+    if timeout > 0 && result.is_ok() {
+        let elapsed = time_start.elapsed();
+        let elapsed_millis =
+            elapsed.as_secs() as u32 * 1000 + elapsed.subsec_millis();
+
+        if elapsed_millis > timeout {
+            return Err(TestCaseError::fail(format!(
+                "Timeout of {} ms exceeded: test took {} ms",
+                timeout, elapsed_millis
+            )));
+        }
+    }
+    result
+}
+
+/// Return the part of the failure message of `why` which tells distinct
+/// failures apart.
+///
+/// This is the location which `prop_assert!` and friends append to their
+/// message, or `call_test_body` to that of a panic, if there is one, and
+/// otherwise the first line of the message, so
+/// that the values `assert_eq!` and the like print on later lines do not
+/// matter.
+fn failure_bucket(why: &Reason) -> &str {
+    let message = why.message();
+    if let Some(ix) = message.rfind(" at ") {
+        let location = &message[ix + 4..];
+        let is_location =
+            location.rsplit_once(':').map_or(false, |(file, line)| {
+                !file.is_empty()
+                    && !line.is_empty()
+                    && line.bytes().all(|b| b.is_ascii_digit())
+            });
+        if is_location {
+            return location;
+        }
+    }
+    message.lines().next().unwrap_or("")
+}
+
+/// Explain in the reason of `error`, a failure found while climbing towards a
+/// higher `prop_target!` score, why it was not persisted.
+fn found_by_targeting<T>(error: TestError<T>) -> TestError<T> {
+    with_note(
+        error,
+        "(found by targeting, which derives inputs from generated ones; no \
+         seed reproduces this input, so it was not persisted and later runs \
+         may not find it)",
+    )
+}
+
+/// Append `note` as a line of its own to the reason of `error`, if it is a
+/// failure.
+fn with_note<T>(error: TestError<T>, note: impl fmt::Display) -> TestError<T> {
+    match error {
+        TestError::Fail(why, value) => {
+            TestError::Fail(format!("{}\n{}", why, note).into(), value)
+        }
+        TestError::Flaky(why, value, runs) => {
+            TestError::Flaky(format!("{}\n{}", why, note).into(), value, runs)
+        }
+        error => error,
+    }
+}
+
+/// A limit on the time a single shrink may take.
+#[cfg(feature = "std")]
+struct ShrinkTimeLimit {
+    /// How long the shrink may take.
+    time: Duration,
+    /// The setting which imposes the limit, for messages.
+    controller: &'static str,
+    /// The configured value of that setting, in milliseconds.
+    configured: u32,
+    /// Why shrinking stopped once it took longer than `time`.
+    stopped: &'static str,
+}
+
+/// Combine the distinct failures found by a run into the error it fails with.
+fn distinct_failures<T>(mut failures: Vec<TestError<T>>) -> TestError<T> {
+    if failures.len() == 1 {
+        failures.pop().unwrap()
+    } else {
+        TestError::MultipleFailures(failures)
+    }
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// Where the last panic on this thread happened, if the panic hook
+    /// installed by `PanicLocationHook` saw it.
+    static PANIC_LOCATION: std::cell::RefCell<Option<String>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// A panic hook, as returned by `panic::take_hook`.
+#[cfg(feature = "std")]
+#[allow(deprecated)] // `PanicInfo` is called `PanicHookInfo` in newer Rust.
+type PanicHook = Box<dyn Fn(&panic::PanicInfo<'_>) + Sync + Send + 'static>;
+
+/// The number of live `PanicLocationHook`s, and the hook which theirs
+/// replaced while it is installed.
+#[cfg(feature = "std")]
+static PANIC_HOOK: std::sync::Mutex<(usize, Option<Arc<PanicHook>>)> =
+    std::sync::Mutex::new((0, None));
+
+/// While any of these are alive, a panic hook is installed which records the
+/// location of each panic in `PANIC_LOCATION` and then calls the previous
+/// hook. The previous hook is reinstated when the last one is dropped.
+#[cfg(feature = "std")]
+struct PanicLocationHook(());
+
+#[cfg(feature = "std")]
+impl PanicLocationHook {
+    fn install() -> Self {
+        let mut state = PANIC_HOOK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if state.1.is_none() {
+            let previous = Arc::new(panic::take_hook());
+            state.1 = Some(Arc::clone(&previous));
+            panic::set_hook(Box::new(move |info| {
+                let location = info.location().map(|location| {
+                    format!("{}:{}", location.file(), location.line())
+                });
+                let _ = PANIC_LOCATION.try_with(|l| *l.borrow_mut() = location);
+                previous(info);
+            }));
+        }
+        state.0 += 1;
+        PanicLocationHook(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for PanicLocationHook {
+    fn drop(&mut self) {
+        let mut state = PANIC_HOOK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        state.0 -= 1;
+        // The hook cannot be changed while panicking, in which case ours is
+        // left in place for the next `install` to reuse.
+        if 0 != state.0 || std::thread::panicking() {
+            return;
+        }
+        if let Some(previous) = state.1.take() {
+            // Dropping our hook drops its reference to the previous one.
+            drop(panic::take_hook());
+            match Arc::try_unwrap(previous) {
+                Ok(previous) => panic::set_hook(previous),
+                Err(previous) => {
+                    panic::set_hook(Box::new(move |info| previous(info)))
+                }
+            }
+        }
+    }
+}
+
+/// Run `test` on `case`, turning any panic into a `TestCaseError::Fail`.
+///
+/// The location of the panic is appended to its message the same way
+/// `prop_assert!` and friends do, so that `failure_bucket` can tell panics
+/// apart by where they happened.
+#[cfg(feature = "std")]
+fn call_test_body<V, F>(test: &F, case: V) -> TestCaseResult
+where
+    F: Fn(V) -> TestCaseResult,
+{
+    let _hook = PanicLocationHook::install();
+    PANIC_LOCATION.with(|l| l.borrow_mut().take());
+    unwrap_or!(
+    panic::catch_unwind(AssertUnwindSafe(|| test(case))),
+    what => {
+        let message: String = what.downcast::<&'static str>().map(|s| (*s).into())
+            .or_else(|what| what.downcast::<String>().map(|b| *b))
+            .or_else(|what| what.downcast::<Box<str>>().map(|b| (*b).into()))
+            .unwrap_or_else(|_| "<unknown panic value>".into());
+        Err(TestCaseError::Fail(
+            match PANIC_LOCATION.with(|l| l.borrow_mut().take()) {
+                Some(location) => format!("{} at {}", message, location),
+                None => message,
+            }.into()))
+    })
+}
+
+/// Run `test` on each of `cases` using up to `threads` worker threads,
+/// returning the results and the labels each case recorded in the same order
+/// as the inputs. Cases which pass but take longer than `timeout`
+/// milliseconds fail, as with `call_test`.
+#[cfg(feature = "std")]
+fn call_test_batch<V, F>(
+    test: &F,
+    cases: Vec<V>,
+    threads: usize,
+    timeout: u32,
+) -> Vec<(TestCaseResult, CaseLabels)>
+where
+    V: Send,
+    F: Fn(V) -> TestCaseResult + Sync,
+{
+    use crate::test_runner::statistics;
+    use std::sync::Mutex;
+    use std::thread;
+
+    let len = cases.len();
+    let queue = Mutex::new(cases.into_iter().enumerate());
+    let mut results: Vec<Option<(TestCaseResult, CaseLabels)>> =
+        vec![None; len];
+
+    thread::scope(|scope| {
+        let workers = (0..threads.min(len))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let next = queue
+                            .lock()
+                            .expect("Test case queue poisoned")
+                            .next();
+                        match next {
+                            Some((ix, case)) => {
+                                let time_start = Instant::now();
+                                let (result, labels) =
+                                    statistics::collect_labels(|| {
+                                        call_test_body(test, case)
+                                    });
+                                let result =
+                                    check_timeout(result, timeout, time_start);
+                                done.push((ix, (result, labels)));
+                            }
+                            None => break done,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            for (ix, result) in
+                worker.join().expect("Test worker thread panicked")
+            {
+                results[ix] = Some(result);
+            }
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.expect("Test case was never run"))
+        .collect()
+}
+
+/// Run `test` on `case` on a new thread, failing if it does not finish within
+/// `timeout` milliseconds, in which case `tripped` is raised and the thread is
+/// left running.
+#[cfg(feature = "std")]
+fn call_test_watched<V, F>(
+    test: &Arc<F>,
+    case: V,
+    timeout: u32,
+    tripped: &AtomicBool,
+) -> TestCaseResult
+where
+    V: Send + 'static,
+    F: Fn(V) -> TestCaseResult + Send + Sync + 'static,
+{
+    use crate::test_runner::statistics;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::thread;
+
+    let (sender, receiver) = mpsc::channel();
+    let test = Arc::clone(test);
+    thread::spawn(move || {
+        let result =
+            statistics::collect_labels(|| call_test_body(&*test, case));
+        // Nobody is listening any more if the case timed out.
+        let _ = sender.send(result);
+    });
+
+    match receiver.recv_timeout(Duration::from_millis(timeout.into())) {
+        Ok((result, labels)) => {
+            statistics::merge_labels(labels);
+            result
+        }
+        Err(RecvTimeoutError::Timeout) => {
+            tripped.store(true, SeqCst);
+            Err(TestCaseError::fail(format!(
+                "Timeout of {} ms exceeded: test is still running on a \
+                 watchdog thread",
+                timeout
+            )))
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(TestCaseError::fail(
+            "Watchdog thread exited without a result",
+        )),
+    }
+}
+
 type TestRunResult<S> = Result<(), TestError<<S as Strategy>::Value>>;
 
+/// A value tree holding a value loaded through `ValuePersistence`.
+///
+/// Values are not required to implement `Clone`, so `current()` deserializes
+/// a fresh copy each time. The value cannot be shrunk.
+#[cfg(feature = "serde")]
+struct PersistedValue<T> {
+    serialized: String,
+    _marker: core::marker::PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T: DeserializeOwned> PersistedValue<T> {
+    fn new(serialized: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<T>(serialized)?;
+        Ok(PersistedValue {
+            serialized: serialized.into(),
+            _marker: core::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: DeserializeOwned + fmt::Debug> ValueTree for PersistedValue<T> {
+    type Value = T;
+
+    fn current(&self) -> T {
+        serde_json::from_str(&self.serialized)
+            .expect("persisted value no longer deserializes")
+    }
+
+    fn simplify(&mut self) -> bool {
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        false
+    }
+}
+
 impl TestRunner {
     /// Create a fresh `TestRunner` with the given configuration.
     ///
     /// The runner will use an RNG with a generated seed and the default
-    /// algorithm.
+    /// algorithm, unless `Config::rng_seed` is set, in which case it starts
+    /// from that seed and the first new test case is generated from it.
     ///
     /// In `no_std` environments, every `TestRunner` will use the same
     /// hard-coded seed. This seed is not contractually guaranteed and may be
     /// changed between releases without notice.
     pub fn new(config: Config) -> Self {
         let algorithm = config.rng_algorithm;
-        TestRunner::new_with_rng(config, TestRng::default_rng(algorithm))
+        match config.rng_seed.clone() {
+            Some(seed) => {
+                let rng = TestRng::from_seed_internal(seed.0.clone());
+                let mut runner = TestRunner::new_with_rng(config, rng);
+                runner.start_seed = Some(seed);
+                runner
+            }
+            None => TestRunner::new_with_rng(
+                config,
+                TestRng::default_rng(algorithm),
+            ),
+        }
     }
 
     /// Create a fresh `TestRunner` with the standard deterministic RNG.
@@ -346,6 +806,27 @@ impl TestRunner {
             flat_map_regens: Arc::new(AtomicUsize::new(0)),
             local_reject_detail: BTreeMap::new(),
             global_reject_detail: BTreeMap::new(),
+            shrink_iters: 0,
+            case_labels: CaseLabels::default(),
+            label_statistics: LabelStatistics::default(),
+            size: FULL_SIZE,
+            start_seed: None,
+            failure_seed: None,
+            failure_buckets: BTreeSet::new(),
+            failing_cases: 0,
+            shrink_bucket: None,
+            #[cfg(feature = "std")]
+            run_start: None,
+            #[cfg(feature = "std")]
+            shrink_time: Duration::ZERO,
+            #[cfg(feature = "std")]
+            watchdog_tripped: None,
+            #[cfg(feature = "std")]
+            shrink_traces: 0,
+            #[cfg(feature = "std")]
+            shrink_trace: None,
+            #[cfg(feature = "fork")]
+            replay_labels: Vec::new().into_iter(),
         }
     }
 
@@ -362,7 +843,68 @@ impl TestRunner {
             flat_map_regens: Arc::clone(&self.flat_map_regens),
             local_reject_detail: BTreeMap::new(),
             global_reject_detail: BTreeMap::new(),
+            shrink_iters: 0,
+            case_labels: CaseLabels::default(),
+            label_statistics: LabelStatistics::default(),
+            size: self.size,
+            start_seed: None,
+            failure_seed: None,
+            failure_buckets: BTreeSet::new(),
+            failing_cases: 0,
+            shrink_bucket: None,
+            #[cfg(feature = "std")]
+            run_start: None,
+            #[cfg(feature = "std")]
+            shrink_time: Duration::ZERO,
+            #[cfg(feature = "std")]
+            watchdog_tripped: None,
+            #[cfg(feature = "std")]
+            shrink_traces: 0,
+            #[cfg(feature = "std")]
+            shrink_trace: None,
+            #[cfg(feature = "fork")]
+            replay_labels: Vec::new().into_iter(),
+        }
+    }
+
+    /// Returns the seed which generated the most recent failing case found by
+    /// this runner, if there is one which reproduces it.
+    ///
+    /// This is the seed that is persisted, and which reproduces the case when
+    /// set as `Config::rng_seed`. It is also mentioned in the reason of the
+    /// failure the run returns.
+    pub fn failure_seed(&self) -> Option<&PersistedSeed> {
+        self.failure_seed.as_ref()
+    }
+
+    /// Returns the size of the case currently being generated, between 0
+    /// (exclusive) and 1.
+    ///
+    /// Strategies generating values of varying size use this to scale the
+    /// upper bound of their range down, so that a run starts with small
+    /// values which grow as it goes on. The size is always 1 unless
+    /// `Config::ramp_size` is enabled and this runner is running a test.
+    /// Custom strategies can follow it through `scale_to_size`.
+    pub fn size(&self) -> f64 {
+        f64::from(self.size) / f64::from(FULL_SIZE)
+    }
+
+    /// Scale the inclusive range `low..=high` down to the current `size`,
+    /// returning the new upper bound.
+    ///
+    /// The result is never less than `low`, and is exactly `high` when the
+    /// size is 1.
+    pub fn scale_to_size(&self, low: usize, high: usize) -> usize {
+        if self.size >= FULL_SIZE || high <= low {
+            return high;
         }
+
+        // Round up, so that a range which can grow only does so as soon as
+        // the size is above 0.
+        let span = (high - low) as u128;
+        let full = u128::from(FULL_SIZE);
+        let scaled = (span * u128::from(self.size) + full - 1) / full;
+        low + scaled as usize
     }
 
     /// Returns the RNG for this test run.
@@ -401,17 +943,95 @@ impl TestRunner {
     /// tested first. If a later non-persisted case fails, its seed is
     /// persisted before returning failure.
     ///
+    /// If `Config::report_dir` is set, a report of the run is written there
+    /// once it is over, and `Config::observer` is notified of its events.
+    ///
     /// Returns success or failure indicating why the test as a whole failed.
     pub fn run<S: Strategy>(
         &mut self,
         strategy: &S,
         test: impl Fn(S::Value) -> TestCaseResult,
     ) -> TestRunResult<S> {
-        if self.config.fork() {
+        #[cfg(feature = "std")]
+        let start = Instant::now();
+        #[cfg(feature = "std")]
+        self.warn_if_watchdog_ignored();
+
+        let result = if self.config.fork() {
             self.run_in_fork(strategy, test)
         } else {
             self.run_in_process(strategy, test)
+        };
+        // Values generated outside of a run use the full size again.
+        self.size = FULL_SIZE;
+
+        #[cfg(feature = "std")]
+        self.finish_run(&result, start.elapsed());
+        result
+    }
+
+    /// Like `run`, but the values of failing cases are persisted as well as
+    /// their seeds.
+    ///
+    /// If `Config::value_persistence` is set, every value it supplies is
+    /// deserialized and tested before anything else, including the persisted
+    /// seeds. Since the value does not depend on the strategy, it keeps
+    /// reproducing the failure after the strategy changes. A persisted value
+    /// which fails is reported as is, without shrinking, while one which no
+    /// longer deserializes is skipped with a warning. When a new failure is
+    /// found, flaky or not, the minimal failing value is saved in addition to
+    /// its seed.
+    ///
+    /// Persisted values are tested in-process, so they are not replayed when
+    /// `fork` is enabled; the persisted seeds still are.
+    ///
+    /// This requires the "serde" feature.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn run_with_value_persistence<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> TestRunResult<S>
+    where
+        S::Value: Serialize + DeserializeOwned,
+    {
+        let persisted_values: Vec<String> = self
+            .config
+            .value_persistence
+            .as_ref()
+            .map(|p| {
+                p.load_persisted_values(
+                    self.config.source_file,
+                    self.config.test_name,
+                )
+            })
+            .unwrap_or_default();
+
+        if !self.config.fork() {
+            for serialized in &persisted_values {
+                self.run_persisted_value(serialized, &test)?;
+            }
+        }
+
+        let result = self.run(strategy, &test);
+        match result {
+            Err(TestError::Fail(_, ref value))
+            | Err(TestError::Flaky(_, ref value, _)) => {
+                self.persist_value(value, &persisted_values)
+            }
+            Err(TestError::MultipleFailures(ref failures)) => {
+                for failure in failures {
+                    if let TestError::Fail(_, ref value)
+                    | TestError::Flaky(_, ref value, _) = *failure
+                    {
+                        self.persist_value(value, &persisted_values);
+                    }
+                }
+            }
+            _ => (),
         }
+        result
     }
 
     #[cfg(not(feature = "fork"))]
@@ -443,9 +1063,11 @@ impl TestRunner {
         let mut replay = replay::Replay {
             seed,
             steps: vec![],
+            labels: vec![],
         };
         let mut child_count = 0;
         let timeout = self.config.timeout();
+        let limits = ResourceLimits::new(&self.config);
 
         fn forkfile_size(forkfile: &Option<tempfile::NamedTempFile>) -> u64 {
             forkfile.as_ref().map_or(0, |ff| {
@@ -472,12 +1094,15 @@ impl TestRunner {
                     init_forkfile_size.set(forkfile_size(&forkfile));
 
                     cmd.env(ENV_FORK_FILE, forkfile.as_ref().unwrap().path());
+                    limits.apply(cmd);
                 },
-                |child, _| {
+                |child, output| {
                     await_child(
                         child,
+                        output,
                         &mut forkfile.borrow_mut().as_mut().unwrap(),
                         timeout,
+                        limits,
                     )
                 },
                 || match self.run_in_process(strategy, test.take().unwrap()) {
@@ -494,12 +1119,15 @@ impl TestRunner {
                 &mut forkfile.borrow_mut().as_mut().unwrap(),
             )
             .expect("Failed to re-read fork file");
+            // Merge rather than replace the steps, so that the synthetic
+            // failures added below keep their reasons, which the file does not
+            // record.
             match parsed {
                 replay::ReplayFileStatus::InProgress(new_replay) => {
-                    replay = new_replay
+                    replay.merge(&new_replay)
                 }
                 replay::ReplayFileStatus::Terminated(new_replay) => {
-                    replay = new_replay;
+                    replay.merge(&new_replay);
                     break;
                 }
                 replay::ReplayFileStatus::Corrupt => {
@@ -534,9 +1162,14 @@ impl TestRunner {
                     "Child process was terminated abruptly \
                      but with successful status",
                 )));
-                replay::append(forkfile.borrow_mut().as_mut().unwrap(), &error)
-                    .expect("Failed to append to replay file");
+                replay::append(
+                    forkfile.borrow_mut().as_mut().unwrap(),
+                    &error,
+                    &CaseLabels::default(),
+                )
+                .expect("Failed to append to replay file");
                 replay.steps.push(error);
+                replay.labels.push(CaseLabels::default());
             }
 
             // Bail if we've gone through too many processes in case the
@@ -553,6 +1186,7 @@ impl TestRunner {
         // tests) to produce the shrunken value and update the persistence
         // file.
         self.rng.set_seed(replay.seed);
+        self.replay_labels = replay.labels.into_iter();
         self.run_in_process_with_replay(
             strategy,
             |_| panic!("Ran past the end of the replay"),
@@ -566,7 +1200,7 @@ impl TestRunner {
         strategy: &S,
         test: impl Fn(S::Value) -> TestCaseResult,
     ) -> TestRunResult<S> {
-        let (replay_steps, fork_output) = init_replay(&mut self.rng);
+        let (replay_steps, fork_output) = init_replay(self);
         self.run_in_process_with_replay(
             strategy,
             test,
@@ -582,723 +1216,3866 @@ impl TestRunner {
         mut replay_from_fork: impl Iterator<Item = TestCaseResult>,
         mut fork_output: ForkOutput,
     ) -> TestRunResult<S> {
-        let old_rng = self.rng.clone();
-
-        let persisted_failure_seeds: Vec<PersistedSeed> = self
-            .config
-            .failure_persistence
-            .as_ref()
-            .map(|f| f.load_persisted_failures2(self.config.source_file))
-            .unwrap_or_default();
-
         let mut result_cache = self.new_cache();
-
-        for PersistedSeed(persisted_seed) in persisted_failure_seeds {
-            self.rng.set_seed(persisted_seed);
-            self.gen_and_run_case(
-                strategy,
-                &test,
-                &mut replay_from_fork,
-                &mut *result_cache,
-                &mut fork_output,
-                true,
-            )?;
+        let mut failures = Vec::new();
+        #[cfg(feature = "std")]
+        {
+            self.run_start = Some(Instant::now());
         }
-        self.rng = old_rng;
 
-        while self.successes < self.config.cases {
-            // Generate a new seed and make an RNG from that so that we know
-            // what seed to persist if this case fails.
-            let seed = self.rng.gen_get_seed();
-            let result = self.gen_and_run_case(
-                strategy,
+        self.run_persisted_failures(
+            strategy,
+            &test,
+            &mut replay_from_fork,
+            &mut *result_cache,
+            &mut fork_output,
+            &mut failures,
+        )?;
+
+        if let Some(enumerable) = self.exhaustive_strategy(strategy) {
+            let result = self.run_exhaustively(
+                enumerable,
                 &test,
                 &mut replay_from_fork,
                 &mut *result_cache,
                 &mut fork_output,
-                false,
+                &mut failures,
             );
-            if let Err(TestError::Fail(_, ref value)) = result {
-                if let Some(ref mut failure_persistence) =
-                    self.config.failure_persistence
-                {
-                    let source_file = &self.config.source_file;
-
-                    // Don't update the persistence file if we're a child
-                    // process. The parent relies on it remaining consistent
-                    // and will take care of updating it itself.
-                    if !fork_output.is_in_fork() {
-                        failure_persistence.save_persisted_failure2(
-                            *source_file,
-                            PersistedSeed(seed),
-                            value,
+            if let Err(e) = result {
+                fork_output.terminate();
+                return Err(e);
+            }
+        } else {
+            let random_cases = self.random_cases();
+            let mut best_target: Option<(f64, PersistedSeed)> = None;
+
+            while self.wants_more_cases() {
+                if self.successes >= random_cases {
+                    if let Some(start) = best_target.take() {
+                        let result = self.climb_target(
+                            strategy,
+                            &test,
+                            start,
+                            &mut replay_from_fork,
+                            &mut *result_cache,
+                            &mut fork_output,
                         );
+                        if let Err(e) = result {
+                            if let Some(e) =
+                                self.collect_failure(e, &mut failures)
+                            {
+                                fork_output.terminate();
+                                return Err(e);
+                            }
+                        }
+                        continue;
                     }
                 }
-            }
 
-            if let Err(e) = result {
-                fork_output.terminate();
-                return Err(e.into());
+                // Generate a new seed and make an RNG from that so that we know
+                // what seed to persist if this case fails.
+                let seed = self.next_case_seed(0);
+                let result = self.gen_and_run_case(
+                    strategy,
+                    &test,
+                    &mut replay_from_fork,
+                    &mut *result_cache,
+                    &mut fork_output,
+                    false,
+                );
+                let result = result.map_err(|e| {
+                    self.persist_failure(seed.clone(), e, &fork_output)
+                });
+
+                match result {
+                    Ok(Some(score)) => {
+                        if best_target
+                            .as_ref()
+                            .map_or(true, |&(best, _)| score > best)
+                        {
+                            best_target = Some((score, seed));
+                        }
+                    }
+                    Ok(None) => (),
+                    Err(e) => {
+                        if let Some(e) = self.collect_failure(e, &mut failures)
+                        {
+                            fork_output.terminate();
+                            return Err(e);
+                        }
+                    }
+                }
             }
         }
 
+        #[cfg(feature = "std")]
+        self.report_timed_run();
+
+        let result = if failures.is_empty() {
+            self.finish_label_statistics(&fork_output)
+        } else {
+            Err(distinct_failures(failures))
+        };
         fork_output.terminate();
-        Ok(())
+        result
     }
 
-    fn gen_and_run_case<S: Strategy>(
+    /// Return `strategy` as an `Enumerable` if the test is to be run on each
+    /// of its values instead of on random cases, which is when it has at most
+    /// `Config::exhaustive_threshold` of them and no more than `Config::cases`.
+    fn exhaustive_strategy<'a, S: Strategy>(
+        &self,
+        strategy: &'a S,
+    ) -> Option<&'a dyn Enumerable<Tree = S::Tree, Value = S::Value>> {
+        let threshold =
+            u64::from(self.config.exhaustive_threshold.min(self.config.cases));
+        let enumerable = strategy.as_enumerable()?;
+        match enumerable.domain_size() {
+            Some(size) if 0 < size && size <= threshold => Some(enumerable),
+            _ => None,
+        }
+    }
+
+    /// Run the test once on every value of `strategy`, in order.
+    ///
+    /// Returns the error the run should end with, if any, in the same way as
+    /// `collect_failure`. Failures are shrunk as usual, but not persisted,
+    /// since the next run will test the same values again anyway.
+    fn run_exhaustively<T: ValueTree>(
         &mut self,
-        strategy: &S,
-        f: &impl Fn(S::Value) -> TestCaseResult,
+        strategy: &dyn Enumerable<Tree = T, Value = T::Value>,
+        test: &impl Fn(T::Value) -> TestCaseResult,
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
         result_cache: &mut dyn ResultCache,
         fork_output: &mut ForkOutput,
-        is_from_persisted_seed: bool,
-    ) -> TestRunResult<S> {
-        let case = unwrap_or!(strategy.new_tree(self), msg =>
-                return Err(TestError::Abort(msg)));
+        failures: &mut Vec<TestError<T::Value>>,
+    ) -> Result<(), TestError<T::Value>> {
+        let domain_size = strategy.domain_size().unwrap_or(0);
+        verbose_message!(
+            self,
+            INFO_LOG,
+            "Testing all {} values of the strategy",
+            domain_size
+        );
 
-        // We only count new cases to our set of successful runs against
-        // `PROPTEST_CASES` config.
-        let ok_type = self.run_one_with_replay(
-            case,
-            f,
-            replay_from_fork,
-            result_cache,
-            fork_output,
-            is_from_persisted_seed,
-        )?;
-        match ok_type {
-            TestCaseOk::NewCaseSuccess | TestCaseOk::ReplayFromForkSuccess => {
-                self.successes += 1
+        self.size = FULL_SIZE;
+        for index in 0..domain_size {
+            let result = match strategy.enumerate_tree(index, self) {
+                Ok(mut case) => self.run_case(
+                    &mut case,
+                    test,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    false,
+                ),
+                Err(msg) => Err(TestError::Abort(msg)),
+            };
+
+            match result {
+                Ok(ok_type) => {
+                    self.record_case_result(ok_type);
+                }
+                Err(e) => {
+                    if let Some(e) = self.collect_failure(e, failures) {
+                        return Err(e);
+                    }
+                }
             }
-            TestCaseOk::PersistedCaseSuccess
-            | TestCaseOk::CacheHitSuccess
-            | TestCaseOk::Reject => (),
         }
-
         Ok(())
     }
 
-    /// Run one specific test case against this runner.
-    ///
-    /// If the test fails, finds the minimal failing test case. If the test
-    /// does not fail, returns whether it succeeded or was filtered out.
-    ///
-    /// This does not honour the `fork` config, and will not be able to
-    /// terminate the run if it runs for longer than `timeout`. However, if the
-    /// test function returns but took longer than `timeout`, the test case
-    /// will fail.
-    pub fn run_one<V: ValueTree>(
-        &mut self,
-        case: V,
-        test: impl Fn(V::Value) -> TestCaseResult,
-    ) -> Result<bool, TestError<V::Value>> {
-        let mut result_cache = self.new_cache();
-        self.run_one_with_replay(
-            case,
-            test,
-            &mut iter::empty::<TestCaseResult>().fuse(),
-            &mut *result_cache,
-            &mut ForkOutput::empty(),
-            false,
-        )
-        .map(|ok_type| match ok_type {
-            TestCaseOk::Reject => false,
-            _ => true,
-        })
+    /// Return how many cases are to be generated at random before switching
+    /// to hill-climbing on the best `prop_target!` score.
+    fn random_cases(&self) -> u32 {
+        // Targeting explores cases that depend on the scores reported by the
+        // test, which are not recorded in the replay file, so a forking
+        // parent would be unable to reproduce the child's steps.
+        if self.config.fork() {
+            return self.config.cases;
+        }
+
+        let target_percent = self.config.target_percent.min(100);
+        let target_cases =
+            u64::from(self.config.cases) * u64::from(target_percent) / 100;
+        self.config.cases - target_cases as u32
     }
 
-    fn run_one_with_replay<V: ValueTree>(
+    /// Spend the rest of the `cases` budget trying to increase the score
+    /// reported via `prop_target!`, starting from the best case found so far,
+    /// given as its score and the seed which generated it.
+    ///
+    /// Neighbouring inputs are explored by walking the value tree with
+    /// `simplify` and `complicate`, keeping every step that raises the score
+    /// just like shrinking keeps every step that still fails. Once a tree has
+    /// nothing more to offer, a fresh random case is tried instead, which
+    /// becomes the new starting point if it scores higher.
+    fn climb_target<S: Strategy>(
         &mut self,
-        mut case: V,
-        test: impl Fn(V::Value) -> TestCaseResult,
+        strategy: &S,
+        test: &impl Fn(S::Value) -> TestCaseResult,
+        (mut best, seed): (f64, PersistedSeed),
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
         result_cache: &mut dyn ResultCache,
         fork_output: &mut ForkOutput,
-        is_from_persisted_seed: bool,
-    ) -> Result<TestCaseOk, TestError<V::Value>> {
-        let result = call_test(
+    ) -> TestRunResult<S> {
+        verbose_message!(
             self,
-            case.current(),
-            &test,
-            replay_from_fork,
-            result_cache,
-            fork_output,
-            is_from_persisted_seed,
+            INFO_LOG,
+            "Targeting: climbing from a case with score {}",
+            best
         );
 
-        match result {
-            Ok(success_type) => Ok(success_type),
-            Err(TestCaseError::Fail(why)) => {
-                let why = self
-                    .shrink(
-                        &mut case,
-                        test,
-                        replay_from_fork,
-                        result_cache,
-                        fork_output,
-                        is_from_persisted_seed,
-                    )
-                    .unwrap_or(why);
-                Err(TestError::Fail(why, case.current()))
+        // Regenerate the case from its seed without disturbing the RNG, from
+        // which the seeds of any new cases are still drawn, so that they are
+        // not the ones already tried before climbing.
+        let (rng, size) = (self.rng.clone(), self.size);
+        self.use_case_seed(&seed);
+        let case = strategy.new_tree(self);
+        self.rng = rng;
+        self.size = size;
+        let mut case =
+            unwrap_or!(case, msg => return Err(TestError::Abort(msg)));
+        let mut has_candidate = case.simplify();
+
+        while self.wants_more_cases() {
+            if !has_candidate {
+                let seed = self.next_case_seed(0);
+                let mut fresh = unwrap_or!(strategy.new_tree(self), msg =>
+                        return Err(TestError::Abort(msg)));
+                let result = self.run_case(
+                    &mut fresh,
+                    test,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    false,
+                );
+                let result = result
+                    .map_err(|e| self.persist_failure(seed, e, fork_output));
+
+                if let Some(score) = self.record_case_result(result?) {
+                    if score > best {
+                        verbose_message!(
+                            self,
+                            INFO_LOG,
+                            "Targeting: restarting from a case with score {}",
+                            score
+                        );
+                        best = score;
+                        case = fresh;
+                        has_candidate = case.simplify();
+                    }
+                }
+                continue;
             }
-            Err(TestCaseError::Reject(whence)) => {
-                self.reject_global(whence)?;
-                Ok(TestCaseOk::Reject)
+
+            let result = self
+                .run_case(
+                    &mut case,
+                    test,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    false,
+                )
+                .map_err(found_by_targeting);
+
+            match self.record_case_result(result?) {
+                Some(score) if score > best => {
+                    verbose_message!(
+                        self,
+                        TRACE,
+                        "Targeting: score increased to {}",
+                        score
+                    );
+                    best = score;
+                    has_candidate = case.simplify();
+                }
+                _ => has_candidate = case.complicate(),
             }
         }
+
+        Ok(())
     }
 
-    fn shrink<V: ValueTree>(
+    /// Like `run`, but `test` is an `async` function, and `block_on` runs the
+    /// futures it returns to completion.
+    ///
+    /// This does not depend on any particular executor: `block_on` can be
+    /// something like `|future| runtime.block_on(future)` for the runtime the
+    /// code under test needs, or [`block_on`](fn.block_on.html) for futures
+    /// which do not need one. Each case is otherwise run exactly as `run`
+    /// would run it, so shrinking, forking, timeouts and failure persistence
+    /// all behave the same.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::{block_on, TestRunner};
+    ///
+    /// async fn double(v: u32) -> u32 {
+    ///     v * 2
+    /// }
+    ///
+    /// let mut runner = TestRunner::default();
+    /// runner
+    ///     .run_async(
+    ///         &(0u32..1000),
+    ///         |v| async move {
+    ///             prop_assert_eq!(v + v, double(v).await);
+    ///             Ok(())
+    ///         },
+    ///         block_on,
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn run_async<S: Strategy, F: Future<Output = TestCaseResult>>(
         &mut self,
-        case: &mut V,
-        test: impl Fn(V::Value) -> TestCaseResult,
-        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
-        result_cache: &mut dyn ResultCache,
-        fork_output: &mut ForkOutput,
-        is_from_persisted_seed: bool,
-    ) -> Option<Reason> {
-        #[cfg(feature = "std")]
-        use std::time;
+        strategy: &S,
+        test: impl Fn(S::Value) -> F,
+        block_on: impl Fn(F) -> TestCaseResult,
+    ) -> TestRunResult<S> {
+        self.run(strategy, |value| block_on(test(value)))
+    }
 
-        let mut last_failure = None;
-        let mut iterations = 0;
+    /// Like `run`, but each test case is run on its own thread and fails if it
+    /// takes longer than `Config::watchdog_timeout`.
+    ///
+    /// Since a thread cannot be killed, the thread running a case which
+    /// overruns is left running in the background. The run then ends straight
+    /// away, failing with that case, which is neither re-run nor shrunk, and
+    /// its input is also printed to stderr as soon as the timeout expires.
+    /// If a simpler case tried while shrinking another failure overruns,
+    /// shrinking stops there and the run fails with that case instead.
+    ///
+    /// If `watchdog_timeout` is `0` or the configuration implies forking,
+    /// this simply delegates to `run`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn run_with_watchdog<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult + Send + Sync + 'static,
+    ) -> TestRunResult<S>
+    where
+        S::Value: Send + 'static,
+    {
+        let timeout = self.config.watchdog_timeout;
+        if 0 == timeout || self.config.fork() {
+            return self.run(strategy, test);
+        }
+
+        let test = Arc::new(test);
+        let tripped = Arc::new(AtomicBool::new(false));
+        self.watchdog_tripped = Some(Arc::clone(&tripped));
+        let result = self.run(strategy, |case| {
+            call_test_watched(&test, case, timeout, &tripped)
+        });
+        self.watchdog_tripped = None;
+        result
+    }
+
+    /// Whether `Config::watchdog_timeout` is set but will not be honoured,
+    /// because the test is not being run by `run_with_watchdog`.
+    #[cfg(feature = "std")]
+    fn watchdog_ignored(&self) -> bool {
+        0 != self.config.watchdog_timeout
+            && self.watchdog_tripped.is_none()
+            && !self.config.fork()
+    }
+
+    /// Warn if `Config::watchdog_timeout` is set but will not be honoured, so
+    /// that it does not silently look like it is in effect.
+    #[cfg(feature = "std")]
+    fn warn_if_watchdog_ignored(&self) {
+        if self.watchdog_ignored() {
+            verbose_message!(
+                self,
+                ALWAYS,
+                "Ignoring the watchdog timeout of {} ms, which is only \
+                 honoured by TestRunner::run_with_watchdog and not by \
+                 proptest! tests; use Config::timeout to limit the time \
+                 each case takes there",
+                self.config.watchdog_timeout
+            );
+        }
+    }
+
+    /// Whether a case run by `run_with_watchdog` overran its timeout, which
+    /// ends the run.
+    fn watchdog_tripped(&self) -> bool {
         #[cfg(feature = "std")]
-        let start_time = time::Instant::now();
+        {
+            if let Some(ref tripped) = self.watchdog_tripped {
+                return tripped.load(SeqCst);
+            }
+        }
+        false
+    }
 
-        if case.simplify() {
-            loop {
-                #[cfg(feature = "std")]
-                let timed_out = if self.config.max_shrink_time > 0 {
-                    let elapsed = start_time.elapsed();
-                    let elapsed_ms = elapsed
-                        .as_secs()
-                        .saturating_mul(1000)
-                        .saturating_add(elapsed.subsec_millis().into());
-                    if elapsed_ms > self.config.max_shrink_time as u64 {
-                        Some(elapsed_ms)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                };
-                #[cfg(not(feature = "std"))]
-                let timed_out: Option<u64> = None;
+    /// Like `run`, but runs the test cases on `Config::threads` threads.
+    ///
+    /// Inputs are generated on the calling thread in the same order and from
+    /// the same seeds as `run` would use, and the results are then processed
+    /// in that order. This means that the cases tried, the case which is
+    /// shrunk (the first one to fail), the shrinking itself and the persisted
+    /// seed are all the same as for `run` with the same RNG, regardless of
+    /// how many threads are used. Shrinking happens on the calling thread.
+    ///
+    /// With `Config::ramp_size`, each case of a batch is generated at the
+    /// size it would get if the cases before it passed. When one of them is
+    /// rejected or hits the result cache instead, the rest of the batch is
+    /// discarded and generated again at the right size, so runs with many
+    /// rejections early on benefit less from the extra threads.
+    ///
+    /// Persisted failing cases are replayed on the calling thread before any
+    /// new cases are generated. The result cache is consulted and updated on
+    /// the calling thread, so only cases which miss it are sent to the worker
+    /// threads.
+    ///
+    /// `proptest!` uses this whenever the test and its inputs allow it.
+    ///
+    /// If `threads` is `0` or `1`, the configuration implies forking (which
+    /// includes setting `Config::timeout`) or collecting distinct failures,
+    /// or the strategy is small enough to be tested exhaustively, this simply
+    /// delegates to `run`.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn run_parallel<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult + Sync,
+    ) -> TestRunResult<S>
+    where
+        S::Value: Send,
+    {
+        if self.config.threads <= 1
+            || self.config.fork()
+            || self.collects_distinct_failures()
+            || self.exhaustive_strategy(strategy).is_some()
+        {
+            return self.run(strategy, test);
+        }
 
-                let bail = if iterations >= self.config.max_shrink_iters() {
-                    #[cfg(feature = "std")]
-                    const CONTROLLER: &str =
-                        "the PROPTEST_MAX_SHRINK_ITERS environment \
-                         variable or ProptestConfig.max_shrink_iters";
-                    #[cfg(not(feature = "std"))]
-                    const CONTROLLER: &str = "ProptestConfig.max_shrink_iters";
-                    verbose_message!(
-                        self,
-                        ALWAYS,
-                        "Aborting shrinking after {} iterations (set {} \
-                         to a large(r) value to shrink more; current \
-                         configuration: {} iterations)",
-                        CONTROLLER,
-                        self.config.max_shrink_iters(),
-                        iterations
-                    );
-                    true
-                } else if let Some(ms) = timed_out {
-                    #[cfg(feature = "std")]
-                    const CONTROLLER: &str =
-                        "the PROPTEST_MAX_SHRINK_TIME environment \
-                         variable or ProptestConfig.max_shrink_time";
-                    #[cfg(feature = "std")]
-                    let current = self.config.max_shrink_time;
-                    #[cfg(not(feature = "std"))]
-                    const CONTROLLER: &str = "(not configurable in no_std)";
-                    #[cfg(not(feature = "std"))]
-                    let current = 0;
-                    verbose_message!(
-                        self,
-                        ALWAYS,
-                        "Aborting shrinking after taking too long: {} ms \
-                         (set {} to a large(r) value to shrink more; current \
-                         configuration: {} ms)",
-                        ms,
-                        CONTROLLER,
-                        current
-                    );
-                    true
+        let start = Instant::now();
+        self.warn_if_watchdog_ignored();
+        let result = self.run_parallel_in_process(strategy, test);
+        self.size = FULL_SIZE;
+        self.finish_run(&result, start.elapsed());
+        result
+    }
+
+    #[cfg(feature = "std")]
+    fn run_parallel_in_process<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(S::Value) -> TestCaseResult + Sync,
+    ) -> TestRunResult<S>
+    where
+        S::Value: Send,
+    {
+        let mut replay_from_fork = iter::empty::<TestCaseResult>();
+        let mut fork_output = ForkOutput::empty();
+        let mut result_cache = self.new_cache();
+        self.run_start = Some(Instant::now());
+
+        self.run_persisted_failures(
+            strategy,
+            &test,
+            &mut replay_from_fork,
+            &mut *result_cache,
+            &mut fork_output,
+            &mut Vec::new(),
+        )?;
+
+        let threads = self.config.threads as usize;
+        while self.wants_more_cases() {
+            // Never generate more cases than could possibly be needed, so
+            // that the outcome is the same as running them one at a time.
+            // That is not possible with a time budget, which is only checked
+            // between batches.
+            let wanted = if self.config.max_run_time() > 0 {
+                u32::MAX
+            } else {
+                self.config.cases - self.successes
+            };
+            let batch_size = (wanted as usize).min(threads.saturating_mul(4));
+            let mut rngs = Vec::with_capacity(batch_size);
+            let mut seeds = Vec::with_capacity(batch_size);
+            let mut cases = Vec::with_capacity(batch_size);
+            for ahead in 0..batch_size as u32 {
+                // The seed given by `Config::rng_seed` does not depend on the
+                // RNG, nor its size on the cases before it.
+                rngs.push(if self.start_seed.is_some() {
+                    None
                 } else {
-                    false
-                };
+                    Some(self.rng.clone())
+                });
+                seeds.push(self.next_case_seed(ahead));
+                cases.push(unwrap_or!(strategy.new_tree(self), msg =>
+                        return Err(TestError::Abort(msg))));
+            }
 
-                if bail {
-                    // Move back to the most recent failing case
-                    while case.complicate() {
-                        fork_output.append(&Ok(()));
+            let results = self.call_test_batch_cached(
+                &test,
+                cases.iter().map(ValueTree::current).collect(),
+                &*result_cache,
+            );
+
+            for (((rng, seed), mut case), (result, labels)) in
+                rngs.into_iter().zip(seeds).zip(cases).zip(results)
+            {
+                // Each case was generated at the size it gets if all the
+                // cases before it in the batch pass. If one of them did not,
+                // `run` would have generated this case at a smaller size, so
+                // do that instead, starting from the same RNG state.
+                if let Some(rng) = rng {
+                    if 0 == self.config.max_run_time()
+                        && seed.1 != self.case_size(0)
+                    {
+                        self.rng = rng;
+                        break;
                     }
-                    break;
                 }
 
-                iterations += 1;
-
-                let result = call_test(
-                    self,
-                    case.current(),
-                    &test,
-                    replay_from_fork,
-                    result_cache,
-                    fork_output,
-                    is_from_persisted_seed,
-                );
-
+                observe!(self, |observer| observer
+                    .case_generated(&case.current()));
+                // The cache is only updated here, so that inputs repeated
+                // within the batch hit it like they would in `run`.
+                let key =
+                    result_cache.key(&ResultCacheKey::new(&case.current()));
+                let result = match result_cache.get(key) {
+                    Some(cached) => {
+                        verbose_message!(
+                            self,
+                            TRACE,
+                            "Test input hit cache, skipping execution"
+                        );
+                        cached.clone().map(|_| TestCaseOk::CacheHitSuccess)
+                    }
+                    None => {
+                        result_cache.put(key, &result);
+                        result.map(|_| TestCaseOk::NewCaseSuccess)
+                    }
+                };
                 match result {
-                    // Rejections are effectively a pass here,
-                    // since they indicate that any behaviour of
-                    // the function under test is acceptable.
-                    Ok(_) | Err(TestCaseError::Reject(..)) => {
-                        if !case.complicate() {
-                            break;
-                        }
+                    Ok(ok_type) => {
+                        observe!(self, |observer| observer
+                            .case_passed(&case.current()));
+                        verbose_message!(self, TRACE, "Test case passed");
+                        self.case_labels = labels;
+                        self.record_case_result(ok_type);
+                    }
+                    Err(TestCaseError::Reject(whence)) => {
+                        verbose_message!(
+                            self,
+                            INFO_LOG,
+                            "Test case rejected: {}",
+                            whence
+                        );
+                        observe!(self, |observer| observer
+                            .case_rejected(&case.current(), &whence));
+                        self.reject_global(whence)?;
                     }
                     Err(TestCaseError::Fail(why)) => {
-                        last_failure = Some(why);
-                        if !case.simplify() {
-                            break;
-                        }
+                        verbose_message!(
+                            self,
+                            INFO_LOG,
+                            "Test case failed: {}",
+                            why
+                        );
+                        observe!(self, |observer| observer
+                            .case_failed(&case.current(), &why));
+                        let original_runs = self.rerun_failure(
+                            &case,
+                            &test,
+                            &mut replay_from_fork,
+                            &mut fork_output,
+                        );
+                        let why = self.shrink(
+                            &mut case,
+                            why,
+                            &test,
+                            &mut replay_from_fork,
+                            &mut *result_cache,
+                            &mut fork_output,
+                            false,
+                        );
+                        let failure = self.minimal_failure(
+                            why,
+                            &case,
+                            original_runs,
+                            &test,
+                            &mut replay_from_fork,
+                            &mut fork_output,
+                        );
+                        return Err(self.persist_failure(
+                            seed,
+                            failure,
+                            &fork_output,
+                        ));
                     }
                 }
             }
         }
-
-        last_failure
+
+        self.report_timed_run();
+        self.finish_label_statistics(&fork_output)
+    }
+
+    /// Run `test` on each of `values` with `call_test_batch`, except those
+    /// whose result is already in `result_cache`, which are not run again.
+    ///
+    /// The results are not added to `result_cache`, which is left to the
+    /// caller.
+    #[cfg(feature = "std")]
+    fn call_test_batch_cached<V, F>(
+        &self,
+        test: &F,
+        values: Vec<V>,
+        result_cache: &dyn ResultCache,
+    ) -> Vec<(TestCaseResult, CaseLabels)>
+    where
+        V: fmt::Debug + Send,
+        F: Fn(V) -> TestCaseResult + Sync,
+    {
+        let results = values
+            .iter()
+            .map(|value| {
+                result_cache
+                    .get(result_cache.key(&ResultCacheKey::new(value)))
+                    .map(|result| (result.clone(), CaseLabels::default()))
+            })
+            .collect::<Vec<_>>();
+        if results.iter().any(Option::is_some) {
+            verbose_message!(
+                self,
+                TRACE,
+                "{} test inputs hit cache, skipping execution",
+                results.iter().filter(|r| r.is_some()).count()
+            );
+        }
+
+        let misses = values
+            .into_iter()
+            .zip(&results)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(value, _)| value)
+            .collect();
+        let mut ran = call_test_batch(
+            test,
+            misses,
+            self.config.threads as usize,
+            self.config.timeout(),
+        )
+        .into_iter();
+
+        results
+            .into_iter()
+            .map(|cached| {
+                cached.unwrap_or_else(|| {
+                    ran.next().expect("Test case was never run")
+                })
+            })
+            .collect()
+    }
+
+    /// Replay every persisted failing case before any new cases are run.
+    ///
+    /// When collecting distinct failures, the cases which fail are added to
+    /// `failures` rather than ending the run, unless there are enough of them.
+    fn run_persisted_failures<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        test: &impl Fn(S::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        result_cache: &mut dyn ResultCache,
+        fork_output: &mut ForkOutput,
+        failures: &mut Vec<TestError<S::Value>>,
+    ) -> TestRunResult<S> {
+        let old_rng = self.rng.clone();
+
+        let persisted_failure_seeds: Vec<PersistedSeed> = self
+            .config
+            .failure_persistence
+            .as_ref()
+            .map(|f| {
+                f.load_persisted_failures3(
+                    self.config.source_file,
+                    self.config.test_name,
+                )
+            })
+            .unwrap_or_default();
+
+        for persisted_seed in persisted_failure_seeds {
+            self.use_case_seed(&persisted_seed);
+            let result = self.gen_and_run_case(
+                strategy,
+                test,
+                replay_from_fork,
+                result_cache,
+                fork_output,
+                true,
+            );
+            if let Err(e) = result {
+                let e = self.note_failure_seed(persisted_seed, e);
+                if let Some(e) = self.collect_failure(e, failures) {
+                    return Err(e);
+                }
+            }
+        }
+        self.rng = old_rng;
+
+        Ok(())
+    }
+
+    /// Test a value loaded through `ValuePersistence`, unless it no longer
+    /// deserializes.
+    #[cfg(feature = "serde")]
+    fn run_persisted_value<T: DeserializeOwned + fmt::Debug>(
+        &mut self,
+        serialized: &str,
+        test: &impl Fn(T) -> TestCaseResult,
+    ) -> Result<(), TestError<T>> {
+        let mut case = match PersistedValue::<T>::new(serialized) {
+            Ok(case) => case,
+            Err(err) => {
+                verbose_message!(
+                    self,
+                    ALWAYS,
+                    "Skipping persisted value {} which can no longer be \
+                     deserialized: {}",
+                    serialized,
+                    err
+                );
+                return Ok(());
+            }
+        };
+
+        let mut result_cache = self.new_cache();
+        self.run_case(
+            &mut case,
+            test,
+            &mut iter::empty::<TestCaseResult>().fuse(),
+            &mut *result_cache,
+            &mut ForkOutput::empty(),
+            true,
+        )
+        .map(|_| ())
+    }
+
+    /// Save the value of a newly found failing case through
+    /// `ValuePersistence`, unless it was already persisted.
+    #[cfg(feature = "serde")]
+    fn persist_value<T: Serialize>(
+        &mut self,
+        value: &T,
+        persisted_values: &[String],
+    ) {
+        let serialized = match serde_json::to_string(value) {
+            Ok(serialized) => serialized,
+            Err(err) => {
+                verbose_message!(
+                    self,
+                    ALWAYS,
+                    "Failed to serialize the failing value: {}",
+                    err
+                );
+                return;
+            }
+        };
+
+        if persisted_values.contains(&serialized) {
+            return;
+        }
+        if let Some(ref mut value_persistence) = self.config.value_persistence {
+            value_persistence.save_persisted_value(
+                self.config.source_file,
+                self.config.test_name,
+                &serialized,
+            );
+        }
+    }
+
+    /// Summarise the run which ended with `result` after `elapsed`.
+    #[cfg(feature = "std")]
+    pub(crate) fn run_report<T: fmt::Debug>(
+        &self,
+        result: &Result<(), TestError<T>>,
+        elapsed: Duration,
+    ) -> RunReport {
+        let (outcome, message, minimal_value) = match *result {
+            Ok(()) => (RunOutcome::Passed, None, None),
+            Err(TestError::Abort(ref why)) => {
+                (RunOutcome::Aborted, Some(String::from(why.message())), None)
+            }
+            Err(TestError::Fail(ref why, ref value)) => (
+                RunOutcome::Failed,
+                Some(String::from(why.message())),
+                Some(format!("{:?}", value)),
+            ),
+            Err(TestError::Flaky(ref why, ref value, ref runs)) => (
+                RunOutcome::Flaky,
+                Some(format!("{}; when re-run, {}", why, runs)),
+                Some(format!("{:?}", value)),
+            ),
+            Err(TestError::MultipleFailures(ref failures)) => {
+                let mut whys = Vec::new();
+                let mut values = Vec::new();
+                for failure in failures {
+                    if let TestError::Fail(ref why, ref value)
+                    | TestError::Flaky(ref why, ref value, _) = *failure
+                    {
+                        whys.push(why.message());
+                        values.push(value);
+                    }
+                }
+                (
+                    RunOutcome::Failed,
+                    Some(format!(
+                        "{} distinct failures: {}",
+                        failures.len(),
+                        whys.join("; ")
+                    )),
+                    Some(format!("{:?}", values)),
+                )
+            }
+        };
+        let detail = |detail: &RejectionDetail| {
+            detail
+                .iter()
+                .map(|(reason, &count)| (reason.clone(), count))
+                .collect()
+        };
+
+        RunReport {
+            test_name: self.config.test_name,
+            source_file: self.config.source_file,
+            outcome,
+            message,
+            cases: self.successes,
+            local_rejects: self.local_rejects,
+            global_rejects: self.global_rejects,
+            local_reject_detail: detail(&self.local_reject_detail),
+            global_reject_detail: detail(&self.global_reject_detail),
+            shrink_iters: self.shrink_iters,
+            elapsed,
+            failure_seed: match *result {
+                Err(TestError::Fail(..))
+                | Err(TestError::Flaky(..))
+                | Err(TestError::MultipleFailures(..)) => {
+                    self.failure_seed.clone()
+                }
+                _ => None,
+            },
+            minimal_value,
+            _non_exhaustive: (),
+        }
+    }
+
+    /// Pass a report of the run which ended with `result` after `elapsed` to
+    /// `Config::observer` and write it to `Config::report_dir`, if they are
+    /// set.
+    #[cfg(feature = "std")]
+    fn finish_run<T: fmt::Debug>(
+        &mut self,
+        result: &Result<(), TestError<T>>,
+        elapsed: Duration,
+    ) {
+        if self.config.observer.is_none() && self.config.report_dir.is_none() {
+            return;
+        }
+
+        let report = self.run_report(result, elapsed);
+        observe!(self, |observer| observer.run_finished(&report));
+
+        let dir = match self.config.report_dir {
+            Some(ref dir) => dir,
+            None => return,
+        };
+        // A forked child only runs part of the test, and its parent reports
+        // on the whole of it.
+        #[cfg(feature = "fork")]
+        {
+            if env::var_os(ENV_FORK_FILE).is_some() {
+                return;
+            }
+        }
+
+        if let Err(err) = write_report(dir, self.config.report_format, &report)
+        {
+            eprintln!(
+                "proptest: failed to write report to {}: {}",
+                dir.display(),
+                err
+            );
+        }
+    }
+
+    /// Report the labels collected over a successful run and enforce any
+    /// `prop_cover!` requirements.
+    ///
+    /// Nothing happens in a forked child, since the parent does not expect
+    /// the child to fail after all its test cases have passed.
+    fn finish_label_statistics<T>(
+        &self,
+        fork_output: &ForkOutput,
+    ) -> Result<(), TestError<T>> {
+        if self.label_statistics.is_empty() || fork_output.is_in_fork() {
+            return Ok(());
+        }
+
+        // The distribution is only worth printing unasked if it explains a
+        // failure.
+        let coverage = self.label_statistics.check_coverage();
+        verbose_message!(
+            self,
+            if coverage.is_ok() { INFO_LOG } else { ALWAYS },
+            "Label distribution for {}:\n{}",
+            self.config.test_name.unwrap_or("<unnamed test>"),
+            self.label_statistics
+        );
+        coverage.map_err(TestError::Abort)
+    }
+
+    /// Take the labels recorded by the test case of the next replay step.
+    #[cfg(feature = "std")]
+    fn next_replay_labels(&mut self) -> CaseLabels {
+        #[cfg(feature = "fork")]
+        {
+            self.replay_labels.next().unwrap_or_default()
+        }
+        #[cfg(not(feature = "fork"))]
+        {
+            CaseLabels::default()
+        }
+    }
+
+    /// Return the seed to generate the next new case from, and set the RNG
+    /// to it. `ahead` is the number of cases generated before it which have
+    /// not been run yet, and are assumed to pass.
+    ///
+    /// This is normally derived from the RNG, except that the seed from
+    /// `Config::rng_seed` is used as is for the first case, so that it is
+    /// the same case as the one the seed was reported for.
+    fn next_case_seed(&mut self, ahead: u32) -> PersistedSeed {
+        match self.start_seed.take() {
+            Some(seed) => {
+                self.use_case_seed(&seed);
+                seed
+            }
+            None => {
+                self.size = self.case_size(ahead);
+                PersistedSeed(self.rng.gen_get_seed(), self.size)
+            }
+        }
+    }
+
+    /// Set up the RNG and the size to generate the case with the given seed.
+    fn use_case_seed(&mut self, seed: &PersistedSeed) {
+        self.rng.set_seed(seed.0.clone());
+        self.size = seed.1;
+    }
+
+    /// Return the size to generate a new case at, which grows linearly over
+    /// the first half of the run when `Config::ramp_size` is enabled, as if
+    /// `ahead` more cases had already passed.
+    fn case_size(&self, ahead: u32) -> u16 {
+        if !self.config.ramp_size {
+            return FULL_SIZE;
+        }
+
+        let full = u128::from(FULL_SIZE);
+        #[cfg(feature = "std")]
+        {
+            let max_run_time = self.config.max_run_time();
+            if max_run_time > 0 {
+                let spent = self.run_budget_spent().as_micros();
+                let ramp = u128::from(max_run_time) * 500;
+                return (spent * full / ramp).clamp(1, full) as u16;
+            }
+        }
+
+        let cases_run =
+            u128::from(self.successes + self.failing_cases) + u128::from(ahead);
+        let ramp = u128::from(self.config.cases / 2).max(1);
+        ((cases_run + 1) * full / ramp).clamp(1, full) as u16
+    }
+
+    /// If `error` is a failure, remember `seed` as the seed of the failing
+    /// case and mention it in the reason of `error`.
+    fn note_failure_seed<T>(
+        &mut self,
+        seed: PersistedSeed,
+        error: TestError<T>,
+    ) -> TestError<T> {
+        if let TestError::Fail(..) | TestError::Flaky(..) = error {
+            let note = format!(
+                "failing case seed: {} (set PROPTEST_SEED=\"{}\" to \
+                 reproduce it)",
+                seed, seed
+            );
+            self.failure_seed = Some(seed);
+            with_note(error, note)
+        } else {
+            error
+        }
+    }
+
+    /// If `error` is a newly found failure, save the seed of the failing case
+    /// if persistence is on, and mention it in the reason of `error`.
+    fn persist_failure<T: fmt::Debug>(
+        &mut self,
+        seed: PersistedSeed,
+        error: TestError<T>,
+        fork_output: &ForkOutput,
+    ) -> TestError<T> {
+        if let TestError::Fail(_, ref value)
+        | TestError::Flaky(_, ref value, _) = error
+        {
+            if let Some(ref mut failure_persistence) =
+                self.config.failure_persistence
+            {
+                // Don't update the persistence file if we're a child
+                // process. The parent relies on it remaining consistent
+                // and will take care of updating it itself.
+                if !fork_output.is_in_fork() {
+                    failure_persistence.save_persisted_failure3(
+                        self.config.source_file,
+                        self.config.test_name,
+                        seed.clone(),
+                        value,
+                    );
+                }
+            }
+        }
+        self.note_failure_seed(seed, error)
+    }
+
+    fn gen_and_run_case<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        f: &impl Fn(S::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        result_cache: &mut dyn ResultCache,
+        fork_output: &mut ForkOutput,
+        is_from_persisted_seed: bool,
+    ) -> Result<Option<f64>, TestError<S::Value>> {
+        let record_choices = self.uses_choice_shrinking();
+        if record_choices {
+            self.rng.start_recording_choices();
+        }
+        let case = strategy.new_tree(self);
+        let choices = if record_choices {
+            Some(self.rng.finish_recording_choices())
+        } else {
+            None
+        };
+        let mut case = unwrap_or!(case, msg =>
+                return Err(TestError::Abort(msg)));
+
+        let ok_type = match choices {
+            Some(choices) => self.run_case_with_choices(
+                strategy,
+                case,
+                choices,
+                f,
+                result_cache,
+                is_from_persisted_seed,
+            )?,
+            None => self.run_case(
+                &mut case,
+                f,
+                replay_from_fork,
+                result_cache,
+                fork_output,
+                is_from_persisted_seed,
+            )?,
+        };
+        Ok(self.record_case_result(ok_type))
+    }
+
+    /// Whether new cases should record the choice sequence they were
+    /// generated from, so that `shrink_choices` can work on it.
+    fn uses_choice_shrinking(&self) -> bool {
+        // A forked child would have to replay every regenerated case to its
+        // parent, and `PassThrough` derives new RNGs by splitting its data,
+        // which a recorded choice sequence cannot reproduce.
+        self.config.choice_shrinking
+            && !self.config.fork()
+            && self.rng.algorithm() != RngAlgorithm::PassThrough
+    }
+
+    /// Account for a test case which did not fail, returning the score it
+    /// reported via `prop_target!`, if any.
+    fn record_case_result(&mut self, ok_type: TestCaseOk) -> Option<f64> {
+        // We only count new cases to our set of successful runs against
+        // `PROPTEST_CASES` config.
+        match ok_type {
+            TestCaseOk::NewCaseSuccess | TestCaseOk::ReplayFromForkSuccess => {
+                self.successes += 1;
+                let labels = mem::take(&mut self.case_labels);
+                let target = labels.target();
+                self.label_statistics.record(labels);
+                target
+            }
+            TestCaseOk::PersistedCaseSuccess
+            | TestCaseOk::CacheHitSuccess
+            | TestCaseOk::Reject
+            | TestCaseOk::KnownFailure => None,
+        }
+    }
+
+    /// Run one specific test case against this runner.
+    ///
+    /// If the test fails, finds the minimal failing test case. If the test
+    /// does not fail, returns whether it succeeded or was filtered out.
+    ///
+    /// This does not honour the `fork` config, and will not be able to
+    /// terminate the run if it runs for longer than `timeout`. However, if the
+    /// test function returns but took longer than `timeout`, the test case
+    /// will fail.
+    pub fn run_one<V: ValueTree>(
+        &mut self,
+        case: V,
+        test: impl Fn(V::Value) -> TestCaseResult,
+    ) -> Result<bool, TestError<V::Value>> {
+        let mut case = case;
+        let mut result_cache = self.new_cache();
+        self.run_case(
+            &mut case,
+            &test,
+            &mut iter::empty::<TestCaseResult>().fuse(),
+            &mut *result_cache,
+            &mut ForkOutput::empty(),
+            false,
+        )
+        .map(|ok_type| match ok_type {
+            TestCaseOk::Reject => false,
+            _ => true,
+        })
+    }
+
+    fn run_case<V: ValueTree>(
+        &mut self,
+        case: &mut V,
+        test: &impl Fn(V::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        result_cache: &mut dyn ResultCache,
+        fork_output: &mut ForkOutput,
+        is_from_persisted_seed: bool,
+    ) -> Result<TestCaseOk, TestError<V::Value>> {
+        observe!(self, |observer| observer.case_generated(&case.current()));
+        let result = call_test(
+            self,
+            case.current(),
+            test,
+            replay_from_fork,
+            result_cache,
+            fork_output,
+            is_from_persisted_seed,
+        );
+
+        match result {
+            Ok(success_type) => {
+                observe!(self, |observer| observer
+                    .case_passed(&case.current()));
+                Ok(success_type)
+            }
+            Err(TestCaseError::Fail(why)) => {
+                observe!(self, |observer| observer
+                    .case_failed(&case.current(), &why));
+                if !self.is_new_failure(&why) {
+                    return Ok(TestCaseOk::KnownFailure);
+                }
+                let original_runs = self.rerun_failure(
+                    case,
+                    test,
+                    replay_from_fork,
+                    fork_output,
+                );
+                let why = self.shrink(
+                    case,
+                    why,
+                    test,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    is_from_persisted_seed,
+                );
+                Err(self.minimal_failure(
+                    why,
+                    case,
+                    original_runs,
+                    test,
+                    replay_from_fork,
+                    fork_output,
+                ))
+            }
+            Err(TestCaseError::Reject(whence)) => {
+                observe!(self, |observer| observer
+                    .case_rejected(&case.current(), &whence));
+                self.reject_global(whence)?;
+                Ok(TestCaseOk::Reject)
+            }
+        }
+    }
+
+    /// Like `run_case`, but if the test fails, the choice sequence `choices`
+    /// which generated `case` is shrunk before the value tree is.
+    fn run_case_with_choices<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        case: S::Tree,
+        choices: Vec<u8>,
+        test: &impl Fn(S::Value) -> TestCaseResult,
+        result_cache: &mut dyn ResultCache,
+        is_from_persisted_seed: bool,
+    ) -> Result<TestCaseOk, TestError<S::Value>> {
+        // Choice shrinking is never used when forking, so there is nothing to
+        // replay or to report to a parent process.
+        let replay_from_fork = &mut iter::empty::<TestCaseResult>().fuse();
+        let fork_output = &mut ForkOutput::empty();
+
+        observe!(self, |observer| observer.case_generated(&case.current()));
+        let result = call_test(
+            self,
+            case.current(),
+            test,
+            replay_from_fork,
+            result_cache,
+            fork_output,
+            is_from_persisted_seed,
+        );
+        let why = match result {
+            Ok(success_type) => {
+                observe!(self, |observer| observer
+                    .case_passed(&case.current()));
+                return Ok(success_type);
+            }
+            Err(TestCaseError::Fail(why)) => {
+                observe!(self, |observer| observer
+                    .case_failed(&case.current(), &why));
+                if !self.is_new_failure(&why) {
+                    return Ok(TestCaseOk::KnownFailure);
+                }
+                why
+            }
+            Err(TestCaseError::Reject(whence)) => {
+                observe!(self, |observer| observer
+                    .case_rejected(&case.current(), &whence));
+                self.reject_global(whence)?;
+                return Ok(TestCaseOk::Reject);
+            }
+        };
+
+        let original_runs =
+            self.rerun_failure(&case, test, replay_from_fork, fork_output);
+        let (mut case, why) = self.shrink_choices(
+            strategy,
+            case,
+            choices,
+            test,
+            result_cache,
+            why,
+        );
+        let why = self.shrink(
+            &mut case,
+            why,
+            test,
+            replay_from_fork,
+            result_cache,
+            fork_output,
+            is_from_persisted_seed,
+        );
+        Err(self.minimal_failure(
+            why,
+            &case,
+            original_runs,
+            test,
+            replay_from_fork,
+            fork_output,
+        ))
+    }
+
+    /// Whether the run should go on generating new cases.
+    ///
+    /// This is until `Config::cases` cases have been run, or with a
+    /// `Config::max_run_time`, until the time is up and `Config::min_cases`
+    /// cases have been run.
+    fn wants_more_cases(&self) -> bool {
+        if self.watchdog_tripped() {
+            return false;
+        }
+
+        let cases_run = self.successes + self.failing_cases;
+
+        #[cfg(feature = "std")]
+        {
+            let max_run_time = self.config.max_run_time();
+            if max_run_time > 0 {
+                return cases_run < self.config.min_cases
+                    || self.run_budget_spent()
+                        < Duration::from_millis(max_run_time.into());
+            }
+        }
+
+        cases_run < self.config.cases
+    }
+
+    /// Return the time spent on the current run, not counting shrinking.
+    #[cfg(feature = "std")]
+    fn run_time_spent(&self) -> Duration {
+        self.run_start.map_or(Duration::ZERO, |start| {
+            start.elapsed().saturating_sub(self.shrink_time)
+        })
+    }
+
+    /// Return how much of `Config::max_run_time` the current run has used
+    /// up, which includes shrinking unless `Config::max_shrink_time` limits
+    /// that separately.
+    #[cfg(feature = "std")]
+    fn run_budget_spent(&self) -> Duration {
+        if self.config.max_shrink_time > 0 {
+            self.run_time_spent()
+        } else {
+            self.run_start
+                .map_or(Duration::ZERO, |start| start.elapsed())
+        }
+    }
+
+    /// Return the limit on the time a shrink which starts now may take, if
+    /// there is one.
+    #[cfg(feature = "std")]
+    fn shrink_time_limit(&self) -> Option<ShrinkTimeLimit> {
+        if self.config.max_shrink_time > 0 {
+            Some(ShrinkTimeLimit {
+                time: Duration::from_millis(self.config.max_shrink_time.into()),
+                controller: "the PROPTEST_MAX_SHRINK_TIME environment \
+                             variable or ProptestConfig.max_shrink_time",
+                configured: self.config.max_shrink_time,
+                stopped: "exceeded max_shrink_time",
+            })
+        } else if self.config.max_run_time() > 0 {
+            let max_run_time = self.config.max_run_time();
+            Some(ShrinkTimeLimit {
+                time: Duration::from_millis(max_run_time.into())
+                    .saturating_sub(self.run_budget_spent()),
+                controller: "the PROPTEST_MAX_RUN_TIME environment \
+                             variable or ProptestConfig.max_run_time",
+                configured: max_run_time,
+                stopped: "exceeded max_run_time",
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Report how many cases a run limited by `Config::max_run_time` ran.
+    #[cfg(feature = "std")]
+    fn report_timed_run(&self) {
+        if self.config.max_run_time() > 0 {
+            verbose_message!(
+                self,
+                ALWAYS,
+                "Ran {} cases in {} ms (not counting shrinking)",
+                self.successes + self.failing_cases,
+                self.run_time_spent().as_millis()
+            );
+        }
+    }
+
+    /// Whether failing cases are collected into distinct buckets instead of
+    /// ending the run.
+    fn collects_distinct_failures(&self) -> bool {
+        self.config.max_distinct_failures > 1
+    }
+
+    /// Account for a case which failed for `why`, returning whether it should
+    /// be shrunk and reported, which it always is unless collecting distinct
+    /// failures and a failure in the same bucket was already found.
+    fn is_new_failure(&mut self, why: &Reason) -> bool {
+        if !self.collects_distinct_failures() {
+            return true;
+        }
+
+        self.failing_cases += 1;
+        let bucket = failure_bucket(why);
+        if self.failure_buckets.contains(bucket) {
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Skipping another failure in bucket {}",
+                bucket
+            );
+            return false;
+        }
+
+        self.failure_buckets.insert(bucket.into());
+        self.shrink_bucket = Some(bucket.into());
+        true
+    }
+
+    /// Whether a failure for `why` found while shrinking is the same failure
+    /// as the one being shrunk.
+    fn is_shrink_failure(&self, why: &Reason) -> bool {
+        self.shrink_bucket
+            .as_ref()
+            .map_or(true, |bucket| failure_bucket(why) == bucket)
+    }
+
+    /// Handle the error `e` which would end the run, returning the error the
+    /// run should end with, if it should end now.
+    ///
+    /// Unless collecting distinct failures, that is always `e`. Otherwise,
+    /// failures are added to `failures` until there are
+    /// `Config::max_distinct_failures` of them.
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn collect_failure<T>(
+        &self,
+        e: TestError<T>,
+        failures: &mut Vec<TestError<T>>,
+    ) -> Option<TestError<T>> {
+        if !self.collects_distinct_failures() {
+            return Some(e);
+        }
+
+        if let TestError::Abort(ref why) = e {
+            if failures.is_empty() {
+                return Some(e);
+            }
+            verbose_message!(
+                self,
+                ALWAYS,
+                "Run aborted after finding {} distinct failures: {}",
+                failures.len(),
+                why
+            );
+            return Some(distinct_failures(mem::take(failures)));
+        }
+
+        failures.push(e);
+        if failures.len() >= self.config.max_distinct_failures as usize {
+            Some(distinct_failures(mem::take(failures)))
+        } else {
+            None
+        }
+    }
+
+    /// Re-run the failing `case` `Config::flaky_reruns` times, returning how
+    /// many of the runs passed and failed.
+    fn rerun_failure<V: ValueTree>(
+        &mut self,
+        case: &V,
+        test: &impl Fn(V::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        fork_output: &mut ForkOutput,
+    ) -> (u32, u32) {
+        // The cache would just give back the result of the first run.
+        let mut result_cache = noop_result_cache();
+        let (mut passes, mut failures) = (0, 0);
+        for _ in 0..self.config.flaky_reruns {
+            // Re-running a case which overran the watchdog would only leave
+            // another thread running.
+            if self.watchdog_tripped() {
+                break;
+            }
+            let result = call_test(
+                self,
+                case.current(),
+                test,
+                replay_from_fork,
+                &mut *result_cache,
+                fork_output,
+                false,
+            );
+            match result {
+                Err(TestCaseError::Fail(..)) => failures += 1,
+                Ok(_) | Err(TestCaseError::Reject(..)) => passes += 1,
+            }
+        }
+        (passes, failures)
+    }
+
+    /// Build the error for the minimal failing `case` found by shrinking,
+    /// which is `TestError::Flaky` if re-running it, or the original failing
+    /// case which re-ran as `original_runs`, did not always fail.
+    fn minimal_failure<V: ValueTree>(
+        &mut self,
+        why: Reason,
+        case: &V,
+        (original_passes, original_failures): (u32, u32),
+        test: &impl Fn(V::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        fork_output: &mut ForkOutput,
+    ) -> TestError<V::Value> {
+        let (minimal_passes, minimal_failures) =
+            self.rerun_failure(case, test, replay_from_fork, fork_output);
+        let runs = FlakyRuns {
+            original_passes,
+            original_failures,
+            minimal_passes,
+            minimal_failures,
+        };
+
+        if runs.is_flaky() {
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Failure is flaky: when re-run, {}",
+                runs
+            );
+            TestError::Flaky(why, case.current(), runs)
+        } else {
+            TestError::Fail(why, case.current())
+        }
+    }
+
+    /// Shrink the choice sequence `choices` which generated the failing
+    /// `case`, returning the value tree generated by the simplest sequence
+    /// found which still fails, along with the reason it failed.
+    ///
+    /// Every accepted change makes the sequence shorter or lexicographically
+    /// smaller, so this always terminates, though it may also be cut short by
+    /// `max_shrink_iters` and `max_shrink_time`. The cases tried are recorded
+    /// in the shrink trace which `shrink` then finishes.
+    fn shrink_choices<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        case: S::Tree,
+        choices: Vec<u8>,
+        test: &impl Fn(S::Value) -> TestCaseResult,
+        result_cache: &mut dyn ResultCache,
+        why: Reason,
+    ) -> (S::Tree, Reason) {
+        #[cfg(feature = "std")]
+        use std::time;
+
+        // Only reported in verbose messages, which need `std`.
+        #[cfg(feature = "std")]
+        let original_len = choices.len();
+        #[cfg(feature = "std")]
+        self.start_shrink_trace(&case.current(), &why);
+        let mut best = (choices, case, why);
+        let mut iterations = 0;
+
+        let max_shrink_iters = self.config.max_shrink_iters();
+        #[cfg(feature = "std")]
+        let time_limit = self.shrink_time_limit().map(|limit| limit.time);
+        #[cfg(feature = "std")]
+        let start_time = time::Instant::now();
+        let out_of_budget = |iterations: u32| {
+            #[cfg(feature = "std")]
+            {
+                if time_limit
+                    .map_or(false, |limit| start_time.elapsed() > limit)
+                {
+                    return true;
+                }
+            }
+
+            iterations >= max_shrink_iters
+        };
+
+        let mut aborted = false;
+        let mut improved = true;
+        'passes: while improved {
+            improved = false;
+
+            for &(pass, block) in CHOICE_PASSES {
+                let mut at = 0;
+                while at < best.0.len() {
+                    let candidate =
+                        match mutate_choices(&best.0, pass, block, at) {
+                            Some(candidate) => candidate,
+                            None => {
+                                at += 1;
+                                continue;
+                            }
+                        };
+
+                    if out_of_budget(iterations) || self.watchdog_tripped() {
+                        aborted = true;
+                        break 'passes;
+                    }
+                    iterations += 1;
+
+                    // Stay at the same position after a success, since the
+                    // block there may now be simplified further.
+                    match self.try_choices(
+                        strategy,
+                        &candidate,
+                        test,
+                        result_cache,
+                    ) {
+                        Some(found) => {
+                            best = found;
+                            improved = true;
+                        }
+                        None => at += 1,
+                    }
+                }
+            }
+
+            // Binary search for the smallest value of each byte which still
+            // fails.
+            let mut at = 0;
+            while at < best.0.len() {
+                let mut lo = 0;
+                let mut hi = best.0[at];
+                while lo < hi && at < best.0.len() {
+                    if out_of_budget(iterations) || self.watchdog_tripped() {
+                        aborted = true;
+                        break 'passes;
+                    }
+                    iterations += 1;
+
+                    let mid = lo + (hi - lo) / 2;
+                    let mut candidate = best.0.clone();
+                    candidate[at] = mid;
+                    match self.try_choices(
+                        strategy,
+                        &candidate,
+                        test,
+                        result_cache,
+                    ) {
+                        Some(found) => {
+                            best = found;
+                            improved = true;
+                            hi = mid;
+                        }
+                        None => lo = mid + 1,
+                    }
+                }
+                at += 1;
+            }
+        }
+
+        if aborted {
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Aborting choice shrinking after {} iterations",
+                iterations
+            );
+        }
+        verbose_message!(
+            self,
+            INFO_LOG,
+            "Shrunk choice sequence from {} to {} bytes",
+            original_len,
+            best.0.len()
+        );
+        #[cfg(feature = "std")]
+        {
+            self.shrink_time += start_time.elapsed();
+        }
+        (best.1, best.2)
+    }
+
+    /// Regenerate a case from the choice sequence `choices` and run `test` on
+    /// it.
+    ///
+    /// If it fails, returns the part of `choices` the strategy actually used,
+    /// the value tree and the reason for the failure.
+    fn try_choices<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        choices: &[u8],
+        test: &impl Fn(S::Value) -> TestCaseResult,
+        result_cache: &mut dyn ResultCache,
+    ) -> Option<(Vec<u8>, S::Tree, Reason)> {
+        let replay = TestRng::from_choices(self.rng.algorithm(), choices);
+        let rng = mem::replace(&mut self.rng, replay);
+        let case = strategy.new_tree(self);
+        let used = self.rng.choices_used();
+        self.rng = rng;
+        let case = case.ok()?;
+
+        self.shrink_iters += 1;
+        let result = call_test(
+            self,
+            case.current(),
+            test,
+            &mut iter::empty::<TestCaseResult>().fuse(),
+            result_cache,
+            &mut ForkOutput::empty(),
+            false,
+        );
+        let accepted = match result {
+            Err(TestCaseError::Fail(ref why)) => {
+                self.is_shrink_failure(why) || self.watchdog_tripped()
+            }
+            _ => false,
+        };
+        #[cfg(feature = "std")]
+        if let Some(ref mut trace) = self.shrink_trace {
+            trace.step(ShrinkMove::Choices, &case.current(), &result, accepted);
+        }
+
+        match result {
+            Err(TestCaseError::Fail(why)) if accepted => {
+                observe!(self, |observer| observer
+                    .shrink_accepted(&case.current(), &why));
+                Some((choices[..used].to_vec(), case, why))
+            }
+            _ => {
+                observe!(self, |observer| observer
+                    .shrink_rejected(&case.current()));
+                None
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn shrink<V: ValueTree>(
+        &mut self,
+        case: &mut V,
+        why: Reason,
+        test: impl Fn(V::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        result_cache: &mut dyn ResultCache,
+        fork_output: &mut ForkOutput,
+        is_from_persisted_seed: bool,
+    ) -> Reason {
+        #[cfg(feature = "std")]
+        use std::time;
+
+        let mut last_failure = None;
+        let mut iterations = 0;
+        #[cfg(feature = "std")]
+        let time_limit = self.shrink_time_limit();
+        #[cfg(feature = "std")]
+        let start_time = time::Instant::now();
+        // The child of a fork leaves the trace to the parent, which replays
+        // the same steps.
+        #[cfg(feature = "std")]
+        if !fork_output.is_in_fork() {
+            self.start_shrink_trace(&case.current(), &why);
+        }
+        #[cfg(feature = "std")]
+        let mut trace = self.shrink_trace.take();
+
+        let stopped = if case.simplify() {
+            #[cfg(feature = "std")]
+            let mut moved = ShrinkMove::Simplify;
+            loop {
+                #[cfg(feature = "std")]
+                let timed_out = time_limit.as_ref().and_then(|limit| {
+                    let elapsed = start_time.elapsed();
+                    if elapsed > limit.time {
+                        Some((
+                            elapsed.as_millis(),
+                            limit.controller,
+                            limit.configured,
+                            limit.stopped,
+                        ))
+                    } else {
+                        None
+                    }
+                });
+                #[cfg(not(feature = "std"))]
+                let timed_out: Option<(
+                    u128,
+                    &str,
+                    u32,
+                    &'static str,
+                )> = None;
+
+                let bail = if iterations >= self.config.max_shrink_iters() {
+                    #[cfg(feature = "std")]
+                    const CONTROLLER: &str =
+                        "the PROPTEST_MAX_SHRINK_ITERS environment \
+                         variable or ProptestConfig.max_shrink_iters";
+                    #[cfg(not(feature = "std"))]
+                    const CONTROLLER: &str = "ProptestConfig.max_shrink_iters";
+                    verbose_message!(
+                        self,
+                        ALWAYS,
+                        "Aborting shrinking after {} iterations (set {} \
+                         to a large(r) value to shrink more; current \
+                         configuration: {} iterations)",
+                        CONTROLLER,
+                        self.config.max_shrink_iters(),
+                        iterations
+                    );
+                    Some("reached max_shrink_iters")
+                } else if let Some((ms, controller, current, stopped)) =
+                    timed_out
+                {
+                    verbose_message!(
+                        self,
+                        ALWAYS,
+                        "Aborting shrinking after taking too long: {} ms \
+                         (set {} to a large(r) value to shrink more; current \
+                         configuration: {} ms)",
+                        ms,
+                        controller,
+                        current
+                    );
+                    Some(stopped)
+                } else if self.watchdog_tripped() {
+                    verbose_message!(
+                        self,
+                        ALWAYS,
+                        "Aborting shrinking after a test case exceeded the \
+                         watchdog timeout"
+                    );
+                    Some("a test case exceeded the watchdog timeout")
+                } else {
+                    None
+                };
+
+                if let Some(stopped) = bail {
+                    // Move back to the most recent failing case
+                    while case.complicate() {
+                        fork_output.append(&Ok(()), &CaseLabels::default());
+                    }
+                    break stopped;
+                }
+
+                iterations += 1;
+                self.shrink_iters += 1;
+
+                let result = call_test(
+                    self,
+                    case.current(),
+                    &test,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    is_from_persisted_seed,
+                );
+                let accepted = match result {
+                    Err(TestCaseError::Fail(ref why)) => {
+                        self.is_shrink_failure(why) || self.watchdog_tripped()
+                    }
+                    _ => false,
+                };
+                #[cfg(feature = "std")]
+                if let Some(ref mut trace) = trace {
+                    trace.step(moved, &case.current(), &result, accepted);
+                }
+
+                match result {
+                    Err(TestCaseError::Fail(why)) if accepted => {
+                        observe!(self, |observer| observer
+                            .shrink_accepted(&case.current(), &why));
+                        last_failure = Some(why);
+                        if !case.simplify() {
+                            break "simplify returned false";
+                        }
+                        #[cfg(feature = "std")]
+                        {
+                            moved = ShrinkMove::Simplify;
+                        }
+                    }
+                    // Rejections are effectively a pass here,
+                    // since they indicate that any behaviour of
+                    // the function under test is acceptable. So are
+                    // failures in a different bucket when collecting
+                    // distinct failures, which are found separately.
+                    _ => {
+                        observe!(self, |observer| observer
+                            .shrink_rejected(&case.current()));
+                        if !case.complicate() {
+                            break "complicate returned false";
+                        }
+                        #[cfg(feature = "std")]
+                        {
+                            moved = ShrinkMove::Complicate;
+                        }
+                    }
+                }
+            }
+        } else {
+            "the failing value could not be simplified"
+        };
+
+        let why = last_failure.unwrap_or(why);
+        #[cfg(feature = "std")]
+        {
+            self.shrink_time += start_time.elapsed();
+            if let Some(mut trace) = trace {
+                trace.finish(stopped);
+                return self.write_shrink_trace(&trace, why);
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = stopped;
+        why
+    }
+
+    /// Start a trace of shrinking the failing `value`, which failed because
+    /// of `why`, if `Config::shrink_trace_dir` asks for one and
+    /// `shrink_choices` did not already start it.
+    #[cfg(feature = "std")]
+    fn start_shrink_trace(&mut self, value: &dyn fmt::Debug, why: &Reason) {
+        if self.config.shrink_trace_dir.is_none() || self.shrink_trace.is_some()
+        {
+            return;
+        }
+
+        let mut trace = ShrinkTrace::new();
+        trace.step(
+            ShrinkMove::Initial,
+            value,
+            &TestCaseResult::Err(TestCaseError::Fail(why.clone())),
+            true,
+        );
+        self.shrink_trace = Some(trace);
+    }
+
+    /// Write `trace` to `Config::shrink_trace_dir`, returning `why` with the
+    /// path of the trace appended.
+    #[cfg(feature = "std")]
+    fn write_shrink_trace(
+        &mut self,
+        trace: &ShrinkTrace,
+        why: Reason,
+    ) -> Reason {
+        let dir = match self.config.shrink_trace_dir {
+            Some(ref dir) => dir,
+            None => return why,
+        };
+        let written =
+            trace.write(dir, self.config.test_name, self.shrink_traces);
+        self.shrink_traces += 1;
+        match written {
+            Ok(path) => {
+                format!("{}\nShrink trace written to {}", why, path.display())
+                    .into()
+            }
+            Err(err) => {
+                verbose_message!(
+                    self,
+                    ALWAYS,
+                    "Failed to write shrink trace to {}: {}",
+                    dir.display(),
+                    err
+                );
+                why
+            }
+        }
+    }
+
+    /// Update the state to account for a local rejection from `whence`, and
+    /// return `Ok` if the caller should keep going or `Err` to abort.
+    pub fn reject_local(
+        &mut self,
+        whence: impl Into<Reason>,
+    ) -> Result<(), Reason> {
+        if self.local_rejects >= self.config.max_local_rejects {
+            Err("Too many local rejects".into())
+        } else {
+            self.local_rejects += 1;
+            Self::insert_or_increment(
+                &mut self.local_reject_detail,
+                whence.into(),
+            );
+            Ok(())
+        }
+    }
+
+    /// Update the state to account for a global rejection from `whence`, and
+    /// return `Ok` if the caller should keep going or `Err` to abort.
+    fn reject_global<T>(&mut self, whence: Reason) -> Result<(), TestError<T>> {
+        if self.global_rejects >= self.config.max_global_rejects {
+            Err(TestError::Abort("Too many global rejects".into()))
+        } else {
+            self.global_rejects += 1;
+            Self::insert_or_increment(&mut self.global_reject_detail, whence);
+            Ok(())
+        }
+    }
+
+    /// Insert 1 or increment the rejection detail at key for whence.
+    fn insert_or_increment(into: &mut RejectionDetail, whence: Reason) {
+        into.entry(whence)
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+
+    /// Increment the counter of flat map regenerations and return whether it
+    /// is still under the configured limit.
+    pub fn flat_map_regen(&self) -> bool {
+        self.flat_map_regens.fetch_add(1, SeqCst)
+            < self.config.max_flat_map_regens as usize
+    }
+
+    fn new_cache(&self) -> Box<dyn ResultCache> {
+        (self.config.result_cache)()
+    }
+}
+
+#[cfg(feature = "fork")]
+fn init_replay(runner: &mut TestRunner) -> (Vec<TestCaseResult>, ForkOutput) {
+    use crate::test_runner::replay::{open_file, Replay, ReplayFileStatus::*};
+
+    if let Some(path) = env::var_os(ENV_FORK_FILE) {
+        let mut file = open_file(&path).expect("Failed to open replay file");
+        let loaded =
+            Replay::parse_from(&mut file).expect("Failed to read replay file");
+        match loaded {
+            InProgress(replay) => {
+                runner.rng.set_seed(replay.seed);
+                runner.replay_labels = replay.labels.into_iter();
+                (replay.steps, ForkOutput { file: Some(file) })
+            }
+
+            Terminated(_) => {
+                panic!("Replay file for child process is terminated?")
+            }
+
+            Corrupt => panic!("Replay file for child process is corrupt"),
+        }
+    } else {
+        (vec![], ForkOutput::empty())
+    }
+}
+
+#[cfg(not(feature = "fork"))]
+fn init_replay(
+    _runner: &mut TestRunner,
+) -> (iter::Empty<TestCaseResult>, ForkOutput) {
+    (iter::empty(), ForkOutput::empty())
+}
+
+/// The failure of a child which exited with `status`.
+///
+/// The reason explains which of `limits` the child exceeded if that is why
+/// it was terminated, or else gives its exit code or the signal which killed
+/// it, followed by the end of the child's `output`, which holds its stdout
+/// and stderr. This is usually where a panic message, sanitizer report or
+/// other explanation of a crash ends up.
+#[cfg(feature = "fork")]
+fn child_failure(
+    status: rusty_fork::ExitStatusWrapper,
+    output: &mut fs::File,
+    limits: ResourceLimits,
+) -> TestCaseError {
+    let tail = child_output_tail(output);
+    let mut reason = limits
+        .describe_signal(status.unix_signal(), &tail)
+        .unwrap_or_else(|| match status.code() {
+            Some(code) => format!("Child process exited with code {}", code),
+            None => format!("Child process was killed by {}", status),
+        });
+
+    if !tail.is_empty() {
+        reason.push_str("\n--- last output of child process ---\n");
+        reason.push_str(&tail);
+        reason.push_str("\n--- end of child output ---");
+    }
+
+    TestCaseError::fail(reason)
+}
+
+/// Returns up to the last `CHILD_OUTPUT_TAIL` bytes written to `output`,
+/// starting at a line boundary where possible.
+#[cfg(feature = "fork")]
+fn child_output_tail(output: &mut fs::File) -> String {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let len = output.metadata().map(|md| md.len()).unwrap_or(0);
+    let start = len.saturating_sub(CHILD_OUTPUT_TAIL);
+    let mut tail = Vec::new();
+    // The file is read again from the start when the child is cleaned up,
+    // so its position does not need to be restored.
+    if output
+        .seek(SeekFrom::Start(start))
+        .and_then(|_| output.read_to_end(&mut tail))
+        .is_err()
+    {
+        return String::new();
+    }
+
+    let mut tail = &tail[..];
+    if start > 0 {
+        if let Some(newline) = tail.iter().position(|&b| b'\n' == b) {
+            tail = &tail[newline + 1..];
+        }
+    }
+    String::from_utf8_lossy(tail).trim().into()
+}
+
+#[cfg(feature = "fork")]
+fn await_child_without_timeout(
+    child: &mut rusty_fork::ChildWrapper,
+    output: &mut fs::File,
+    limits: ResourceLimits,
+) -> (Option<TestCaseError>, Option<u64>) {
+    let status = child.wait().expect("Failed to wait for child process");
+
+    if status.success() {
+        (None, None)
+    } else {
+        (Some(child_failure(status, output, limits)), None)
+    }
+}
+
+#[cfg(all(feature = "fork", not(feature = "timeout")))]
+fn await_child(
+    child: &mut rusty_fork::ChildWrapper,
+    output: &mut fs::File,
+    _: &mut tempfile::NamedTempFile,
+    _timeout: u32,
+    limits: ResourceLimits,
+) -> (Option<TestCaseError>, Option<u64>) {
+    await_child_without_timeout(child, output, limits)
+}
+
+#[cfg(all(feature = "fork", feature = "timeout"))]
+fn await_child(
+    child: &mut rusty_fork::ChildWrapper,
+    output: &mut fs::File,
+    forkfile: &mut tempfile::NamedTempFile,
+    timeout: u32,
+    limits: ResourceLimits,
+) -> (Option<TestCaseError>, Option<u64>) {
+    use std::time::Duration;
+
+    if 0 == timeout {
+        return await_child_without_timeout(child, output, limits);
+    }
+
+    // The child can run for longer than the timeout since it may run
+    // multiple tests. Each time the timeout expires, we check whether the
+    // file has grown larger. If it has, we allow the child to keep running
+    // until the next timeout.
+    let mut last_forkfile_len = forkfile
+        .as_file()
+        .metadata()
+        .map(|md| md.len())
+        .unwrap_or(0);
+
+    loop {
+        if let Some(status) = child
+            .wait_timeout(Duration::from_millis(timeout.into()))
+            .expect("Failed to wait for child process")
+        {
+            if status.success() {
+                return (None, None);
+            } else {
+                return (Some(child_failure(status, output, limits)), None);
+            }
+        }
+
+        let current_len = forkfile
+            .as_file()
+            .metadata()
+            .map(|md| md.len())
+            .unwrap_or(0);
+        // If we've gone a full timeout period without the file growing,
+        // fail the test and kill the child.
+        if current_len <= last_forkfile_len {
+            return (
+                Some(TestCaseError::fail(format!(
+                    "Timed out waiting for child process"
+                ))),
+                Some(current_len),
+            );
+        } else {
+            last_forkfile_len = current_len;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::fs;
+
+    use super::*;
+    use crate::strategy::Strategy;
+    use crate::test_runner::{
+        FileFailurePersistence, RngAlgorithm, RunObserver, TestRng,
+    };
+
+    #[test]
+    fn gives_up_after_too_many_rejections() {
+        let config = Config::default();
+        let mut runner = TestRunner::new(config.clone());
+        let runs = Cell::new(0);
+        let result = runner.run(&(0u32..), |_| {
+            runs.set(runs.get() + 1);
+            Err(TestCaseError::reject("reject"))
+        });
+        match result {
+            Err(TestError::Abort(_)) => (),
+            e => panic!("Unexpected result: {:?}", e),
+        }
+        assert_eq!(config.max_global_rejects + 1, runs.get());
+    }
+
+    #[test]
+    fn test_pass() {
+        let mut runner = TestRunner::default();
+        let result = runner.run(&(1u32..), |v| {
+            assert!(v > 0);
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+    }
+
+    #[test]
+    fn test_fail_via_result() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..10u32), |v| {
+            if v < 5 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("not less than 5"))
+            }
+        });
+
+        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+    }
+
+    #[test]
+    fn test_fail_via_panic() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let line = line!() + 2;
+        let result = runner.run(&(0u32..10u32), |v| {
+            assert!(v < 5, "not less than 5");
+            Ok(())
+        });
+        let why = format!("not less than 5 at {}:{}", file!(), line);
+        assert_eq!(Err(TestError::Fail(why.into(), 5)), result);
+    }
+
+    #[test]
+    fn consistent_failure_is_not_flaky() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            flaky_reruns: 4,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..10u32), |v| {
+            if v < 5 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("not less than 5"))
+            }
+        });
+        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+    }
+
+    #[test]
+    fn inconsistent_failure_is_flaky() {
+        let calls = Cell::new(0);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            flaky_reruns: 4,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..1000u32), |v| {
+            calls.set(calls.get() + 1);
+            if v < 500 || calls.get() % 2 == 0 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("flaked"))
+            }
+        });
+
+        match result {
+            Err(TestError::Flaky(why, value, runs)) => {
+                assert!(why
+                    .message()
+                    .starts_with("flaked\nfailing case seed: "));
+                assert!(value >= 500);
+                assert_eq!(4, runs.original_passes + runs.original_failures);
+                assert_eq!(4, runs.minimal_passes + runs.minimal_failures);
+                assert!(runs.is_flaky());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn distinct_failures_collected_and_shrunk_separately() {
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                failure_persistence: None,
+                max_distinct_failures: 10,
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::default()),
+        );
+        let result = runner.run(&(0u32..1000u32), |v| {
+            prop_assert!(v < 500);
+            prop_assert!(v % 7 != 3);
+            Ok(())
+        });
+
+        let mut values = match result {
+            Err(TestError::MultipleFailures(failures)) => failures
+                .into_iter()
+                .map(|failure| match failure {
+                    TestError::Fail(_, value) => value,
+                    failure => panic!("Unexpected failure: {:?}", failure),
+                })
+                .collect::<Vec<_>>(),
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        values.sort();
+        assert_eq!(2, values.len());
+        assert!(values[0] < 500 && 3 == values[0] % 7, "{:?}", values);
+        assert_eq!(500, values[1]);
+    }
+
+    #[test]
+    fn panics_bucketed_by_location() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            max_distinct_failures: 10,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..1000u32), |v| {
+            if v < 500 {
+                assert!(v % 2 == 0, "odd");
+            } else {
+                assert!(v % 2 == 0, "odd");
+            }
+            Ok(())
+        });
+
+        let mut values = match result {
+            Err(TestError::MultipleFailures(failures)) => failures
+                .into_iter()
+                .map(|failure| match failure {
+                    TestError::Fail(why, value) => {
+                        assert!(why
+                            .message()
+                            .starts_with(&format!("odd at {}:", file!())));
+                        value
+                    }
+                    failure => panic!("Unexpected failure: {:?}", failure),
+                })
+                .collect::<Vec<_>>(),
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        values.sort();
+        assert_eq!(2, values.len());
+        assert!(values[0] < 500 && 1 == values[0] % 2, "{:?}", values);
+        assert!(values[1] >= 500 && 1 == values[1] % 2, "{:?}", values);
+    }
+
+    #[test]
+    fn distinct_failures_stop_at_first_by_default() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..1000u32), |v| {
+            prop_assert!(v < 500);
+            prop_assert!(v % 7 != 3);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(..)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn failure_bucket_prefers_location() {
+        assert_eq!(
+            "src/lib.rs:12",
+            failure_bucket(&"assertion failed: x < 5 at src/lib.rs:12".into())
+        );
+        assert_eq!(
+            "assertion `left == right` failed",
+            failure_bucket(
+                &"assertion `left == right` failed\n  left: 1\n right: 2"
+                    .into()
+            )
+        );
+        assert_eq!("stuck at home", failure_bucket(&"stuck at home".into()));
+    }
+
+    #[test]
+    fn max_run_time_replaces_case_count() {
+        let runs = Cell::new(0);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            cases: 1,
+            max_run_time: 50,
+            exhaustive_threshold: 0,
+            ..Config::default()
+        });
+        let start = std::time::Instant::now();
+        runner
+            .run(&(0u32..10), |_| {
+                runs.set(runs.get() + 1);
+                Ok(())
+            })
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(runs.get() > 1, "{}", runs.get());
+    }
+
+    #[test]
+    fn max_run_time_runs_min_cases() {
+        let runs = Cell::new(0);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            max_run_time: 1,
+            min_cases: 5,
+            exhaustive_threshold: 0,
+            ..Config::default()
+        });
+        runner
+            .run(&(0u32..10), |_| {
+                runs.set(runs.get() + 1);
+                Ok(())
+            })
+            .unwrap();
+        assert!(runs.get() >= 5, "{}", runs.get());
+    }
+
+    #[test]
+    fn max_run_time_shared_with_shrinking() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            max_run_time: 50,
+            ..Config::default()
+        });
+        runner.run_start =
+            Instant::now().checked_sub(Duration::from_millis(30));
+        runner.shrink_time = Duration::from_millis(20);
+
+        let limit = runner.shrink_time_limit().expect("no limit");
+        assert!(limit.time <= Duration::from_millis(20), "{:?}", limit.time);
+        assert!(limit.controller.contains("PROPTEST_MAX_RUN_TIME"));
+        assert_eq!(50, limit.configured);
+
+        runner.run_start =
+            Instant::now().checked_sub(Duration::from_millis(60));
+        let limit = runner.shrink_time_limit().expect("no limit");
+        assert_eq!(Duration::ZERO, limit.time);
+        assert_eq!("exceeded max_run_time", limit.stopped);
+    }
+
+    #[test]
+    fn max_shrink_time_limits_each_shrink() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            max_run_time: 50,
+            max_shrink_time: 1000,
+            ..Config::default()
+        });
+        runner.run_start =
+            Instant::now().checked_sub(Duration::from_millis(60));
+        runner.shrink_time = Duration::from_millis(20);
+
+        let limit = runner.shrink_time_limit().expect("no limit");
+        assert_eq!(Duration::from_millis(1000), limit.time);
+        assert!(limit.controller.contains("PROPTEST_MAX_SHRINK_TIME"));
+        assert_eq!("exceeded max_shrink_time", limit.stopped);
+        // Shrinking does not count towards the budget for new cases then.
+        assert!(runner.run_budget_spent() < Duration::from_millis(60));
+        assert!(runner.run_budget_spent() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn persisted_cases_do_not_count_towards_total_cases() {
+        const FILE: &'static str = "persistence-test.txt";
+        let _ = fs::remove_file(FILE);
+
+        let config = Config {
+            failure_persistence: Some(Box::new(
+                FileFailurePersistence::Direct(FILE),
+            )),
+            cases: 1,
+            ..Config::default()
+        };
+
+        let max = 10_000_000i32;
+        {
+            TestRunner::new(config.clone())
+                .run(&(0i32..max), |_v| {
+                    Err(TestCaseError::Fail("persist a failure".into()))
+                })
+                .expect_err("didn't fail?");
+        }
+
+        let run_count = RefCell::new(0);
+        TestRunner::new(config.clone())
+            .run(&(0i32..max), |_v| {
+                *run_count.borrow_mut() += 1;
+                Ok(())
+            })
+            .expect("should succeed");
+
+        // Persisted ran, and a new case ran, and only new case counts
+        // against `cases: 1`.
+        assert_eq!(run_count.into_inner(), 2);
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    struct PoorlyBehavedDebug(i32);
+    impl fmt::Debug for PoorlyBehavedDebug {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "\r\n{:?}\r\n", self.0)
+        }
+    }
+
+    #[test]
+    fn failing_cases_persisted_and_reloaded() {
+        const FILE: &'static str = "persistence-test.txt";
+        let _ = fs::remove_file(FILE);
+
+        let max = 10_000_000i32;
+        let input = (0i32..max).prop_map(PoorlyBehavedDebug);
+        let config = Config {
+            failure_persistence: Some(Box::new(
+                FileFailurePersistence::Direct(FILE),
+            )),
+            ..Config::default()
+        };
+
+        // First test with cases that fail above half max, and then below half
+        // max, to ensure we can correctly parse both lines of the persistence
+        // file.
+        let first_sub_failure = {
+            TestRunner::new(config.clone())
+                .run(&input, |v| {
+                    if v.0 < max / 2 {
+                        Ok(())
+                    } else {
+                        Err(TestCaseError::Fail("too big".into()))
+                    }
+                })
+                .expect_err("didn't fail?")
+        };
+        let first_super_failure = {
+            TestRunner::new(config.clone())
+                .run(&input, |v| {
+                    if v.0 >= max / 2 {
+                        Ok(())
+                    } else {
+                        Err(TestCaseError::Fail("too small".into()))
+                    }
+                })
+                .expect_err("didn't fail?")
+        };
+        let second_sub_failure = {
+            TestRunner::new(config.clone())
+                .run(&input, |v| {
+                    if v.0 < max / 2 {
+                        Ok(())
+                    } else {
+                        Err(TestCaseError::Fail("too big".into()))
+                    }
+                })
+                .expect_err("didn't fail?")
+        };
+        let second_super_failure = {
+            TestRunner::new(config.clone())
+                .run(&input, |v| {
+                    if v.0 >= max / 2 {
+                        Ok(())
+                    } else {
+                        Err(TestCaseError::Fail("too small".into()))
+                    }
+                })
+                .expect_err("didn't fail?")
+        };
+
+        assert_eq!(first_sub_failure, second_sub_failure);
+        assert_eq!(first_super_failure, second_super_failure);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn failing_values_persisted_and_replayed_first() {
+        use crate::collection::vec;
+        use crate::test_runner::{MapValuePersistence, ValuePersistence};
+
+        let config = Config {
+            failure_persistence: None,
+            value_persistence: Some(Box::new(MapValuePersistence::default())),
+            source_file: Some("hi"),
+            test_name: Some("foo"),
+            ..Config::default()
+        };
+        let test = |v: Vec<u32>| {
+            if v.contains(&7) {
+                Err(TestCaseError::fail("contains 7"))
+            } else {
+                Ok(())
+            }
+        };
+
+        let mut runner = TestRunner::new(config.clone());
+        let result = runner
+            .run_with_value_persistence(&vec(0u32..10, 0..10), test)
+            .expect_err("didn't fail?");
+        match result {
+            TestError::Fail(why, value) => {
+                assert!(why.message().starts_with("contains 7\n"));
+                assert_eq!(vec![7], value);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        let saved = runner
+            .config()
+            .value_persistence
+            .as_ref()
+            .unwrap()
+            .load_persisted_values(Some("hi"), Some("foo"));
+        assert_eq!(vec!["[7]"], saved);
+
+        // A value which no longer deserializes is skipped, and the rest are
+        // tested as is, even though the strategy can't produce them.
+        let mut values = MapValuePersistence::default();
+        values.save_persisted_value(Some("hi"), None, "\"stale\"");
+        values.save_persisted_value(Some("hi"), None, "[100,7]");
+        let mut runner = TestRunner::new(Config {
+            value_persistence: Some(Box::new(values)),
+            ..config
+        });
+        let result = runner
+            .run_with_value_persistence(&Just(vec![]), test)
+            .expect_err("didn't fail?");
+        assert_eq!(TestError::Fail("contains 7".into(), vec![100, 7]), result);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn flaky_values_persisted() {
+        use crate::test_runner::MapValuePersistence;
+
+        let calls = Cell::new(0);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            value_persistence: Some(Box::new(MapValuePersistence::default())),
+            flaky_reruns: 4,
+            source_file: Some("hi"),
+            test_name: Some("foo"),
+            ..Config::default()
+        });
+        let result = runner.run_with_value_persistence(&(0u32..1000), |v| {
+            calls.set(calls.get() + 1);
+            if v < 500 || calls.get() % 2 == 0 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("flaked"))
+            }
+        });
+
+        let value = match result {
+            Err(TestError::Flaky(_, value, _)) => value,
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        let saved = runner
+            .config()
+            .value_persistence
+            .as_ref()
+            .unwrap()
+            .load_persisted_values(Some("hi"), Some("foo"));
+        assert_eq!(vec![format!("{}", value)], saved);
+    }
+
+    #[test]
+    fn new_rng_makes_separate_rng() {
+        use rand::Rng;
+        let mut runner = TestRunner::default();
+        let from_1 = runner.new_rng().gen::<[u8; 16]>();
+        let from_2 = runner.rng().gen::<[u8; 16]>();
+        assert_ne!(from_1, from_2);
+    }
+
+    #[test]
+    fn failure_seed_reproduces_failing_case() {
+        let strategy = (0u32..1_000_000).no_shrink();
+        let test = |v: u32| {
+            if v % 5 == 0 {
+                Err(TestCaseError::fail("multiple of 5"))
+            } else {
+                Ok(())
+            }
+        };
+        let config = Config {
+            failure_persistence: None,
+            ..Config::default()
+        };
+        let pass_through_data: Vec<u8> = (0..=255).cycle().take(8192).collect();
+
+        for algorithm in [
+            RngAlgorithm::XorShift,
+            RngAlgorithm::ChaCha,
+            RngAlgorithm::PassThrough,
+            RngAlgorithm::Recorder,
+        ] {
+            let config = Config {
+                rng_algorithm: algorithm,
+                ..config.clone()
+            };
+            let mut runner = if RngAlgorithm::PassThrough == algorithm {
+                TestRunner::new_with_rng(
+                    config.clone(),
+                    TestRng::from_seed(algorithm, &pass_through_data),
+                )
+            } else {
+                TestRunner::new(config.clone())
+            };
+            let failure =
+                runner.run(&strategy, test).expect_err("didn't fail?");
+            let seed = runner.failure_seed().expect("no seed").clone();
+            assert!(format!("{}", failure).contains(&format!(
+                "set PROPTEST_SEED=\"{}\" to reproduce it",
+                seed
+            )));
+
+            let runs = Cell::new(0);
+            let mut replay = TestRunner::new(Config {
+                rng_seed: Some(seed.clone()),
+                ..config
+            });
+            let replayed = replay
+                .run(&strategy, |v| {
+                    runs.set(runs.get() + 1);
+                    test(v)
+                })
+                .expect_err("didn't fail?");
+            assert_eq!(failure, replayed, "{:?}", algorithm);
+            assert_eq!(1, runs.get(), "{:?}", algorithm);
+            assert_eq!(Some(&seed), replay.failure_seed());
+        }
+    }
+
+    #[test]
+    fn ramp_size_starts_with_small_cases() {
+        let lens = RefCell::new(Vec::new());
+        let mut runner = TestRunner::new(Config {
+            cases: 100,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&crate::collection::vec(0u8..10, 0..=100), |v| {
+                lens.borrow_mut().push(v.len());
+                Ok(())
+            })
+            .unwrap();
+
+        let lens = lens.into_inner();
+        assert_eq!(100, lens.len());
+        // The first case is at 1/50 of the full size, and the size grows by
+        // that much with each case until the 50th.
+        for (i, &len) in lens[..50].iter().enumerate() {
+            assert!(len <= 2 * (i + 1), "case {} has length {}", i, len);
+        }
+        assert!(lens[50..].iter().any(|&len| len > 50));
+        // Once the run is over, values are generated at the full size again.
+        assert_eq!(1.0, runner.size());
+    }
+
+    #[test]
+    fn ramp_size_disabled_uses_full_size() {
+        let mut runner = TestRunner::new(Config {
+            ramp_size: false,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&crate::collection::vec(0u8..10, 0..=100), |v| {
+                prop_assert!(v.len() < 90);
+                Ok(())
+            })
+            .expect_err("didn't fail?");
+
+        let seed = runner.failure_seed().expect("no seed");
+        assert_eq!(FULL_SIZE, seed.1);
+        assert!(!format!("{}", seed).contains("size"));
+        assert_eq!(1.0, runner.size());
+        assert_eq!(100, runner.scale_to_size(3, 100));
+    }
+
+    #[test]
+    fn ramped_failure_seed_reproduces_failing_case() {
+        let strategy = crate::collection::vec(0u8..10, 0..=100).no_shrink();
+        let test = |v: Vec<u8>| {
+            if v.len() > 5 {
+                Err(TestCaseError::fail("too long"))
+            } else {
+                Ok(())
+            }
+        };
+        let config = Config {
+            failure_persistence: None,
+            ..Config::default()
+        };
+
+        let mut runner = TestRunner::new(config.clone());
+        let failure = runner.run(&strategy, test).expect_err("didn't fail?");
+        let seed = runner.failure_seed().expect("no seed").clone();
+        assert!(seed.1 < FULL_SIZE);
+        assert_eq!(Ok(seed.clone()), format!("{}", seed).parse());
+        assert!(format!("{}", failure).contains(&format!("{}", seed)));
+
+        let mut replay = TestRunner::new(Config {
+            rng_seed: Some(seed),
+            ..config
+        });
+        let replayed = replay.run(&strategy, test).expect_err("didn't fail?");
+        assert_eq!(failure, replayed);
+    }
+
+    #[test]
+    fn small_strategy_tested_exhaustively() {
+        let strategy = (
+            crate::bool::ANY,
+            0u8..4,
+            prop_oneof![Just('a'), Just('b'), Just('c')],
+        );
+        let seen = RefCell::new(Vec::new());
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&strategy, |v| {
+                seen.borrow_mut().push(v);
+                Ok(())
+            })
+            .unwrap();
+
+        let mut seen = seen.into_inner();
+        assert_eq!(24, seen.len());
+        seen.sort();
+        seen.dedup();
+        assert_eq!(24, seen.len());
+    }
+
+    #[test]
+    fn exhaustive_failure_is_shrunk() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u8..100, 0u8..2), |(a, b)| {
+            if a >= 50 && b > 0 {
+                Err(TestCaseError::fail("too big"))
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(Err(TestError::Fail("too big".into(), (50, 1))), result);
+        assert!(runner.failure_seed().is_none());
+    }
+
+    #[test]
+    fn exhaustive_threshold_zero_samples_randomly() {
+        let seen = RefCell::new(Vec::new());
+        let mut runner = TestRunner::new(Config {
+            exhaustive_threshold: 0,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&(0u8..4), |v| {
+                seen.borrow_mut().push(v);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(256, seen.into_inner().len());
+    }
+
+    #[test]
+    fn large_strategy_sampled_randomly() {
+        let mut runner = TestRunner::new(Config {
+            exhaustive_threshold: 10,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let count = Cell::new(0);
+        runner
+            .run(&(0u8..11), |_| {
+                count.set(count.get() + 1);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(256, count.get());
+    }
+
+    #[test]
+    fn strategy_with_more_values_than_cases_sampled_randomly() {
+        let mut runner = TestRunner::new(Config {
+            cases: 2,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let values = RefCell::new(Vec::new());
+        runner
+            .run(&(0u8..4), |v| {
+                values.borrow_mut().push(v);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(2, values.borrow().len());
+
+        let mut runner = TestRunner::new(Config {
+            cases: 4,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        values.borrow_mut().clear();
+        runner
+            .run(&(0u8..4), |v| {
+                values.borrow_mut().push(v);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(vec![0, 1, 2, 3], *values.borrow());
+    }
+
+    #[test]
+    fn run_report_written_to_report_dir() {
+        let dir = std::env::temp_dir()
+            .join(format!("proptest-report-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            report_dir: Some(dir.clone()),
+            test_name: Some("runner::test::report"),
+            ..Config::default()
+        });
+        runner
+            .run(&(0u32..1000), |v| {
+                prop_assert!(v < 500);
+                Ok(())
+            })
+            .expect_err("didn't fail?");
+
+        let report =
+            std::fs::read_to_string(dir.join("proptest-report.jsonl")).unwrap();
+        assert_eq!(1, report.lines().count());
+        assert!(report.starts_with(
+            "{\"test\":\"runner::test::report\",\"source_file\":null,\
+             \"outcome\":\"failed\","
+        ));
+        assert!(report.contains(&format!(
+            "\"seed\":\"{}\"",
+            runner.failure_seed().unwrap()
+        )));
+        assert!(report.contains("\"minimal_value\":\"500\""));
+        assert!(!report.contains("\"shrink_iters\":0,"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn shrink_trace_written_to_shrink_trace_dir() {
+        let dir = std::env::temp_dir()
+            .join(format!("proptest-shrink-trace-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            shrink_trace_dir: Some(dir.clone()),
+            test_name: Some("runner::test::trace"),
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..1000), |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        });
+
+        let path = dir.join("shrink-trace-runner__test__trace.jsonl");
+        match result {
+            Err(TestError::Fail(why, 500)) => assert!(why.message().contains(
+                &format!("\nShrink trace written to {}\n", path.display())
+            )),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        let trace = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = trace.lines().collect();
+        assert!(lines.len() > 2);
+        assert!(lines[0].starts_with("{\"step\":0,\"move\":\"initial\","));
+        assert!(lines[1].starts_with("{\"step\":1,\"move\":\"simplify\","));
+        assert!(lines.iter().any(
+            |line| line.contains("\"value\":\"500\",\"outcome\":\"fail\"")
+        ));
+        assert!(lines[lines.len() - 1].starts_with("{\"stopped\":"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn shrink_trace_includes_choice_shrinking() {
+        let dir = std::env::temp_dir().join(format!(
+            "proptest-choice-shrink-trace-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            choice_shrinking: true,
+            shrink_trace_dir: Some(dir.clone()),
+            test_name: Some("runner::test::choice_trace"),
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..1000).no_shrink(), |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        });
+        assert!(
+            matches!(result, Err(TestError::Fail(_, 500))),
+            "{:?}",
+            result
+        );
+
+        let path = dir.join("shrink-trace-runner__test__choice_trace.jsonl");
+        let trace = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = trace.lines().collect();
+        assert_eq!(
+            1,
+            lines
+                .iter()
+                .filter(|line| line.contains("\"move\":\"initial\""))
+                .count()
+        );
+        assert!(lines[1].starts_with("{\"step\":1,\"move\":\"choices\","));
+        assert!(lines.iter().any(|line| line.contains(
+            "\"move\":\"choices\",\"value\":\"500\",\"outcome\":\"fail\""
+        )));
+        assert!(lines[lines.len() - 1].starts_with("{\"stopped\":"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct RecordingObserver(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl RunObserver for RecordingObserver {
+        fn case_generated(&mut self, value: &dyn fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("generated {:?}", value));
+        }
+
+        fn case_passed(&mut self, value: &dyn fmt::Debug) {
+            self.0.lock().unwrap().push(format!("passed {:?}", value));
+        }
+
+        fn case_rejected(&mut self, value: &dyn fmt::Debug, _: &Reason) {
+            self.0.lock().unwrap().push(format!("rejected {:?}", value));
+        }
+
+        fn case_failed(&mut self, value: &dyn fmt::Debug, _: &Reason) {
+            self.0.lock().unwrap().push(format!("failed {:?}", value));
+        }
+
+        fn shrink_accepted(&mut self, value: &dyn fmt::Debug, _: &Reason) {
+            self.0.lock().unwrap().push(format!("accepted {:?}", value));
+        }
+
+        fn shrink_rejected(&mut self, value: &dyn fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("not accepted {:?}", value));
+        }
+
+        fn run_finished(&mut self, report: &RunReport) {
+            self.0.lock().unwrap().push(format!(
+                "finished {:?} {:?}",
+                report.outcome, report.minimal_value
+            ));
+        }
+
+        fn box_clone(&self) -> Box<dyn RunObserver> {
+            Box::new(self.clone())
+        }
+
+        fn eq(&self, other: &dyn RunObserver) -> bool {
+            other
+                .as_any()
+                .downcast_ref::<Self>()
+                .map_or(false, |x| Arc::ptr_eq(&x.0, &self.0))
+        }
+
+        fn as_any(&self) -> &dyn core::any::Any {
+            self
+        }
+    }
+
+    #[test]
+    fn observer_sees_run_events() {
+        let observer = RecordingObserver::default();
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                failure_persistence: None,
+                observer: Some(Box::new(observer.clone())),
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::default()),
+        );
+        let minimal = match runner.run(&(0u32..1000), |v| {
+            prop_assume!(v % 3 != 0);
+            prop_assert!(v < 900);
+            Ok(())
+        }) {
+            Err(TestError::Fail(_, value)) => value,
+            result => panic!("Unexpected result: {:?}", result),
+        };
+
+        let events = observer.0.lock().unwrap().clone();
+        let failed = events
+            .iter()
+            .position(|e| e.starts_with("failed "))
+            .expect("no failure");
+        let count = |prefix: &str, events: &[String]| {
+            events.iter().filter(|e| e.starts_with(prefix)).count()
+        };
+
+        let cases = &events[..=failed];
+        assert!(count("passed ", cases) > 0);
+        assert!(count("rejected ", cases) > 0);
+        assert_eq!(
+            count("generated ", cases),
+            count("passed ", cases) + count("rejected ", cases) + 1
+        );
+        for pair in cases.chunks(2) {
+            assert_eq!(
+                pair[0]["generated ".len()..],
+                *pair[1].split_once(' ').unwrap().1
+            );
+        }
+
+        let shrinking = &events[failed + 1..events.len() - 1];
+        assert!(count("accepted ", shrinking) > 0);
+        assert!(count("not accepted ", shrinking) > 0);
+        assert_eq!(
+            shrinking.len(),
+            count("accepted ", shrinking) + count("not accepted ", shrinking)
+        );
+        assert_eq!(
+            format!("accepted {}", minimal),
+            *shrinking
+                .iter()
+                .rev()
+                .find(|e| e.starts_with("accepted "))
+                .unwrap()
+        );
+        assert_eq!(
+            format!("finished Failed Some(\"{}\")", minimal),
+            events[events.len() - 1]
+        );
+    }
+
+    #[test]
+    fn record_rng_use() {
+        use rand::Rng;
+
+        // create value with recorder rng
+        let default_config = Config::default();
+        let recorder_rng = TestRng::default_rng(RngAlgorithm::Recorder);
+        let mut runner =
+            TestRunner::new_with_rng(default_config.clone(), recorder_rng);
+        let random_byte_array1 = runner.rng().gen::<[u8; 16]>();
+        let bytes_used = runner.bytes_used();
+        assert!(bytes_used.len() >= 16); // could use more bytes for some reason
+
+        // re-create value with pass-through rng
+        let passthrough_rng =
+            TestRng::from_seed(RngAlgorithm::PassThrough, &bytes_used);
+        let mut runner =
+            TestRunner::new_with_rng(default_config, passthrough_rng);
+        let random_byte_array2 = runner.rng().gen::<[u8; 16]>();
+
+        // make sure the same value was created
+        assert_eq!(random_byte_array1, random_byte_array2);
+    }
+
+    #[test]
+    fn run_parallel_runs_all_cases() {
+        use std::sync::atomic::AtomicU32;
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            threads: 4,
+            ..Config::default()
+        });
+        let runs = AtomicU32::new(0);
+        let result = runner.run_parallel(&(0u32..), |_| {
+            runs.fetch_add(1, SeqCst);
+            Ok(())
+        });
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(runner.config().cases, runs.into_inner());
+        assert_eq!(runner.config().cases, runner.successes);
+    }
+
+    #[test]
+    fn run_parallel_matches_sequential_run() {
+        let run = |threads| {
+            let config = Config {
+                failure_persistence: None,
+                threads,
+                ..Config::default()
+            };
+            let mut runner = TestRunner::new_with_rng(
+                config.clone(),
+                TestRng::deterministic_rng(config.rng_algorithm),
+            );
+            // The length of the vectors follows the size, so any case
+            // generated at a different size shows up as a different input.
+            let result = runner.run_parallel(
+                &crate::collection::vec(0u32..1_000_000, 1..100),
+                |v| {
+                    prop_assume!(v[0] % 3 != 0);
+                    prop_assert!(v.len() < 60);
+                    Ok(())
+                },
+            );
+            (result, runner.successes, runner.global_rejects)
+        };
+
+        let sequential = run(1);
+        let parallel = run(4);
+        // The failure reason includes the seed and size of the failing case.
+        assert_eq!(sequential, parallel);
+        match parallel.0 {
+            Err(TestError::Fail(_, value)) => assert_eq!(60, value.len()),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn run_parallel_reports_panics_as_failures() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            threads: 4,
+            ..Config::default()
+        });
+        let line = line!() + 2;
+        let result = runner.run_parallel(&(0u32..10u32), |v| {
+            assert!(v < 5, "not less than 5");
+            Ok(())
+        });
+        let why = format!("not less than 5 at {}:{}", file!(), line);
+        assert_eq!(Err(TestError::Fail(why.into(), 5)), result);
+    }
+
+    #[test]
+    fn run_parallel_does_not_count_cache_hits() {
+        use crate::test_runner::basic_result_cache;
+        use std::collections::HashSet;
+        use std::sync::Mutex;
+
+        let mut runner = TestRunner::new(Config {
+            cases: 10,
+            exhaustive_threshold: 0,
+            failure_persistence: None,
+            result_cache: basic_result_cache,
+            threads: 4,
+            ..Config::default()
+        });
+        let seen = Mutex::new(HashSet::new());
+        let result = runner.run_parallel(&(0u32..10), |v| {
+            seen.lock().unwrap().insert(v);
+            Ok(())
+        });
+
+        // Only inputs which miss the cache count towards `cases`, so every
+        // value must have been tried.
+        assert_eq!(Ok(()), result);
+        assert_eq!(10, runner.successes);
+        assert_eq!(10, seen.into_inner().unwrap().len());
+    }
+
+    #[test]
+    fn run_parallel_uses_result_cache() {
+        use crate::test_runner::{ResultCache, ResultCacheKey};
+
+        struct FailingCache(TestCaseResult);
+        impl ResultCache for FailingCache {
+            fn key(&self, _: &ResultCacheKey) -> u64 {
+                0
+            }
+            fn put(&mut self, _: u64, _: &TestCaseResult) {}
+            fn get(&self, _: u64) -> Option<&TestCaseResult> {
+                Some(&self.0)
+            }
+        }
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            threads: 4,
+            result_cache: || {
+                Box::new(FailingCache(Err(TestCaseError::fail("cached"))))
+            },
+            ..Config::default()
+        });
+        let result = runner.run_parallel(&(0u32..1_000_000), |_| {
+            panic!("cached case was run");
+        });
+        match result {
+            Err(TestError::Fail(why, 0)) => {
+                assert!(why.message().starts_with("cached\n"))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
-    /// Update the state to account for a local rejection from `whence`, and
-    /// return `Ok` if the caller should keep going or `Err` to abort.
-    pub fn reject_local(
-        &mut self,
-        whence: impl Into<Reason>,
-    ) -> Result<(), Reason> {
-        if self.local_rejects >= self.config.max_local_rejects {
-            Err("Too many local rejects".into())
-        } else {
-            self.local_rejects += 1;
-            Self::insert_or_increment(
-                &mut self.local_reject_detail,
-                whence.into(),
-            );
-            Ok(())
+    #[test]
+    fn call_test_batch_fails_cases_exceeding_timeout() {
+        let results = call_test_batch(
+            &|v: u32| {
+                if v >= 2 {
+                    std::thread::sleep(Duration::from_millis(200));
+                }
+                Ok(())
+            },
+            vec![0, 1, 2, 3],
+            4,
+            100,
+        );
+
+        let passed = results
+            .iter()
+            .map(|(result, _)| result.is_ok())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![true, true, false, false], passed);
+        match results[2].0 {
+            Err(TestCaseError::Fail(ref why)) => {
+                assert!(why.message().starts_with("Timeout of 100 ms"))
+            }
+            ref result => panic!("Unexpected result: {:?}", result),
         }
     }
 
-    /// Update the state to account for a global rejection from `whence`, and
-    /// return `Ok` if the caller should keep going or `Err` to abort.
-    fn reject_global<T>(&mut self, whence: Reason) -> Result<(), TestError<T>> {
-        if self.global_rejects >= self.config.max_global_rejects {
-            Err(TestError::Abort("Too many global rejects".into()))
-        } else {
-            self.global_rejects += 1;
-            Self::insert_or_increment(&mut self.global_reject_detail, whence);
+    #[test]
+    fn watchdog_ends_run_on_hung_case() {
+        let slow_calls = Arc::new(AtomicUsize::new(0));
+        let calls = Arc::clone(&slow_calls);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            watchdog_timeout: 50,
+            ..Config::default()
+        });
+        let start = std::time::Instant::now();
+        let result = runner.run_with_watchdog(&(0u32..1000), move |v| {
+            if v >= 500 {
+                calls.fetch_add(1, SeqCst);
+                std::thread::sleep(std::time::Duration::from_secs(5));
+            }
             Ok(())
-        }
-    }
+        });
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
 
-    /// Insert 1 or increment the rejection detail at key for whence.
-    fn insert_or_increment(into: &mut RejectionDetail, whence: Reason) {
-        into.entry(whence)
-            .and_modify(|count| *count += 1)
-            .or_insert(1);
+        match result {
+            Err(TestError::Fail(why, v)) => {
+                assert!(
+                    why.message().starts_with("Timeout of 50 ms exceeded"),
+                    "{}",
+                    why
+                );
+                assert!(v >= 500, "{}", v);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        // The hung case was neither re-run nor shrunk.
+        assert_eq!(1, slow_calls.load(SeqCst));
     }
 
-    /// Increment the counter of flat map regenerations and return whether it
-    /// is still under the configured limit.
-    pub fn flat_map_regen(&self) -> bool {
-        self.flat_map_regens.fetch_add(1, SeqCst)
-            < self.config.max_flat_map_regens as usize
-    }
+    #[test]
+    fn watchdog_ignored_outside_run_with_watchdog() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            watchdog_timeout: 50,
+            ..Config::default()
+        });
+        assert!(runner.watchdog_ignored());
+        runner.watchdog_tripped = Some(Arc::new(AtomicBool::new(false)));
+        assert!(!runner.watchdog_ignored());
 
-    fn new_cache(&self) -> Box<dyn ResultCache> {
-        (self.config.result_cache)()
+        let runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        assert!(!runner.watchdog_ignored());
     }
-}
-
-#[cfg(feature = "fork")]
-fn init_replay(rng: &mut TestRng) -> (Vec<TestCaseResult>, ForkOutput) {
-    use crate::test_runner::replay::{open_file, Replay, ReplayFileStatus::*};
 
-    if let Some(path) = env::var_os(ENV_FORK_FILE) {
-        let mut file = open_file(&path).expect("Failed to open replay file");
-        let loaded =
-            Replay::parse_from(&mut file).expect("Failed to read replay file");
-        match loaded {
-            InProgress(replay) => {
-                rng.set_seed(replay.seed);
-                (replay.steps, ForkOutput { file: Some(file) })
-            }
+    #[test]
+    fn watchdog_runs_quick_cases_normally() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            watchdog_timeout: 10_000,
+            cases: 20,
+            ..Config::default()
+        });
+        let result = runner.run_with_watchdog(&(0u32..1000), |v| {
+            prop_classify!(v < 1000, "in range");
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+        assert!(format!("{}", runner).contains("100.00% (20) in range"));
 
-            Terminated(_) => {
-                panic!("Replay file for child process is terminated?")
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            watchdog_timeout: 10_000,
+            ..Config::default()
+        });
+        let result = runner.run_with_watchdog(&(0u32..1000), |v| {
+            prop_assert!(v < 500, "not less than 500");
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(why, v)) => {
+                assert!(why.message().starts_with("not less than 500"));
+                assert_eq!(500, v);
             }
-
-            Corrupt => panic!("Replay file for child process is corrupt"),
+            result => panic!("Unexpected result: {:?}", result),
         }
-    } else {
-        (vec![], ForkOutput::empty())
     }
-}
-
-#[cfg(not(feature = "fork"))]
-fn init_replay(
-    _rng: &mut TestRng,
-) -> (iter::Empty<TestCaseResult>, ForkOutput) {
-    (iter::empty(), ForkOutput::empty())
-}
 
-#[cfg(feature = "fork")]
-fn await_child_without_timeout(
-    child: &mut rusty_fork::ChildWrapper,
-) -> (Option<TestCaseError>, Option<u64>) {
-    let status = child.wait().expect("Failed to wait for child process");
+    #[test]
+    fn labels_reported_for_passing_cases() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            cases: 100,
+            exhaustive_threshold: 0,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..100), |v| {
+            prop_assume!(v % 2 == 0);
+            prop_classify!(true, "even");
+            prop_collect!(v, 50);
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
 
-    if status.success() {
-        (None, None)
-    } else {
-        (
-            Some(TestCaseError::fail(format!(
-                "Child process exited with {}",
-                status
-            ))),
-            None,
-        )
+        let display = format!("{}", runner);
+        assert!(display.contains("labels (of 100 passing cases):"));
+        assert!(display.contains("100.00% (100) even"));
+        assert!(display.contains(" 0..50"));
+        assert!(display.contains(" 50..100"));
     }
-}
-
-#[cfg(all(feature = "fork", not(feature = "timeout")))]
-fn await_child(
-    child: &mut rusty_fork::ChildWrapper,
-    _: &mut tempfile::NamedTempFile,
-    _timeout: u32,
-) -> (Option<TestCaseError>, Option<u64>) {
-    await_child_without_timeout(child)
-}
 
-#[cfg(all(feature = "fork", feature = "timeout"))]
-fn await_child(
-    child: &mut rusty_fork::ChildWrapper,
-    forkfile: &mut tempfile::NamedTempFile,
-    timeout: u32,
-) -> (Option<TestCaseError>, Option<u64>) {
-    use std::time::Duration;
+    #[test]
+    fn unmet_coverage_aborts_run() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..100), |v| {
+            prop_cover!(v < 10, 50, "single digit");
+            Ok(())
+        });
+        match result {
+            Err(TestError::Abort(why)) => {
+                assert!(why.message().contains("`single digit`"))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
 
-    if 0 == timeout {
-        return await_child_without_timeout(child);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..100), |v| {
+            prop_cover!(v < 10, 1, "single digit");
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
     }
 
-    // The child can run for longer than the timeout since it may run
-    // multiple tests. Each time the timeout expires, we check whether the
-    // file has grown larger. If it has, we allow the child to keep running
-    // until the next timeout.
-    let mut last_forkfile_len = forkfile
-        .as_file()
-        .metadata()
-        .map(|md| md.len())
-        .unwrap_or(0);
+    #[test]
+    fn targeting_climbs_towards_high_scores() {
+        let config = Config {
+            failure_persistence: None,
+            cases: 100,
+            edge_case_percent: 0,
+            ..Config::default()
+        };
+        // Larger values are simpler here, so walking the value tree of a
+        // high-scoring case is able to reach the failure, whereas random
+        // generation has to hit a 0.01% window (edge cases are turned off,
+        // since the failure is at the end of the range). Shrinking then heads
+        // for the simplest failing value, 999_999.
+        let strategy = (0u32..1_000_000).prop_map(|v| 999_999 - v);
+        let test = |v: u32| {
+            prop_target!(v);
+            prop_assert!(v < 999_900);
+            Ok(())
+        };
 
-    loop {
-        if let Some(status) = child
-            .wait_timeout(Duration::from_millis(timeout.into()))
-            .expect("Failed to wait for child process")
-        {
-            if status.success() {
-                return (None, None);
-            } else {
-                return (
-                    Some(TestCaseError::fail(format!(
-                        "Child process exited with {}",
-                        status
-                    ))),
-                    None,
-                );
+        let mut runner = TestRunner::new_with_rng(
+            config.clone(),
+            TestRng::deterministic_rng(config.rng_algorithm),
+        );
+        match runner.run(&strategy, test) {
+            Err(TestError::Fail(why, value)) => {
+                assert_eq!(999_999, value);
+                assert!(why.message().contains("found by targeting"));
             }
+            result => panic!("Unexpected result: {:?}", result),
         }
 
-        let current_len = forkfile
-            .as_file()
-            .metadata()
-            .map(|md| md.len())
-            .unwrap_or(0);
-        // If we've gone a full timeout period without the file growing,
-        // fail the test and kill the child.
-        if current_len <= last_forkfile_len {
-            return (
-                Some(TestCaseError::fail(format!(
-                    "Timed out waiting for child process"
-                ))),
-                Some(current_len),
-            );
-        } else {
-            last_forkfile_len = current_len;
-        }
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                target_percent: 0,
+                ..config.clone()
+            },
+            TestRng::deterministic_rng(config.rng_algorithm),
+        );
+        assert_eq!(Ok(()), runner.run(&strategy, test));
+        assert_eq!(100, runner.successes);
     }
-}
 
-#[cfg(test)]
-mod test {
-    use std::cell::Cell;
-    use std::fs;
+    #[test]
+    fn targeting_does_not_rerun_cases() {
+        use std::cell::RefCell;
+        use std::collections::HashSet;
 
-    use super::*;
-    use crate::strategy::Strategy;
-    use crate::test_runner::{FileFailurePersistence, RngAlgorithm, TestRng};
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            cases: 200,
+            edge_case_percent: 0,
+            ..Config::default()
+        });
+        let seen = RefCell::new(HashSet::new());
+        // Since the values cannot be simplified, every case tried while
+        // climbing is a new one, generated from a new seed.
+        let strategy = crate::arbitrary::any::<u64>().no_shrink();
+        let result = runner.run(&strategy, |v| {
+            assert!(seen.borrow_mut().insert(v), "{} was run twice", v);
+            prop_target!(v as f64);
+            Ok(())
+        });
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(200, seen.into_inner().len());
+    }
 
     #[test]
-    fn gives_up_after_too_many_rejections() {
-        let config = Config::default();
+    fn choice_shrinking_shrinks_unshrinkable_strategies() {
+        let config = Config {
+            failure_persistence: None,
+            choice_shrinking: true,
+            ..Config::default()
+        };
+        let strategy =
+            crate::collection::vec(crate::num::u8::ANY, 0..50).no_shrink();
+
         let mut runner = TestRunner::new(config.clone());
-        let runs = Cell::new(0);
-        let result = runner.run(&(0u32..), |_| {
-            runs.set(runs.get() + 1);
-            Err(TestCaseError::reject("reject"))
-        });
-        match result {
-            Err(TestError::Abort(_)) => (),
-            e => panic!("Unexpected result: {:?}", e),
+        match runner.run(&strategy, |v| {
+            prop_assert!(v.len() < 3);
+            Ok(())
+        }) {
+            Err(TestError::Fail(_, value)) => assert_eq!(vec![0, 0, 0], value),
+            result => panic!("Unexpected result: {:?}", result),
         }
-        assert_eq!(config.max_global_rejects + 1, runs.get());
     }
 
     #[test]
-    fn test_pass() {
-        let mut runner = TestRunner::default();
-        let result = runner.run(&(1u32..), |v| {
-            assert!(v > 0);
+    fn choice_shrinking_works_through_flat_map_and_perturb() {
+        let config = Config {
+            failure_persistence: None,
+            choice_shrinking: true,
+            ..Config::default()
+        };
+        let strategy = (1usize..20)
+            .prop_flat_map(|len| crate::collection::vec(0u32..1000, len))
+            .prop_perturb(|mut v, mut rng| {
+                let extra = rand::Rng::gen_range(&mut rng, 0..1000);
+                v.push(extra);
+                v
+            });
+
+        let mut runner = TestRunner::new(config.clone());
+        match runner.run(&strategy, |v| {
+            prop_assert!(v.iter().sum::<u32>() < 500);
             Ok(())
-        });
-        assert_eq!(Ok(()), result);
+        }) {
+            // The last element comes from the RNG passed to `prop_perturb`.
+            // Its seed is part of the choice sequence, but the values it
+            // produces are not, so they cannot be minimised.
+            Err(TestError::Fail(_, value)) => {
+                assert_eq!(2, value.len());
+                assert_eq!(0, value[0]);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
     }
 
+    #[cfg(feature = "fork")]
     #[test]
-    fn test_fail_via_result() {
+    fn run_successful_test_in_fork() {
         let mut runner = TestRunner::new(Config {
-            failure_persistence: None,
+            fork: true,
+            test_name: Some(concat!(
+                module_path!(),
+                "::run_successful_test_in_fork"
+            )),
             ..Config::default()
         });
-        let result = runner.run(&(0u32..10u32), |v| {
-            if v < 5 {
-                Ok(())
-            } else {
-                Err(TestCaseError::fail("not less than 5"))
-            }
-        });
 
-        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+        assert!(runner.run(&(0u32..1000), |_| Ok(())).is_ok());
     }
 
+    #[cfg(feature = "fork")]
     #[test]
-    fn test_fail_via_panic() {
+    fn labels_reported_from_fork() {
         let mut runner = TestRunner::new(Config {
+            fork: true,
             failure_persistence: None,
+            cases: 100,
+            test_name: Some(concat!(
+                module_path!(),
+                "::labels_reported_from_fork"
+            )),
             ..Config::default()
         });
-        let result = runner.run(&(0u32..10u32), |v| {
-            assert!(v < 5, "not less than 5");
+        let result = runner.run(&(0u32..1000), |v| {
+            prop_classify!(true, "any; =label");
+            prop_cover!(v < 500, 10, "small");
             Ok(())
         });
-        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+        assert_eq!(Ok(()), result);
+
+        let display = format!("{}", runner);
+        assert!(display.contains("labels (of 100 passing cases):"));
+        assert!(display.contains("100.00% (100) any; =label"));
+        assert!(display.contains(") small (required: 10%)"));
     }
 
+    #[cfg(feature = "fork")]
     #[test]
-    fn persisted_cases_do_not_count_towards_total_cases() {
-        const FILE: &'static str = "persistence-test.txt";
-        let _ = fs::remove_file(FILE);
-
-        let config = Config {
-            failure_persistence: Some(Box::new(
-                FileFailurePersistence::Direct(FILE),
+    fn normal_failure_in_fork_results_in_correct_failure() {
+        let mut runner = TestRunner::new(Config {
+            fork: true,
+            test_name: Some(concat!(
+                module_path!(),
+                "::normal_failure_in_fork_results_in_correct_failure"
             )),
-            cases: 1,
             ..Config::default()
-        };
-
-        let max = 10_000_000i32;
-        {
-            TestRunner::new(config.clone())
-                .run(&(0i32..max), |_v| {
-                    Err(TestCaseError::Fail("persist a failure".into()))
-                })
-                .expect_err("didn't fail?");
-        }
+        });
 
-        let run_count = RefCell::new(0);
-        TestRunner::new(config.clone())
-            .run(&(0i32..max), |_v| {
-                *run_count.borrow_mut() += 1;
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                prop_assert!(v < 500);
                 Ok(())
             })
-            .expect("should succeed");
-
-        // Persisted ran, and a new case ran, and only new case counts
-        // against `cases: 1`.
-        assert_eq!(run_count.into_inner(), 2);
-    }
+            .err()
+            .unwrap();
 
-    #[derive(Clone, Copy, PartialEq)]
-    struct PoorlyBehavedDebug(i32);
-    impl fmt::Debug for PoorlyBehavedDebug {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "\r\n{:?}\r\n", self.0)
+        match failure {
+            TestError::Fail(_, value) => assert_eq!(500, value),
+            failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
 
+    #[cfg(feature = "fork")]
     #[test]
-    fn failing_cases_persisted_and_reloaded() {
-        const FILE: &'static str = "persistence-test.txt";
-        let _ = fs::remove_file(FILE);
-
-        let max = 10_000_000i32;
-        let input = (0i32..max).prop_map(PoorlyBehavedDebug);
-        let config = Config {
-            failure_persistence: Some(Box::new(
-                FileFailurePersistence::Direct(FILE),
+    fn nonsuccessful_exit_finds_correct_failure() {
+        let mut runner = TestRunner::new(Config {
+            fork: true,
+            test_name: Some(concat!(
+                module_path!(),
+                "::nonsuccessful_exit_finds_correct_failure"
             )),
             ..Config::default()
-        };
+        });
 
-        // First test with cases that fail above half max, and then below half
-        // max, to ensure we can correctly parse both lines of the persistence
-        // file.
-        let first_sub_failure = {
-            TestRunner::new(config.clone())
-                .run(&input, |v| {
-                    if v.0 < max / 2 {
-                        Ok(())
-                    } else {
-                        Err(TestCaseError::Fail("too big".into()))
-                    }
-                })
-                .expect_err("didn't fail?")
-        };
-        let first_super_failure = {
-            TestRunner::new(config.clone())
-                .run(&input, |v| {
-                    if v.0 >= max / 2 {
-                        Ok(())
-                    } else {
-                        Err(TestCaseError::Fail("too small".into()))
-                    }
-                })
-                .expect_err("didn't fail?")
-        };
-        let second_sub_failure = {
-            TestRunner::new(config.clone())
-                .run(&input, |v| {
-                    if v.0 < max / 2 {
-                        Ok(())
-                    } else {
-                        Err(TestCaseError::Fail("too big".into()))
-                    }
-                })
-                .expect_err("didn't fail?")
-        };
-        let second_super_failure = {
-            TestRunner::new(config.clone())
-                .run(&input, |v| {
-                    if v.0 >= max / 2 {
-                        Ok(())
-                    } else {
-                        Err(TestCaseError::Fail("too small".into()))
-                    }
-                })
-                .expect_err("didn't fail?")
-        };
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                if v >= 500 {
+                    ::std::process::exit(1);
+                }
+                Ok(())
+            })
+            .err()
+            .unwrap();
 
-        assert_eq!(first_sub_failure, second_sub_failure);
-        assert_eq!(first_super_failure, second_super_failure);
+        match failure {
+            TestError::Fail(_, value) => assert_eq!(500, value),
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
     }
 
+    #[cfg(feature = "std")]
     #[test]
-    fn new_rng_makes_separate_rng() {
-        use rand::Rng;
+    fn async_failure_is_shrunk() {
         let mut runner = TestRunner::default();
-        let from_1 = runner.new_rng().gen::<[u8; 16]>();
-        let from_2 = runner.rng().gen::<[u8; 16]>();
-        assert_ne!(from_1, from_2);
+        let failure = runner
+            .run_async(
+                &(0u32..1000),
+                |v| async move {
+                    prop_assert!(v < 500);
+                    Ok(())
+                },
+                crate::test_runner::block_on,
+            )
+            .err()
+            .unwrap();
+
+        match failure {
+            TestError::Fail(_, value) => assert_eq!(500, value),
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
     }
 
+    #[cfg(all(feature = "fork", unix))]
     #[test]
-    fn record_rng_use() {
-        use rand::Rng;
-
-        // create value with recorder rng
-        let default_config = Config::default();
-        let recorder_rng = TestRng::default_rng(RngAlgorithm::Recorder);
-        let mut runner =
-            TestRunner::new_with_rng(default_config.clone(), recorder_rng);
-        let random_byte_array1 = runner.rng().gen::<[u8; 16]>();
-        let bytes_used = runner.bytes_used();
-        assert!(bytes_used.len() >= 16); // could use more bytes for some reason
+    fn crash_in_fork_reports_signal_and_output() {
+        let mut runner = TestRunner::new(Config {
+            fork: true,
+            test_name: Some(concat!(
+                module_path!(),
+                "::crash_in_fork_reports_signal_and_output"
+            )),
+            ..Config::default()
+        });
 
-        // re-create value with pass-through rng
-        let passthrough_rng =
-            TestRng::from_seed(RngAlgorithm::PassThrough, &bytes_used);
-        let mut runner =
-            TestRunner::new_with_rng(default_config, passthrough_rng);
-        let random_byte_array2 = runner.rng().gen::<[u8; 16]>();
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                if v >= 500 {
+                    eprintln!("native code crashed on {}", v);
+                    std::process::abort();
+                }
+                Ok(())
+            })
+            .err()
+            .unwrap();
 
-        // make sure the same value was created
-        assert_eq!(random_byte_array1, random_byte_array2);
+        match failure {
+            TestError::Fail(why, value) => {
+                assert_eq!(500, value);
+                assert!(why.message().contains("SIGABRT"), "{}", why);
+                assert!(
+                    why.message().contains("native code crashed on 500"),
+                    "{}",
+                    why
+                );
+                assert!(
+                    !why.message().contains("native code crashed on 501"),
+                    "{}",
+                    why
+                );
+            }
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
     }
 
     #[cfg(feature = "fork")]
     #[test]
-    fn run_successful_test_in_fork() {
+    fn exit_in_fork_reports_code() {
         let mut runner = TestRunner::new(Config {
             fork: true,
             test_name: Some(concat!(
                 module_path!(),
-                "::run_successful_test_in_fork"
+                "::exit_in_fork_reports_code"
             )),
             ..Config::default()
         });
 
-        assert!(runner.run(&(0u32..1000), |_| Ok(())).is_ok());
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                if v >= 500 {
+                    std::process::exit(3);
+                }
+                Ok(())
+            })
+            .err()
+            .unwrap();
+
+        match failure {
+            TestError::Fail(why, value) => {
+                assert_eq!(500, value);
+                assert!(
+                    why.message()
+                        .starts_with("Child process exited with code 3"),
+                    "{}",
+                    why
+                );
+            }
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
     }
 
-    #[cfg(feature = "fork")]
+    #[cfg(all(feature = "fork", target_os = "linux"))]
     #[test]
-    fn normal_failure_in_fork_results_in_correct_failure() {
+    fn memory_limit_failure_in_fork_is_shrunk() {
         let mut runner = TestRunner::new(Config {
-            fork: true,
+            memory_limit: 256,
             test_name: Some(concat!(
                 module_path!(),
-                "::normal_failure_in_fork_results_in_correct_failure"
+                "::memory_limit_failure_in_fork_is_shrunk"
             )),
             ..Config::default()
         });
 
         let failure = runner
             .run(&(0u32..1000), |v| {
-                prop_assert!(v < 500);
+                if v >= 500 {
+                    let huge = vec![v as u8; 1 << 30];
+                    prop_assert_eq!(v as u8, huge[huge.len() - 1]);
+                }
                 Ok(())
             })
             .err()
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(why, value) => {
+                assert_eq!(500, value);
+                assert!(why.message().contains("exceeded 256 MiB"), "{}", why);
+            }
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
 
-    #[cfg(feature = "fork")]
+    #[cfg(all(feature = "fork", target_os = "linux"))]
     #[test]
-    fn nonsuccessful_exit_finds_correct_failure() {
+    fn cpu_time_limit_applies_to_each_case() {
+        use std::time::{Duration, Instant};
+
         let mut runner = TestRunner::new(Config {
-            fork: true,
+            cases: 40,
+            cpu_time_limit: 1,
             test_name: Some(concat!(
                 module_path!(),
-                "::nonsuccessful_exit_finds_correct_failure"
+                "::cpu_time_limit_applies_to_each_case"
+            )),
+            ..Config::default()
+        });
+
+        // Together the cases take about two seconds of CPU time.
+        let result = runner.run(&(0u32..1000), |_| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(50) {}
+            Ok(())
+        });
+        assert!(result.is_ok(), "{:?}", result);
+    }
+
+    #[cfg(all(feature = "fork", target_os = "linux"))]
+    #[test]
+    fn output_limit_failure_in_fork_is_shrunk() {
+        let mut runner = TestRunner::new(Config {
+            output_limit: 1,
+            test_name: Some(concat!(
+                module_path!(),
+                "::output_limit_failure_in_fork_is_shrunk"
             )),
             ..Config::default()
         });
@@ -1306,7 +5083,10 @@ mod test {
         let failure = runner
             .run(&(0u32..1000), |v| {
                 if v >= 500 {
-                    ::std::process::exit(1);
+                    let line = "x".repeat(1023);
+                    for _ in 0..2048 {
+                        println!("{}", line);
+                    }
                 }
                 Ok(())
             })
@@ -1314,7 +5094,10 @@ mod test {
             .unwrap();
 
         match failure {
-            TestError::Fail(_, value) => assert_eq!(500, value),
+            TestError::Fail(why, value) => {
+                assert_eq!(500, value);
+                assert!(why.message().contains("exceeded 1 MiB"), "{}", why);
+            }
             failure => panic!("Unexpected failure: {:?}", failure),
         }
     }
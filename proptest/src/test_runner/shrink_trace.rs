@@ -0,0 +1,168 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Traces of the steps taken while shrinking, written to
+//! `Config::shrink_trace_dir`.
+
+use core::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::time::Instant;
+
+use crate::test_runner::report::{file_name_part, json_string};
+use crate::test_runner::TestCaseError;
+
+/// How the value tree was moved to reach a value tried while shrinking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ShrinkMove {
+    /// The failing value shrinking started from.
+    Initial,
+    Simplify,
+    Complicate,
+    /// A value regenerated from a simplified choice sequence, with
+    /// `Config::choice_shrinking`.
+    Choices,
+}
+
+impl ShrinkMove {
+    fn name(self) -> &'static str {
+        match self {
+            ShrinkMove::Initial => "initial",
+            ShrinkMove::Simplify => "simplify",
+            ShrinkMove::Complicate => "complicate",
+            ShrinkMove::Choices => "choices",
+        }
+    }
+}
+
+/// The steps of one shrinking of a failing case, as lines of JSON.
+#[derive(Clone)]
+pub(crate) struct ShrinkTrace {
+    start: Instant,
+    steps: u32,
+    out: String,
+}
+
+impl ShrinkTrace {
+    pub(crate) fn new() -> Self {
+        ShrinkTrace {
+            start: Instant::now(),
+            steps: 0,
+            out: String::new(),
+        }
+    }
+
+    /// Record that `value`, reached by `moved`, was tested with `result`,
+    /// and whether it was `accepted` as the simplest failure so far.
+    pub(crate) fn step<T>(
+        &mut self,
+        moved: ShrinkMove,
+        value: &dyn fmt::Debug,
+        result: &Result<T, TestCaseError>,
+        accepted: bool,
+    ) {
+        let (outcome, reason) = match *result {
+            Ok(_) => ("pass", None),
+            Err(TestCaseError::Reject(ref why)) => ("reject", Some(why)),
+            Err(TestCaseError::Fail(ref why)) => ("fail", Some(why)),
+        };
+
+        let _ = write!(self.out, "{{\"step\":{},\"move\":", self.steps);
+        json_string(&mut self.out, moved.name());
+        self.out.push_str(",\"value\":");
+        json_string(&mut self.out, &format!("{:?}", value));
+        self.out.push_str(",\"outcome\":");
+        json_string(&mut self.out, outcome);
+        self.out.push_str(",\"reason\":");
+        match reason {
+            Some(why) => json_string(&mut self.out, why.message()),
+            None => self.out.push_str("null"),
+        }
+        let _ = writeln!(
+            self.out,
+            ",\"accepted\":{},\"elapsed_secs\":{}}}",
+            accepted,
+            self.start.elapsed().as_secs_f64()
+        );
+        self.steps += 1;
+    }
+
+    /// Record `why` shrinking stopped, which ends the trace.
+    pub(crate) fn finish(&mut self, why: &str) {
+        self.out.push_str("{\"stopped\":");
+        json_string(&mut self.out, why);
+        let _ = writeln!(
+            self.out,
+            ",\"steps\":{},\"elapsed_secs\":{}}}",
+            self.steps,
+            self.start.elapsed().as_secs_f64()
+        );
+    }
+
+    /// Write the trace to a file in `dir` named after `test_name`, creating
+    /// the directory if necessary, and return the path of the file.
+    ///
+    /// `index` counts the traces already written during the same run, which
+    /// has more than one when collecting distinct failures.
+    pub(crate) fn write(
+        &self,
+        dir: &Path,
+        test_name: Option<&str>,
+        index: u32,
+    ) -> io::Result<PathBuf> {
+        let mut name = format!("shrink-trace-{}", file_name_part(test_name));
+        if index > 0 {
+            let _ = write!(name, "-{}", index + 1);
+        }
+        name.push_str(".jsonl");
+
+        fs::create_dir_all(dir)?;
+        let path = dir.join(name);
+        fs::write(&path, &self.out)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::test_runner::TestCaseResult;
+
+    #[test]
+    fn steps_are_written_as_json_lines() {
+        let mut trace = ShrinkTrace::new();
+        trace.step(
+            ShrinkMove::Initial,
+            &"ab",
+            &TestCaseResult::Err(TestCaseError::fail("too \"long\"")),
+            true,
+        );
+        trace.step(ShrinkMove::Simplify, &"", &TestCaseResult::Ok(()), false);
+        trace.finish("complicate returned false");
+
+        let lines: Vec<&str> = trace.out.lines().collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with(
+            "{\"step\":0,\"move\":\"initial\",\"value\":\"\\\"ab\\\"\",\
+             \"outcome\":\"fail\",\"reason\":\"too \\\"long\\\"\",\
+             \"accepted\":true,\"elapsed_secs\":"
+        ));
+        assert!(lines[1].starts_with(
+            "{\"step\":1,\"move\":\"simplify\",\"value\":\"\\\"\\\"\",\
+             \"outcome\":\"pass\",\"reason\":null,\"accepted\":false,"
+        ));
+        assert!(lines[2].starts_with(
+            "{\"stopped\":\"complicate returned false\",\"steps\":2,"
+        ));
+    }
+}
@@ -0,0 +1,397 @@
+//-
+// Copyright 2023 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `prop_classify!`, `prop_collect!`, `prop_cover!` and
+//! `prop_target!`.
+//!
+//! Labels are attached to whichever test case is currently running on the
+//! calling thread, and the runner folds them into a `LabelStatistics` once it
+//! knows the case passed.
+
+use crate::std_facade::{fmt, BTreeMap, BTreeSet, String, ToOwned, Vec};
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+#[cfg(feature = "std")]
+use std::string::ToString;
+
+use crate::test_runner::Reason;
+
+/// The labels attached to a single test case.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct CaseLabels {
+    labels: BTreeSet<String>,
+    coverage: BTreeMap<String, f64>,
+    target: Option<f64>,
+}
+
+impl CaseLabels {
+    /// The highest score reported via `prop_target!`, if any.
+    pub(crate) fn target(&self) -> Option<f64> {
+        self.target
+    }
+}
+
+/// Encoding of `CaseLabels` in replay files, which only uses characters that
+/// do not mean anything else there.
+///
+/// Each label is written as `l<hex>;`, each coverage requirement as
+/// `r<hex>=<bits>;` and the target as `t<bits>;`, where `<hex>` is the UTF-8
+/// of the label and `<bits>` the bits of the `f64`, both in hex.
+#[cfg(feature = "fork")]
+impl CaseLabels {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+            && self.coverage.is_empty()
+            && self.target.is_none()
+    }
+
+    /// Whether `ch` can occur in the encoding of labels.
+    pub(crate) fn is_replay_char(ch: char) -> bool {
+        ch.is_ascii_hexdigit() || "lrt;=".contains(ch)
+    }
+
+    pub(crate) fn to_replay(&self) -> String {
+        use core::fmt::Write;
+
+        fn hex(s: &str) -> String {
+            s.bytes().map(|b| format!("{:02x}", b)).collect()
+        }
+
+        let mut out = String::new();
+        for label in &self.labels {
+            let _ = write!(out, "l{};", hex(label));
+        }
+        for (label, min_pct) in &self.coverage {
+            let _ = write!(out, "r{}={:016x};", hex(label), min_pct.to_bits());
+        }
+        if let Some(target) = self.target {
+            let _ = write!(out, "t{:016x};", target.to_bits());
+        }
+        out
+    }
+
+    /// Parse the output of `to_replay`, returning `None` if it is malformed.
+    pub(crate) fn from_replay(s: &str) -> Option<Self> {
+        fn unhex(s: &str) -> Option<String> {
+            let bytes = (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+                .collect::<Option<Vec<_>>>()?;
+            String::from_utf8(bytes).ok()
+        }
+        fn float(s: &str) -> Option<f64> {
+            u64::from_str_radix(s, 16).ok().map(f64::from_bits)
+        }
+
+        let mut labels = CaseLabels::default();
+        for item in s.split_terminator(';') {
+            let (tag, rest) = item.split_at(item.len().min(1));
+            match tag {
+                "l" => {
+                    labels.labels.insert(unhex(rest)?);
+                }
+                "r" => {
+                    let (label, min_pct) = rest.split_once('=')?;
+                    labels.coverage.insert(unhex(label)?, float(min_pct)?);
+                }
+                "t" => labels.target = Some(float(rest)?),
+                _ => return None,
+            }
+        }
+        Some(labels)
+    }
+}
+
+#[cfg(feature = "std")]
+impl CaseLabels {
+    fn label(&mut self, label: String) {
+        self.labels.insert(label);
+    }
+
+    fn require(&mut self, label: String, min_pct: f64) {
+        let required = self.coverage.entry(label).or_insert(min_pct);
+        if *required < min_pct {
+            *required = min_pct;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    static CURRENT_CASE: RefCell<Option<CaseLabels>> = RefCell::new(None);
+}
+
+/// Run `f`, returning its result together with all labels that were recorded
+/// on this thread while it ran.
+///
+/// `f` must not unwind.
+#[cfg(feature = "std")]
+pub(crate) fn collect_labels<R>(f: impl FnOnce() -> R) -> (R, CaseLabels) {
+    // Nested runners each get their own set of labels, and the outer one is
+    // restored afterwards.
+    let outer = CURRENT_CASE.with(|c| c.replace(Some(CaseLabels::default())));
+    let result = f();
+    let labels = CURRENT_CASE.with(|c| c.replace(outer)).unwrap_or_default();
+    (result, labels)
+}
+
+#[cfg(feature = "std")]
+fn with_current_case(f: impl FnOnce(&mut CaseLabels)) {
+    CURRENT_CASE.with(|c| {
+        if let Some(ref mut labels) = *c.borrow_mut() {
+            f(labels);
+        }
+    });
+}
+
+/// Attach all labels of `case`, which were recorded on another thread, to the
+/// currently running test case.
+///
+/// Does nothing if no test case is running on this thread.
+#[cfg(feature = "std")]
+pub(crate) fn merge_labels(case: CaseLabels) {
+    with_current_case(|labels| {
+        labels.labels.extend(case.labels);
+        for (label, min_pct) in case.coverage {
+            labels.require(label, min_pct);
+        }
+        if let Some(score) = case.target {
+            if labels.target.map_or(true, |target| target < score) {
+                labels.target = Some(score);
+            }
+        }
+    });
+}
+
+/// Attach `label` to the currently running test case.
+///
+/// Does nothing if no test case is running on this thread.
+#[cfg(feature = "std")]
+pub(crate) fn record_label(label: &dyn fmt::Display) {
+    with_current_case(|labels| labels.label(label.to_string()));
+}
+
+/// Require `label` to be attached to at least `min_pct` percent of all test
+/// cases, and attach it to the current case if `cond` is true.
+///
+/// Does nothing if no test case is running on this thread.
+#[cfg(feature = "std")]
+pub(crate) fn record_coverage(
+    cond: bool,
+    min_pct: f64,
+    label: &dyn fmt::Display,
+) {
+    with_current_case(|labels| {
+        let label = label.to_string();
+        if cond {
+            labels.label(label.clone());
+        }
+        labels.require(label, min_pct);
+    });
+}
+
+/// Report `score` as the target of the currently running test case.
+///
+/// If called several times, the highest score counts. NaN is ignored. Does
+/// nothing if no test case is running on this thread.
+#[cfg(feature = "std")]
+pub(crate) fn record_target(score: f64) {
+    if score.is_nan() {
+        return;
+    }
+
+    with_current_case(|labels| {
+        if labels.target.map_or(true, |target| target < score) {
+            labels.target = Some(score);
+        }
+    });
+}
+
+/// Labels aggregated over all passing test cases of a run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct LabelStatistics {
+    cases: u32,
+    counts: BTreeMap<String, u32>,
+    coverage: BTreeMap<String, f64>,
+}
+
+impl LabelStatistics {
+    /// Account for one more passing test case carrying `case`.
+    pub(crate) fn record(&mut self, case: CaseLabels) {
+        self.cases += 1;
+        for label in case.labels {
+            *self.counts.entry(label).or_insert(0) += 1;
+        }
+        for (label, min_pct) in case.coverage {
+            let required = self.coverage.entry(label).or_insert(min_pct);
+            if *required < min_pct {
+                *required = min_pct;
+            }
+        }
+    }
+
+    /// Whether any labels or coverage requirements have been seen at all.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.counts.is_empty() && self.coverage.is_empty()
+    }
+
+    fn percentage(&self, count: u32) -> f64 {
+        if 0 == self.cases {
+            0.0
+        } else {
+            f64::from(count) * 100.0 / f64::from(self.cases)
+        }
+    }
+
+    /// Check every `prop_cover!` requirement, returning the first one which
+    /// was not met.
+    pub(crate) fn check_coverage(&self) -> Result<(), Reason> {
+        for (label, &min_pct) in &self.coverage {
+            let count = self.counts.get(label).cloned().unwrap_or(0);
+            let actual = self.percentage(count);
+            if actual < min_pct {
+                return Err(format!(
+                    "Insufficient coverage: `{}` occurred in {:.2}% of \
+                     cases ({} of {}), but at least {}% was required",
+                    label, actual, count, self.cases, min_pct
+                )
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for LabelStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut by_count = self
+            .counts
+            .iter()
+            .map(|(label, &count)| (label.to_owned(), count))
+            .chain(
+                self.coverage
+                    .keys()
+                    .filter(|label| !self.counts.contains_key(*label))
+                    .map(|label| (label.to_owned(), 0)),
+            )
+            .collect::<Vec<_>>();
+        // Most frequent first; ties stay in label order.
+        by_count.sort_by(|a, b| b.1.cmp(&a.1));
+
+        writeln!(f, "\tlabels (of {} passing cases):", self.cases)?;
+        for (label, count) in by_count {
+            write!(
+                f,
+                "\t\t{:6.2}% ({}) {}",
+                self.percentage(count),
+                count,
+                label
+            )?;
+            if let Some(min_pct) = self.coverage.get(&label) {
+                write!(f, " (required: {}%)", min_pct)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    fn case(labels: &[&str]) -> CaseLabels {
+        collect_labels(|| {
+            for label in labels {
+                record_label(label);
+            }
+        })
+        .1
+    }
+
+    #[test]
+    fn labels_only_recorded_inside_a_case() {
+        record_label(&"outside");
+        let ((), labels) = collect_labels(|| record_label(&"inside"));
+        assert_eq!(case(&["inside"]), labels);
+    }
+
+    #[test]
+    fn nested_cases_are_independent() {
+        let (inner, outer) = collect_labels(|| {
+            record_label(&"outer");
+            let inner = collect_labels(|| record_label(&"inner")).1;
+            record_label(&"outer again");
+            inner
+        });
+
+        assert_eq!(case(&["inner"]), inner);
+        assert_eq!(case(&["outer", "outer again"]), outer);
+    }
+
+    #[test]
+    fn highest_target_counts() {
+        let ((), labels) = collect_labels(|| {
+            record_target(2.0);
+            record_target(f64::NAN);
+            record_target(5.0);
+            record_target(-1.0);
+        });
+        assert_eq!(Some(5.0), labels.target());
+        assert_eq!(None, case(&["untargeted"]).target());
+    }
+
+    #[test]
+    fn coverage_checked_against_passing_cases() {
+        let mut stats = LabelStatistics::default();
+        for i in 0..10 {
+            let ((), labels) =
+                collect_labels(|| record_coverage(i < 3, 25.0, &"small"));
+            stats.record(labels);
+        }
+        assert!(stats.check_coverage().is_ok());
+
+        let ((), labels) =
+            collect_labels(|| record_coverage(false, 30.0, &"small"));
+        stats.record(labels);
+        assert!(stats.check_coverage().is_err());
+    }
+
+    #[cfg(feature = "fork")]
+    #[test]
+    fn replay_encoding_round_trips() {
+        let ((), labels) = collect_labels(|| {
+            record_label(&"plain");
+            record_coverage(false, 12.5, &"needs; escaping=");
+            record_target(-3.25);
+        });
+
+        let encoded = labels.to_replay();
+        assert!(encoded.chars().all(CaseLabels::is_replay_char));
+        assert_eq!(Some(labels), CaseLabels::from_replay(&encoded));
+        assert_eq!(Some(CaseLabels::default()), CaseLabels::from_replay(""));
+        assert_eq!(None, CaseLabels::from_replay("l6;"));
+    }
+
+    #[test]
+    fn display_sorts_by_frequency() {
+        let mut stats = LabelStatistics::default();
+        stats.record(case(&["rare", "common"]));
+        stats.record(case(&["common"]));
+
+        assert_eq!(
+            "\tlabels (of 2 passing cases):\n\
+             \t\t100.00% (2) common\n\
+             \t\t 50.00% (1) rare\n",
+            stats.to_string()
+        );
+    }
+}
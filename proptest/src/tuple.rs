@@ -47,6 +47,38 @@ macro_rules! tuple {
                 let values = ($(self.$fld.new_tree(runner)?,)*);
                 Ok(TupleValueTree::new(values))
             }
+
+            fn tree_from_value(&self, value: &Self::Value,
+                               runner: &mut TestRunner) -> NewTree<Self> {
+                let values = ($(
+                    self.$fld.tree_from_value(&value.$fld, runner)?,
+                )*);
+                Ok(TupleValueTree::new(values))
+            }
+
+            fn as_enumerable(&self) -> Option<
+                &dyn Enumerable<Tree = Self::Tree, Value = Self::Value>>
+            {
+                if $(self.$fld.as_enumerable().is_some() &&)* true {
+                    Some(self)
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl<$($typ : Strategy),*> Enumerable for ($($typ,)*) {
+            fn domain_size(&self) -> Option<u64> {
+                product_size([$(domain_size(&self.$fld)),*])
+            }
+
+            fn enumerate_tree(&self, mut index: u64, runner: &mut TestRunner)
+                              -> NewTree<Self> {
+                let values = ($(
+                    enumerate_part(&self.$fld, &mut index, runner)?,
+                )*);
+                Ok(TupleValueTree::new(values))
+            }
         }
 
         impl<$($typ : ValueTree),*> ValueTree